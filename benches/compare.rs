@@ -0,0 +1,144 @@
+// Run with `cargo bench`, add `--features parallel` to compare the threaded build.
+// criterion is a native dev-dependency, the wasm target only gets an empty main
+#[cfg(target_arch = "wasm32")]
+fn main() {}
+
+#[cfg(not(target_arch = "wasm32"))]
+criterion::criterion_main!(native::benches);
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::fs;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use criterion::{Criterion, criterion_group};
+    use db_diff::{ColumnInfo, build_schema_map, compare_schema_maps, compare_schemas, create_column_info};
+    use db_diff::index::{IndexInfo, build_index_map, compare_index_maps, compare_indexs, create_index_info};
+    use db_diff::stream::schema_map_from_json;
+
+    // Counts live heap bytes so the large export benchmarks can report their peak next to the timing
+    struct PeakAllocator;
+
+    static CURRENT: AtomicUsize = AtomicUsize::new(0);
+    static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+    unsafe impl GlobalAlloc for PeakAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let pointer = System.alloc(layout);
+            if !pointer.is_null() {
+                grow(layout.size());
+            }
+            pointer
+        }
+
+        unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+            System.dealloc(pointer, layout);
+            CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+        }
+
+        unsafe fn realloc(&self, pointer: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new_pointer = System.realloc(pointer, layout, new_size);
+            if !new_pointer.is_null() {
+                if new_size > layout.size() {
+                    grow(new_size - layout.size());
+                } else {
+                    CURRENT.fetch_sub(layout.size() - new_size, Ordering::Relaxed);
+                }
+            }
+            new_pointer
+        }
+    }
+
+    fn grow(size: usize) {
+        let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: PeakAllocator = PeakAllocator;
+
+    // Heap growth over what was live before f ran, at its highest point
+    fn peak_bytes<T>(f: impl FnOnce() -> T) -> usize {
+        let base = CURRENT.load(Ordering::Relaxed);
+        PEAK.store(base, Ordering::Relaxed);
+        let result = f();
+        let peak = PEAK.load(Ordering::Relaxed) - base;
+        drop(result);
+        peak
+    }
+
+    fn read(path: &str) -> String {
+        fs::read_to_string(path).unwrap_or_else(|e| panic!("Failed to read {}: {}", path, e))
+    }
+
+    // The rows of a sample export repeated for `copies` tables, to get a cluster-sized schema
+    fn scaled_columns(json: &str, copies: usize) -> Vec<ColumnInfo> {
+        let columns: Vec<ColumnInfo> = serde_json::from_str(json).expect("sample columns");
+        (0..copies)
+            .flat_map(|copy| {
+                columns.iter().cloned().map(move |mut column| {
+                    column.table_name = format!("{}_{}", column.table_name, copy);
+                    column
+                })
+            })
+            .collect()
+    }
+
+    fn scaled_indexes(json: &str, copies: usize) -> Vec<IndexInfo> {
+        let indexes = create_index_info(json).expect("sample indexes");
+        (0..copies)
+            .flat_map(|copy| {
+                indexes.iter().cloned().map(move |mut index| {
+                    index.table_name = format!("{}_{}", index.table_name, copy);
+                    index
+                })
+            })
+            .collect()
+    }
+
+    fn sample_files(c: &mut Criterion) {
+        let schema1 = read("sample_schema1.json");
+        let schema2 = read("sample_schema2.json");
+        let index1 = read("sample_index1.json");
+        let index2 = read("sample_index2.json");
+
+        c.bench_function("compare_schemas sample_schema1/2", |b| b.iter(|| compare_schemas(&schema1, &schema2)));
+        c.bench_function("compare_indexs sample_index1/2", |b| b.iter(|| compare_indexs(&index1, &index2)));
+    }
+
+    fn scaled_maps(c: &mut Criterion) {
+        let map1 = build_schema_map(scaled_columns(&read("sample_schema1.json"), 5_000));
+        let map2 = build_schema_map(scaled_columns(&read("sample_schema2.json"), 5_000));
+        c.bench_function("compare_schema_maps 5000 x sample_schema1/2", |b| b.iter(|| compare_schema_maps(&map1, &map2)));
+
+        let index_map1 = build_index_map(scaled_indexes(&read("sample_index1.json"), 200));
+        let index_map2 = build_index_map(scaled_indexes(&read("sample_index2.json"), 200));
+        c.bench_function("compare_index_maps 200 x sample_index1/2", |b| b.iter(|| compare_index_maps(&index_map1, &index_map2)));
+    }
+
+    // 2 x 100k columns as JSON text, read with and without collecting the rows first.
+    // The peak heap of each path is printed once, criterion then times it
+    fn large_exports(c: &mut Criterion) {
+        let json1 = serde_json::to_string(&scaled_columns(&read("sample_schema1.json"), 16_667)).unwrap();
+        let json2 = serde_json::to_string(&scaled_columns(&read("sample_schema2.json"), 12_500)).unwrap();
+        let collected = || (build_schema_map(create_column_info(&json1).unwrap()), build_schema_map(create_column_info(&json2).unwrap()));
+        let streamed = || (schema_map_from_json(&json1).unwrap(), schema_map_from_json(&json2).unwrap());
+
+        for (name, peak) in [
+            ("create_column_info + build_schema_map", peak_bytes(collected)),
+            ("stream::schema_map_from_json", peak_bytes(streamed)),
+            ("compare_schemas", peak_bytes(|| compare_schemas(&json1, &json2))),
+        ] {
+            println!("peak heap 2 x 100k columns, {}: {:.1} MB", name, peak as f64 / 1_048_576.0);
+        }
+
+        let mut group = c.benchmark_group("2 x 100k columns");
+        group.sample_size(10);
+        group.bench_function("create_column_info + build_schema_map", |b| b.iter(collected));
+        group.bench_function("stream::schema_map_from_json", |b| b.iter(streamed));
+        group.bench_function("compare_schemas", |b| b.iter(|| compare_schemas(&json1, &json2)));
+        group.finish();
+    }
+
+    criterion_group!(benches, sample_files, scaled_maps, large_exports);
+}
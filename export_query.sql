@@ -8,7 +8,9 @@ SELECT
     extra,
     column_comment,
     column_default,
-    character_maximum_length
+    character_maximum_length,
+    character_set_name,
+    collation_name
 FROM information_schema.COLUMNS
 WHERE table_schema = 'your_database_name'
 ORDER BY table_name, ordinal_position;
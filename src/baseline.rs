@@ -0,0 +1,266 @@
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use wasm_bindgen::prelude::*;
+use super::SchemaDiff;
+use super::index::IndexDiff;
+use super::output::FORMAT_VERSION;
+use super::report::{DiffReport, ReportFormat, parse_diffs, table_label};
+
+// Kind of a difference, the same names the JUnit report uses as failure types
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    TableOnlyInFirst,
+    TableOnlyInSecond,
+    ColumnOnlyInFirst,
+    ColumnOnlyInSecond,
+    ColumnDefinitionDiffers,
+    IndexOnlyInFirst,
+    IndexOnlyInSecond,
+    IndexDefinitionDiffers,
+    IndexRenamed,
+}
+
+impl ChangeKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ChangeKind::TableOnlyInFirst => "table_only_in_first",
+            ChangeKind::TableOnlyInSecond => "table_only_in_second",
+            ChangeKind::ColumnOnlyInFirst => "column_only_in_first",
+            ChangeKind::ColumnOnlyInSecond => "column_only_in_second",
+            ChangeKind::ColumnDefinitionDiffers => "column_definition_differs",
+            ChangeKind::IndexOnlyInFirst => "index_only_in_first",
+            ChangeKind::IndexOnlyInSecond => "index_only_in_second",
+            ChangeKind::IndexDefinitionDiffers => "index_definition_differs",
+            ChangeKind::IndexRenamed => "index_renamed",
+        }
+    }
+
+    fn is_index(self) -> bool {
+        matches!(
+            self,
+            ChangeKind::IndexOnlyInFirst | ChangeKind::IndexOnlyInSecond | ChangeKind::IndexDefinitionDiffers | ChangeKind::IndexRenamed
+        )
+    }
+}
+
+// An accepted difference. path is schema.table for tables and schema.table.name for columns and
+// indexes (the name in Schema 1 for a renamed index), * matches any run of characters
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct BaselineEntry {
+    pub path: String,
+    pub kind: ChangeKind,
+    /// Why the difference is accepted, shown in reports
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+// Differences known and accepted between two environments, e.g.
+// {"accepted": [{"path": "shop.orders.audit_user", "kind": "column_only_in_second", "reason": "prod audit column"}]}
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+pub struct Baseline {
+    #[serde(default)]
+    pub accepted: Vec<BaselineEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct SuppressedDifference {
+    pub path: String,
+    pub kind: ChangeKind,
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+pub struct BaselineResult {
+    /// Differences removed from the diffs, one per table, column or index, in path order
+    pub suppressed: Vec<SuppressedDifference>,
+    /// Entries that matched no difference, the drift they accepted is gone
+    pub stale: Vec<BaselineEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+pub struct BaselineComparison {
+    pub format_version: u32,
+    /// Differences left after the baseline, only these are new drift
+    pub schema_diff: Option<SchemaDiff>,
+    pub index_diff: Option<IndexDiff>,
+    pub baseline: BaselineResult,
+}
+
+impl Baseline {
+    pub fn parse(json: &str) -> Result<Baseline, String> {
+        if json.trim().is_empty() {
+            return Ok(Baseline::default());
+        }
+        serde_json::from_str(json).map_err(|e| format!("Error parsing baseline: {}", e))
+    }
+
+    // Remove every accepted difference from the diffs. An accepted table also covers its indexes,
+    // which the index diff lists on their own. Index entries are only reported stale when there is
+    // an index diff to match them against
+    pub fn apply(&self, schema_diff: Option<&mut SchemaDiff>, index_diff: Option<&mut IndexDiff>) -> BaselineResult {
+        let mut matched = vec![false; self.accepted.len()];
+        let mut suppressed = Vec::new();
+        let mut accept = |path: String, kind: ChangeKind| -> bool {
+            let entry = self.accepted.iter().position(|entry| entry.kind == kind && path_matches(&entry.path, &path));
+            if let Some(position) = entry {
+                matched[position] = true;
+                suppressed.push(SuppressedDifference { path, kind, reason: self.accepted[position].reason.clone() });
+            }
+            entry.is_some()
+        };
+
+        let mut tables_only_in_first = Vec::new();
+        let mut tables_only_in_second = Vec::new();
+        if let Some(diff) = schema_diff {
+            diff.tables_only_in_first.retain(|table| {
+                let path = table_label(&table.schema, &table.table);
+                let accepted = accept(path.clone(), ChangeKind::TableOnlyInFirst);
+                if accepted {
+                    tables_only_in_first.push(path);
+                }
+                !accepted
+            });
+            diff.tables_only_in_second.retain(|table| {
+                let path = table_label(&table.schema, &table.table);
+                let accepted = accept(path.clone(), ChangeKind::TableOnlyInSecond);
+                if accepted {
+                    tables_only_in_second.push(path);
+                }
+                !accepted
+            });
+            diff.columns_only_in_first.retain(|column| {
+                !accept(column_path(&column.table_schema, &column.table_name, &column.column_name), ChangeKind::ColumnOnlyInFirst)
+            });
+            diff.columns_only_in_second.retain(|column| {
+                !accept(column_path(&column.table_schema, &column.table_name, &column.column_name), ChangeKind::ColumnOnlyInSecond)
+            });
+            diff.columns_with_different_definitions.retain(|difference| {
+                !accept(
+                    column_path(&difference.table_schema, &difference.table_name, &difference.column_name),
+                    ChangeKind::ColumnDefinitionDiffers,
+                )
+            });
+        }
+
+        let has_index_diff = index_diff.is_some();
+        if let Some(diff) = index_diff {
+            // Index diffs hold one entry per indexed column, an index is accepted or not as a whole
+            let mut decided: Vec<(String, ChangeKind, bool)> = Vec::new();
+            let mut decide = |path: String, kind: ChangeKind, covered: bool| -> bool {
+                if let Some((_, _, accepted)) = decided.iter().find(|(seen, seen_kind, _)| *seen == path && *seen_kind == kind) {
+                    return *accepted;
+                }
+                let accepted = covered || accept(path.clone(), kind);
+                decided.push((path, kind, accepted));
+                accepted
+            };
+            diff.indexes_only_in_first.retain(|index| {
+                let covered = tables_only_in_first.contains(&table_label(&index.table_schema, &index.table_name));
+                !decide(column_path(&index.table_schema, &index.table_name, &index.index_name), ChangeKind::IndexOnlyInFirst, covered)
+            });
+            diff.indexes_only_in_second.retain(|index| {
+                let covered = tables_only_in_second.contains(&table_label(&index.table_schema, &index.table_name));
+                !decide(column_path(&index.table_schema, &index.table_name, &index.index_name), ChangeKind::IndexOnlyInSecond, covered)
+            });
+            diff.indexes_with_different_definitions.retain(|difference| {
+                !decide(
+                    column_path(&difference.table_schema, &difference.table_name, &difference.index_name),
+                    ChangeKind::IndexDefinitionDiffers,
+                    false,
+                )
+            });
+            diff.indexes_renamed.retain(|rename| {
+                !accept(column_path(&rename.table_schema, &rename.table_name, &rename.old_index_name), ChangeKind::IndexRenamed)
+            });
+        }
+
+        let stale = self
+            .accepted
+            .iter()
+            .zip(matched)
+            .filter(|(entry, matched)| !matched && (has_index_diff || !entry.kind.is_index()))
+            .map(|(entry, _)| entry.clone())
+            .collect();
+        suppressed.sort_by(|a, b| (&a.path, a.kind).cmp(&(&b.path, b.kind)));
+        BaselineResult { suppressed, stale }
+    }
+}
+
+fn column_path(table_schema: &str, table_name: &str, name: &str) -> String {
+    format!("{}.{}", table_label(table_schema, table_name), name)
+}
+
+// Glob match where * stands for any run of characters, backticks in the pattern are ignored
+fn path_matches(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.replace('`', "");
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(position) => rest = &rest[position + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+// Column and index diffs of both export pairs with the baseline applied. Empty inputs skip that
+// part like generate_report, an empty baseline accepts nothing
+#[wasm_bindgen]
+pub fn apply_baseline(columns_json1: &str, columns_json2: &str, indexes_json1: &str, indexes_json2: &str, baseline_json: &str) -> String {
+    let result = Baseline::parse(baseline_json).and_then(|baseline| {
+        let (mut schema_diff, mut index_diff) = parse_diffs(columns_json1, columns_json2, indexes_json1, indexes_json2)?;
+        let result = baseline.apply(schema_diff.as_mut(), index_diff.as_mut());
+        Ok(BaselineComparison { format_version: FORMAT_VERSION, schema_diff, index_diff, baseline: result })
+    });
+    match result {
+        Ok(comparison) => match serde_json::to_string(&comparison) {
+            Ok(result) => result,
+            Err(e) => format!("Error serializing result: {}", e),
+        },
+        Err(e) => e,
+    }
+}
+
+// generate_report without the accepted differences, which are listed as suppressed together
+// with the stale baseline entries
+#[wasm_bindgen]
+pub fn generate_report_with_baseline(
+    columns_json1: &str,
+    columns_json2: &str,
+    indexes_json1: &str,
+    indexes_json2: &str,
+    format: &str,
+    baseline_json: &str,
+) -> String {
+    let format = match ReportFormat::parse(format) {
+        Ok(format) => format,
+        Err(e) => return e,
+    };
+    let baseline = match Baseline::parse(baseline_json) {
+        Ok(baseline) => baseline,
+        Err(e) => return e,
+    };
+    let (mut schema_diff, mut index_diff) = match parse_diffs(columns_json1, columns_json2, indexes_json1, indexes_json2) {
+        Ok(diffs) => diffs,
+        Err(e) => return e,
+    };
+    let result = baseline.apply(schema_diff.as_mut(), index_diff.as_mut());
+
+    let mut report = DiffReport::new().with_baseline(&result);
+    if let Some(diff) = &schema_diff {
+        report = report.with_schema_diff(diff);
+    }
+    if let Some(diff) = &index_diff {
+        report = report.with_index_diff(diff);
+    }
+    report.render(format)
+}
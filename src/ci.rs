@@ -0,0 +1,419 @@
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+use super::baseline::{Baseline, BaselineResult};
+use super::{SchemaDiff, build_schema_map, create_column_info, format_column_definition, generate_sql_diff};
+use super::index::{IndexDiff, build_index_map, create_index_info, generate_sql_index_diff};
+use super::lint::{LintConfig, LintContext, LintFinding, Linter, Severity};
+use super::naming::NamingConvention;
+use super::report::{group_indexes, parse_diffs, table_label};
+use super::risk::{RISK_RULES, StatementRisk, assess_sql};
+
+// One failed <testcase> of the JUnit report
+struct JunitFailure {
+    classname: String,
+    name: String,
+    kind: &'static str,
+    message: String,
+}
+
+// JUnit XML with one suite per object kind and a failing test case per difference.
+// A suite without differences gets a single passing case so dashboards show it as green
+pub fn render_junit(schema_diff: Option<&SchemaDiff>, index_diff: Option<&IndexDiff>) -> String {
+    render_junit_with_baseline(schema_diff, index_diff, None)
+}
+
+// render_junit of diffs with a baseline applied. Suppressed differences and stale entries are
+// skipped cases of a "baseline" suite, so only new drift fails the build
+pub fn render_junit_with_baseline(schema_diff: Option<&SchemaDiff>, index_diff: Option<&IndexDiff>, baseline: Option<&BaselineResult>) -> String {
+    let mut suites: Vec<(&str, Vec<JunitFailure>)> = Vec::new();
+
+    if let Some(diff) = schema_diff {
+        let mut tables = Vec::new();
+        for (keys, kind, message) in [
+            (&diff.tables_only_in_first, "table_only_in_first", "Table only exists in Schema 1"),
+            (&diff.tables_only_in_second, "table_only_in_second", "Table only exists in Schema 2"),
+        ] {
+            for entry in keys {
+                let table = table_label(&entry.schema, &entry.table);
+                tables.push(JunitFailure {
+                    classname: table.clone(),
+                    name: table,
+                    kind,
+                    message: message.to_string(),
+                });
+            }
+        }
+        suites.push(("tables", tables));
+
+        let mut columns = Vec::new();
+        for (list, kind, message) in [
+            (&diff.columns_only_in_first, "column_only_in_first", "Column only exists in Schema 1"),
+            (&diff.columns_only_in_second, "column_only_in_second", "Column only exists in Schema 2"),
+        ] {
+            for column in list {
+                columns.push(JunitFailure {
+                    classname: table_label(&column.table_schema, &column.table_name),
+                    name: column.column_name.clone(),
+                    kind,
+                    message: message.to_string(),
+                });
+            }
+        }
+        for difference in &diff.columns_with_different_definitions {
+            columns.push(JunitFailure {
+                classname: table_label(&difference.table_schema, &difference.table_name),
+                name: difference.column_name.clone(),
+                kind: "column_definition_differs",
+                message: format!(
+                    "Column definition differs: {} in Schema 1, {} in Schema 2",
+                    format_column_definition(&difference.first),
+                    format_column_definition(&difference.second)
+                ),
+            });
+        }
+        suites.push(("columns", columns));
+    }
+
+    if let Some(diff) = index_diff {
+        let mut indexes = Vec::new();
+        for (list, kind, message) in [
+            (&diff.indexes_only_in_first, "index_only_in_first", "Index only exists in Schema 1"),
+            (&diff.indexes_only_in_second, "index_only_in_second", "Index only exists in Schema 2"),
+        ] {
+            for (table_schema, table_name, index_name) in group_indexes(list).into_keys() {
+                indexes.push(JunitFailure {
+                    classname: table_label(table_schema, table_name),
+                    name: index_name.to_string(),
+                    kind,
+                    message: message.to_string(),
+                });
+            }
+        }
+        // One case per index even when several of its columns differ
+        let mut modified: BTreeMap<(String, String), Vec<String>> = BTreeMap::new();
+        for difference in &diff.indexes_with_different_definitions {
+            modified
+                .entry((table_label(&difference.table_schema, &difference.table_name), difference.index_name.clone()))
+                .or_default()
+                .push(difference.first.column_name.clone());
+        }
+        for ((classname, name), columns) in modified {
+            indexes.push(JunitFailure {
+                classname,
+                name,
+                kind: "index_definition_differs",
+                message: format!("Index definition differs on {}", columns.join(", ")),
+            });
+        }
+        for rename in &diff.indexes_renamed {
+            indexes.push(JunitFailure {
+                classname: table_label(&rename.table_schema, &rename.table_name),
+                name: rename.old_index_name.clone(),
+                kind: "index_renamed",
+                message: format!("Index is named {} in Schema 2", rename.new_index_name),
+            });
+        }
+        suites.push(("indexes", indexes));
+    }
+
+    let mut skipped = Vec::new();
+    if let Some(result) = baseline {
+        for suppressed in &result.suppressed {
+            skipped.push(JunitFailure {
+                classname: "baseline".to_string(),
+                name: suppressed.path.clone(),
+                kind: suppressed.kind.as_str(),
+                message: match &suppressed.reason {
+                    Some(reason) => format!("Accepted by baseline: {}", reason),
+                    None => "Accepted by baseline".to_string(),
+                },
+            });
+        }
+        for entry in &result.stale {
+            skipped.push(JunitFailure {
+                classname: "baseline".to_string(),
+                name: entry.path.clone(),
+                kind: entry.kind.as_str(),
+                message: "Stale baseline entry, the difference no longer exists".to_string(),
+            });
+        }
+    }
+
+    let total_failures: usize = suites.iter().map(|(_, failures)| failures.len()).sum();
+    let total_tests: usize = suites.iter().map(|(_, failures)| failures.len().max(1)).sum::<usize>() + skipped.len();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    if baseline.is_some() {
+        xml.push_str(&format!(
+            "<testsuites name=\"schema drift\" tests=\"{}\" failures=\"{}\" skipped=\"{}\">\n",
+            total_tests,
+            total_failures,
+            skipped.len()
+        ));
+    } else {
+        xml.push_str(&format!(
+            "<testsuites name=\"schema drift\" tests=\"{}\" failures=\"{}\">\n",
+            total_tests, total_failures
+        ));
+    }
+    for (suite, mut failures) in suites {
+        failures.sort_by(|a, b| (&a.classname, &a.name, a.kind).cmp(&(&b.classname, &b.name, b.kind)));
+        xml.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\">\n",
+            suite,
+            failures.len().max(1),
+            failures.len()
+        ));
+        if failures.is_empty() {
+            xml.push_str(&format!("    <testcase classname=\"{}\" name=\"no drift\"/>\n", suite));
+        }
+        for failure in failures {
+            xml.push_str(&format!(
+                "    <testcase classname=\"{}\" name=\"{}\">\n      <failure type=\"{}\" message=\"{}\"/>\n    </testcase>\n",
+                escape_xml(&failure.classname),
+                escape_xml(&failure.name),
+                failure.kind,
+                escape_xml(&failure.message)
+            ));
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    if !skipped.is_empty() {
+        xml.push_str(&format!(
+            "  <testsuite name=\"baseline\" tests=\"{}\" failures=\"0\" skipped=\"{}\">\n",
+            skipped.len(),
+            skipped.len()
+        ));
+        for case in skipped {
+            xml.push_str(&format!(
+                "    <testcase classname=\"{}\" name=\"{}\">\n      <skipped type=\"{}\" message=\"{}\"/>\n    </testcase>\n",
+                case.classname,
+                escape_xml(&case.name),
+                case.kind,
+                escape_xml(&case.message)
+            ));
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+// SARIF 2.1.0 log with lint findings and statement risks as results.
+// There are no source files behind an export, objects are reported as logical locations
+pub struct SarifLog {
+    rules: BTreeMap<String, (String, Severity)>,
+    results: Vec<Value>,
+    notifications: Vec<Value>,
+}
+
+impl SarifLog {
+    pub fn new() -> Self {
+        SarifLog {
+            rules: BTreeMap::new(),
+            results: Vec::new(),
+            notifications: Vec::new(),
+        }
+    }
+
+    pub fn with_lint_findings(mut self, linter: &Linter, findings: &[LintFinding]) -> Self {
+        for finding in findings {
+            let description = linter
+                .rules()
+                .iter()
+                .find(|rule| rule.id() == finding.rule_id)
+                .map(|rule| rule.description().to_string())
+                .unwrap_or_default();
+            self.rules
+                .entry(finding.rule_id.clone())
+                .or_insert((description, finding.severity));
+
+            let mut name = table_label(&finding.table_schema, &finding.table_name);
+            if let Some(object) = &finding.object {
+                name = format!("{}.{}", name, object);
+            }
+            self.results.push(json!({
+                "ruleId": finding.rule_id,
+                "level": sarif_level(finding.severity),
+                "message": { "text": finding.message },
+                "locations": [logical_location(&name, if finding.object.is_some() { "member" } else { "type" })]
+            }));
+        }
+        self
+    }
+
+    pub fn with_risks(mut self, risks: &[StatementRisk]) -> Self {
+        for risk in risks {
+            let description = RISK_RULES
+                .iter()
+                .find(|(id, _, _)| *id == risk.rule_id)
+                .map(|(_, _, description)| description.to_string())
+                .unwrap_or_default();
+            self.rules
+                .entry(risk.rule_id.clone())
+                .or_insert((description, risk.severity));
+
+            self.results.push(json!({
+                "ruleId": risk.rule_id,
+                "level": sarif_level(risk.severity),
+                "message": { "text": format!("{}: {}", risk.message, risk.statement) },
+                "locations": [logical_location(&risk.table.replace('`', ""), "type")]
+            }));
+        }
+        self
+    }
+
+    // Problems of the run itself, e.g. a generated statement that could not be assessed
+    pub fn with_notifications(mut self, messages: &[String]) -> Self {
+        self.notifications.extend(messages.iter().map(|message| json!({
+            "level": "warning",
+            "message": { "text": message }
+        })));
+        self
+    }
+
+    pub fn to_value(&self) -> Value {
+        let rules: Vec<Value> = self
+            .rules
+            .iter()
+            .map(|(id, (description, severity))| json!({
+                "id": id,
+                "shortDescription": { "text": description },
+                "defaultConfiguration": { "level": sarif_level(*severity) }
+            }))
+            .collect();
+
+        json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "db_diff",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "rules": rules
+                    }
+                },
+                "invocations": [{
+                    "executionSuccessful": true,
+                    "toolExecutionNotifications": self.notifications
+                }],
+                "results": self.results
+            }]
+        })
+    }
+}
+
+impl Default for SarifLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen]
+pub fn junit_report(columns_json1: &str, columns_json2: &str, indexes_json1: &str, indexes_json2: &str) -> String {
+    match parse_diffs(columns_json1, columns_json2, indexes_json1, indexes_json2) {
+        Ok((schema_diff, index_diff)) => render_junit(schema_diff.as_ref(), index_diff.as_ref()),
+        Err(e) => e,
+    }
+}
+
+#[wasm_bindgen]
+pub fn junit_report_with_baseline(columns_json1: &str, columns_json2: &str, indexes_json1: &str, indexes_json2: &str, baseline_json: &str) -> String {
+    let baseline = match Baseline::parse(baseline_json) {
+        Ok(baseline) => baseline,
+        Err(e) => return e,
+    };
+    match parse_diffs(columns_json1, columns_json2, indexes_json1, indexes_json2) {
+        Ok((mut schema_diff, mut index_diff)) => {
+            let result = baseline.apply(schema_diff.as_mut(), index_diff.as_mut());
+            render_junit_with_baseline(schema_diff.as_ref(), index_diff.as_ref(), Some(&result))
+        }
+        Err(e) => e,
+    }
+}
+
+// Lint findings of the target (second) export plus the risk of each forward statement
+#[wasm_bindgen]
+pub fn sarif_report(columns_json1: &str, columns_json2: &str, indexes_json1: &str, indexes_json2: &str, config_json: &str) -> String {
+    let (schema_diff, index_diff) = match parse_diffs(columns_json1, columns_json2, indexes_json1, indexes_json2) {
+        Ok(diffs) => diffs,
+        Err(e) => return e,
+    };
+
+    let config = if config_json.trim().is_empty() {
+        LintConfig::default()
+    } else {
+        match serde_json::from_str::<LintConfig>(config_json) {
+            Ok(config) => config,
+            Err(e) => return format!("Error parsing lint config: {}", e),
+        }
+    };
+
+    let mut linter = Linter::new();
+    if let Some(naming) = &config.naming {
+        match NamingConvention::new(naming) {
+            Ok(rule) => linter = linter.with_rule(Box::new(rule)),
+            Err(e) => return e,
+        }
+    }
+    let linter = linter.with_config(config);
+
+    let columns = if columns_json2.trim().is_empty() {
+        vec![]
+    } else {
+        match create_column_info(columns_json2) {
+            Ok(column) => column,
+            Err(e) => return e,
+        }
+    };
+    let indexes = if indexes_json2.trim().is_empty() {
+        vec![]
+    } else {
+        match create_index_info(indexes_json2) {
+            Ok(indexes) => indexes,
+            Err(e) => return e,
+        }
+    };
+    let schema_map = build_schema_map(columns);
+    let index_map = build_index_map(indexes);
+    let findings = linter.run(&LintContext {
+        schema_map: &schema_map,
+        index_map: &index_map,
+    });
+
+    let mut sql_statements = schema_diff.as_ref().map(generate_sql_diff).unwrap_or_default();
+    if let Some(diff) = &index_diff {
+        sql_statements.extend(generate_sql_index_diff(diff));
+    }
+    let (risks, errors) = assess_sql(&sql_statements);
+
+    let log = SarifLog::new().with_lint_findings(&linter, &findings).with_risks(&risks).with_notifications(&errors);
+    match serde_json::to_string(&log.to_value()) {
+        Ok(result) => result,
+        Err(e) => format!("Error serializing result: {}", e),
+    }
+}
+
+fn sarif_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info => "note",
+    }
+}
+
+fn logical_location(name: &str, kind: &str) -> Value {
+    json!({
+        "logicalLocations": [{ "fullyQualifiedName": name, "kind": kind }]
+    })
+}
+
+pub(crate) fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
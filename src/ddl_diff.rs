@@ -0,0 +1,223 @@
+use std::collections::{BTreeSet, HashMap};
+use indexmap::IndexMap;
+use wasm_bindgen::prelude::*;
+use super::{ColumnInfo, build_schema_map, create_column_info, format_column_definition};
+use super::index::{IndexInfo, build_index_map, create_index_info};
+
+pub const DEFAULT_CONTEXT_LINES: usize = 3;
+
+// Canonical CREATE TABLE of one side: columns in export order, then PRIMARY KEY and the
+// other indexes sorted by name. Returns no lines when the table does not exist on that side
+pub fn table_ddl(
+    table_key: &str,
+    columns: Option<&IndexMap<String, ColumnInfo>>,
+    indexes: Option<&IndexMap<String, Vec<IndexInfo>>>
+) -> Vec<String> {
+    let mut definitions: Vec<String> = columns
+        .map(|columns| columns.values().map(format_column_definition).collect())
+        .unwrap_or_default();
+
+    if let Some(indexes) = indexes {
+        let mut names: Vec<&String> = indexes.keys().collect();
+        names.sort_by_key(|name| (name.as_str() != "PRIMARY", name.as_str()));
+        for name in names {
+            definitions.push(index_definition(name, &indexes[name]));
+        }
+    }
+
+    if definitions.is_empty() {
+        return vec![];
+    }
+
+    let last = definitions.len() - 1;
+    let mut lines = vec![format!("CREATE TABLE {} (", table_key)];
+    for (position, definition) in definitions.into_iter().enumerate() {
+        let separator = if position == last { "" } else { "," };
+        lines.push(format!("  {}{}", definition, separator));
+    }
+    lines.push(");".to_string());
+    lines
+}
+
+// Unified diff of every table that differs, tables in key order.
+// Either pair of maps may be empty to only diff columns or only indexes
+pub fn unified_schema_diff(
+    schema_map1: &HashMap<String, IndexMap<String, ColumnInfo>>,
+    schema_map2: &HashMap<String, IndexMap<String, ColumnInfo>>,
+    index_map1: &HashMap<String, IndexMap<String, Vec<IndexInfo>>>,
+    index_map2: &HashMap<String, IndexMap<String, Vec<IndexInfo>>>,
+    context: usize
+) -> String {
+    let tables: BTreeSet<&String> = schema_map1
+        .keys()
+        .chain(schema_map2.keys())
+        .chain(index_map1.keys())
+        .chain(index_map2.keys())
+        .collect();
+
+    let mut out = String::new();
+    for table_key in tables {
+        out.push_str(&table_diff(table_key, schema_map1, schema_map2, index_map1, index_map2, context));
+    }
+    out
+}
+
+// Unified diff of a single table, a/ and b/ named after the table or /dev/null on the missing side
+pub(crate) fn table_diff(
+    table_key: &str,
+    schema_map1: &HashMap<String, IndexMap<String, ColumnInfo>>,
+    schema_map2: &HashMap<String, IndexMap<String, ColumnInfo>>,
+    index_map1: &HashMap<String, IndexMap<String, Vec<IndexInfo>>>,
+    index_map2: &HashMap<String, IndexMap<String, Vec<IndexInfo>>>,
+    context: usize
+) -> String {
+    let old = table_ddl(table_key, schema_map1.get(table_key), index_map1.get(table_key));
+    let new = table_ddl(table_key, schema_map2.get(table_key), index_map2.get(table_key));
+    let name = table_key.replace('`', "");
+    let old_name = if old.is_empty() { "/dev/null".to_string() } else { format!("a/{}", name) };
+    let new_name = if new.is_empty() { "/dev/null".to_string() } else { format!("b/{}", name) };
+    unified_diff(&old, &new, &old_name, &new_name, context)
+}
+
+// Classic ---/+++ diff of two line lists, empty when they are equal
+pub fn unified_diff(old: &[String], new: &[String], old_name: &str, new_name: &str, context: usize) -> String {
+    let ops = diff_lines(old, new);
+    let changes: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, (kind, _))| *kind != ' ')
+        .map(|(position, _)| position)
+        .collect();
+    if changes.is_empty() {
+        return String::new();
+    }
+
+    // Group changes whose context overlaps into the same hunk
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for position in changes {
+        let start = position.saturating_sub(context);
+        let end = (position + context + 1).min(ops.len());
+        match hunks.last_mut() {
+            Some((_, last_end)) if start <= *last_end => *last_end = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut out = format!("--- {}\n+++ {}\n", old_name, new_name);
+    for (start, end) in hunks {
+        let old_before = ops[..start].iter().filter(|(kind, _)| *kind != '+').count();
+        let new_before = ops[..start].iter().filter(|(kind, _)| *kind != '-').count();
+        let old_count = ops[start..end].iter().filter(|(kind, _)| *kind != '+').count();
+        let new_count = ops[start..end].iter().filter(|(kind, _)| *kind != '-').count();
+        out.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(old_before, old_count),
+            hunk_range(new_before, new_count)
+        ));
+        for (kind, line) in &ops[start..end] {
+            out.push_str(&format!("{}{}\n", kind, line));
+        }
+    }
+    out
+}
+
+// Empty inputs skip that pair, so only columns or only indexes can be diffed
+#[wasm_bindgen]
+pub fn unified_ddl_diff(columns_json1: &str, columns_json2: &str, indexes_json1: &str, indexes_json2: &str, context_lines: u32) -> String {
+    let columns1 = match parse_columns(columns_json1) {
+        Ok(columns) => build_schema_map(columns),
+        Err(e) => return e,
+    };
+    let columns2 = match parse_columns(columns_json2) {
+        Ok(columns) => build_schema_map(columns),
+        Err(e) => return e,
+    };
+    let indexes1 = match parse_indexes(indexes_json1) {
+        Ok(indexes) => build_index_map(indexes),
+        Err(e) => return e,
+    };
+    let indexes2 = match parse_indexes(indexes_json2) {
+        Ok(indexes) => build_index_map(indexes),
+        Err(e) => return e,
+    };
+
+    unified_schema_diff(&columns1, &columns2, &indexes1, &indexes2, context_lines as usize)
+}
+
+pub(crate) fn parse_columns(json: &str) -> Result<Vec<ColumnInfo>, String> {
+    if json.trim().is_empty() {
+        Ok(vec![])
+    } else {
+        create_column_info(json)
+    }
+}
+
+pub(crate) fn parse_indexes(json: &str) -> Result<Vec<IndexInfo>, String> {
+    if json.trim().is_empty() {
+        Ok(vec![])
+    } else {
+        create_index_info(json)
+    }
+}
+
+fn index_definition(name: &str, index_list: &[IndexInfo]) -> String {
+    let mut sorted: Vec<&IndexInfo> = index_list.iter().collect();
+    sorted.sort_by_key(|index| index.seq_in_index);
+    let columns: Vec<String> = sorted
+        .iter()
+        .map(|index| match index.sub_part {
+            Some(sub_part) => format!("{}({})", index.column_name, sub_part),
+            None => index.column_name.clone(),
+        })
+        .collect();
+    let index_type = sorted.first().map(|index| index.index_type.as_str()).unwrap_or_default();
+
+    if name == "PRIMARY" {
+        format!("PRIMARY KEY ({}) USING {}", columns.join(", "), index_type)
+    } else if sorted.iter().all(|index| index.non_unique == Some(0)) {
+        format!("UNIQUE KEY `{}` ({}) USING {}", name, columns.join(", "), index_type)
+    } else {
+        format!("KEY `{}` ({}) USING {}", name, columns.join(", "), index_type)
+    }
+}
+
+// Line edit script from the longest common subsequence: ' ' keeps, '-' removes, '+' adds
+fn diff_lines<'a>(old: &'a [String], new: &'a [String]) -> Vec<(char, &'a str)> {
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            ops.push((' ', old[i].as_str()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            ops.push(('-', old[i].as_str()));
+            i += 1;
+        } else {
+            ops.push(('+', new[j].as_str()));
+            j += 1;
+        }
+    }
+    ops.extend(old[i..].iter().map(|line| ('-', line.as_str())));
+    ops.extend(new[j..].iter().map(|line| ('+', line.as_str())));
+    ops
+}
+
+fn hunk_range(before: usize, count: usize) -> String {
+    match count {
+        0 => format!("{},0", before),
+        1 => format!("{}", before + 1),
+        _ => format!("{},{}", before + 1, count),
+    }
+}
@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use wasm_bindgen::prelude::*;
+use web_sys::{File, FileReader};
+use js_sys::{Function, Promise};
+use super::input::{Compression, decode_upload};
+use super::job::{Phase, Progress, report_progress};
+use super::js::{db_diff_error, to_js_value};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DetectedBy {
+    // Encoding given by the caller
+    Override,
+    // UTF-8, UTF-16LE or UTF-16BE byte order mark
+    Bom,
+    // No BOM, but the bytes are valid UTF-8
+    Utf8,
+    // Guessed from the byte distribution, see guess_encoding
+    Heuristic,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DecodedText {
+    pub text: String,
+    // WHATWG name of the encoding used, e.g. "UTF-8", "windows-874"
+    pub encoding: String,
+    pub detected_by: DetectedBy,
+    // True when malformed sequences were replaced with U+FFFD
+    pub had_replacements: bool,
+    #[serde(default)]
+    pub compression: Compression,
+    // Names of the files read, the entries of a zip archive or the member of a gzip file
+    #[serde(default)]
+    pub files: Vec<String>,
+}
+
+// Decode an uploaded file. The override is any encoding_rs label ("latin1", "utf-16", "tis-620", ...),
+// otherwise a BOM wins, then BOM-less UTF-16, UTF-8 and finally the chardetng guess
+pub fn decode_bytes(bytes: &[u8], encoding_override: Option<&str>) -> Result<DecodedText, String> {
+    let (encoding, detected_by, bom_length) = match encoding_override.map(str::trim).filter(|label| !label.is_empty()) {
+        Some(label) => {
+            let encoding = Encoding::for_label(label.as_bytes()).ok_or_else(|| format!("Unknown encoding: {}", label))?;
+            // A BOM of the requested encoding is still stripped
+            let bom_length = match Encoding::for_bom(bytes) {
+                Some((bom_encoding, length)) if bom_encoding == encoding => length,
+                _ => 0,
+            };
+            (encoding, DetectedBy::Override, bom_length)
+        }
+        None => match Encoding::for_bom(bytes) {
+            Some((encoding, length)) => (encoding, DetectedBy::Bom, length),
+            // ASCII in UTF-16 is valid UTF-8 as well, so this has to be checked first
+            None => match utf16_without_bom(bytes) {
+                Some(encoding) => (encoding, DetectedBy::Heuristic, 0),
+                None if std::str::from_utf8(bytes).is_ok() => (UTF_8, DetectedBy::Utf8, 0),
+                None => (guess_encoding(bytes), DetectedBy::Heuristic, 0),
+            },
+        },
+    };
+
+    let (text, had_replacements) = encoding.decode_without_bom_handling(&bytes[bom_length..]);
+    Ok(DecodedText {
+        text: text.into_owned(),
+        encoding: encoding.name().to_string(),
+        detected_by,
+        had_replacements,
+        compression: Compression::None,
+        files: Vec::new(),
+    })
+}
+
+// Best guess for bytes without a BOM that are not UTF-8
+pub fn guess_encoding(bytes: &[u8]) -> &'static Encoding {
+    if let Some(encoding) = utf16_without_bom(bytes) {
+        return encoding;
+    }
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    detector.guess(None, false)
+}
+
+// JSON exports are mostly ASCII, so UTF-16 without a BOM shows up as every other byte being zero
+fn utf16_without_bom(bytes: &[u8]) -> Option<&'static Encoding> {
+    let pairs = bytes.len() / 2;
+    let zeros_high = bytes.chunks_exact(2).filter(|pair| pair[1] == 0 && pair[0] != 0).count();
+    let zeros_low = bytes.chunks_exact(2).filter(|pair| pair[0] == 0 && pair[1] != 0).count();
+    if pairs > 0 && zeros_high * 2 > pairs {
+        Some(UTF_16LE)
+    } else if pairs > 0 && zeros_low * 2 > pairs {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+// Same as process_uploaded_file, resolving to a DecodedText object instead of a bare string.
+// Rejects with a DbDiffError of kind "encoding" for an unknown override label and "input"
+// for an unreadable archive or SQL dump
+#[wasm_bindgen(unchecked_return_type = "Promise<DecodedText>")]
+pub fn decode_uploaded_file(
+    file: File,
+    encoding: Option<String>,
+    #[wasm_bindgen(unchecked_param_type = "((progress: Progress) => void) | undefined")] on_progress: Option<Function>,
+) -> Promise {
+    read_uploaded_file(file, encoding, on_progress, |decoded| to_js_value(&decoded))
+}
+
+// Read a File as bytes, unpack and decode it and settle the promise with whatever on_decoded returns.
+// on_progress gets a "read" Progress in bytes for every ProgressEvent of the FileReader
+pub(crate) fn read_uploaded_file<F>(file: File, encoding: Option<String>, on_progress: Option<Function>, on_decoded: F) -> Promise
+where
+    F: Fn(DecodedText) -> Result<JsValue, JsValue> + 'static,
+{
+    let file_reader = FileReader::new().unwrap();
+    let mut on_decoded = Some(on_decoded);
+    let mut encoding = Some(encoding);
+
+    // Create a promise to handle the async operation
+    let promise = js_sys::Promise::new(&mut |resolve, reject| {
+        let file_reader_clone = file_reader.clone();
+        let on_decoded = on_decoded.take().unwrap();
+        let encoding = encoding.take().flatten();
+        let name = file.name();
+        let on_progress_clone = on_progress.clone();
+        let onloaded_closure = Closure::wrap(Box::new(move |event: web_sys::ProgressEvent| {
+            // A failing progress callback must not keep the promise from settling
+            let _ = report_progress(on_progress_clone.as_ref(), &read_progress(&event));
+            let result = file_reader_clone.result().unwrap();
+            let array_buffer = result.dyn_into::<js_sys::ArrayBuffer>().unwrap();
+            let data = js_sys::Uint8Array::new(&array_buffer).to_vec();
+
+            let settled = decode_upload(&name, &data, encoding.as_deref())
+                .map_err(|e| db_diff_error(if e.starts_with("Unknown encoding") { "encoding" } else { "input" }, &e))
+                .and_then(&on_decoded);
+            match settled {
+                Ok(value) => resolve.call1(&JsValue::NULL, &value).unwrap(),
+                Err(error) => reject.call1(&JsValue::NULL, &error).unwrap(),
+            };
+        }) as Box<dyn FnMut(web_sys::ProgressEvent)>);
+
+        file_reader.set_onloadend(Some(onloaded_closure.as_ref().unchecked_ref()));
+        onloaded_closure.forget();
+
+        if let Some(on_progress) = on_progress.clone() {
+            let onprogress_closure = Closure::wrap(Box::new(move |event: web_sys::ProgressEvent| {
+                let _ = report_progress(Some(&on_progress), &read_progress(&event));
+            }) as Box<dyn FnMut(web_sys::ProgressEvent)>);
+            file_reader.set_onprogress(Some(onprogress_closure.as_ref().unchecked_ref()));
+            onprogress_closure.forget();
+        }
+    });
+
+    file_reader.read_as_array_buffer(&file).unwrap();
+    promise
+}
+
+fn read_progress(event: &web_sys::ProgressEvent) -> Progress {
+    let total = if event.length_computable() { event.total() as u64 } else { 0 };
+    Progress { phase: Phase::Read, done: event.loaded() as u64, total }
+}
@@ -0,0 +1,368 @@
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use indexmap::IndexMap;
+use wasm_bindgen::prelude::*;
+use super::{ColumnDifference, ColumnInfo, build_schema_map, compare_schema_maps, format_column_definition};
+use super::ddl_diff::{DEFAULT_CONTEXT_LINES, parse_columns, parse_indexes, table_diff};
+use super::index::{IndexDifference, IndexInfo, IndexRename, build_index_map, compare_index_maps};
+use super::model::{SchemaModel, verify_script};
+use super::output::FORMAT_VERSION;
+
+type SchemaMap = HashMap<String, IndexMap<String, ColumnInfo>>;
+type IndexMapByTable = HashMap<String, IndexMap<String, Vec<IndexInfo>>>;
+
+// Phases of the combined script. Indexes are dropped before the columns they cover and
+// created after the columns they need, new tables get their indexes right after CREATE TABLE
+const DROP_INDEX: usize = 0;
+const RENAME_INDEX: usize = 1;
+const DROP_TABLE: usize = 2;
+const CREATE_TABLE: usize = 3;
+const DROP_COLUMN: usize = 4;
+const ADD_COLUMN: usize = 5;
+const MODIFY_COLUMN: usize = 6;
+const ADD_INDEX: usize = 7;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TableStatus {
+    OnlyInFirst,
+    OnlyInSecond,
+    Changed,
+}
+
+// Everything that differs for one table, with the statements of the combined scripts that touch it
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+pub struct TableComparison {
+    pub schema: String,
+    pub table: String,
+    pub status: TableStatus,
+    pub columns_only_in_first: Vec<ColumnInfo>,
+    pub columns_only_in_second: Vec<ColumnInfo>,
+    pub columns_with_different_definitions: Vec<ColumnDifference>,
+    pub indexes_only_in_first: Vec<IndexInfo>,
+    pub indexes_only_in_second: Vec<IndexInfo>,
+    pub indexes_with_different_definitions: Vec<IndexDifference>,
+    pub indexes_renamed: Vec<IndexRename>,
+    /// Unified diff of the canonical CREATE TABLE of both sides, see unified_ddl_diff
+    pub ddl_diff: String,
+    /// Statements of forward_script for this table, in script order
+    pub forward_sql: Vec<String>,
+    /// Statements of reverse_script for this table, in script order
+    pub reverse_sql: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+pub struct FullComparison {
+    pub format_version: u32,
+    /// Every table with a column or index difference, in `schema`.table order
+    pub tables: Vec<TableComparison>,
+    /// Statements that turn schema 1 into schema 2, one per line in executable order
+    pub forward_script: String,
+    /// Statements that turn schema 2 back into schema 1, one per line in executable order
+    pub reverse_script: String,
+    /// Differences left after applying the scripts to an in-memory model, and index rows left out
+    /// because their table has no columns on that side. Empty when both scripts are complete
+    pub verification_errors: Vec<String>,
+}
+
+// Columns and indexes of both exports compared in one go. Empty inputs are allowed,
+// e.g. to compare indexes only
+#[wasm_bindgen]
+pub fn compare_full(columns_json1: &str, columns_json2: &str, indexes_json1: &str, indexes_json2: &str) -> String {
+    let columns1 = match parse_columns(columns_json1) {
+        Ok(columns) => columns,
+        Err(e) => return e,
+    };
+    let columns2 = match parse_columns(columns_json2) {
+        Ok(columns) => columns,
+        Err(e) => return e,
+    };
+    let indexes1 = match parse_indexes(indexes_json1) {
+        Ok(indexes) => indexes,
+        Err(e) => return e,
+    };
+    let indexes2 = match parse_indexes(indexes_json2) {
+        Ok(indexes) => indexes,
+        Err(e) => return e,
+    };
+
+    match serde_json::to_string(&compare_columns_and_indexes(columns1, columns2, indexes1, indexes2)) {
+        Ok(result) => result,
+        Err(e) => format!("Error serializing result: {}", e),
+    }
+}
+
+// Diff, combined scripts and self-check of both export pairs, shared by the wasm exports
+pub fn compare_columns_and_indexes(
+    columns1: Vec<ColumnInfo>,
+    columns2: Vec<ColumnInfo>,
+    indexes1: Vec<IndexInfo>,
+    indexes2: Vec<IndexInfo>
+) -> FullComparison {
+    let mut input_errors = Vec::new();
+    let indexes1 = indexes_with_columns(indexes1, &columns1, &columns2, "first", &mut input_errors);
+    let indexes2 = indexes_with_columns(indexes2, &columns2, &columns1, "second", &mut input_errors);
+    let model1 = SchemaModel::from_columns(&columns1).with_indexes(&indexes1);
+    let model2 = SchemaModel::from_columns(&columns2).with_indexes(&indexes2);
+
+    let schema_map1 = build_schema_map(columns1);
+    let schema_map2 = build_schema_map(columns2);
+    let index_map1 = build_index_map(indexes1);
+    let index_map2 = build_index_map(indexes2);
+
+    // Bucket the diffs per table, both scripts and the per-table statements are planned from them
+    let mut tables: BTreeMap<String, TableComparison> = BTreeMap::new();
+    let schema_diff = compare_schema_maps(&schema_map1, &schema_map2);
+    let index_diff = compare_index_maps(&index_map1, &index_map2);
+    for column in schema_diff.columns_only_in_first {
+        table_entry(&mut tables, &column.table_schema, &column.table_name).columns_only_in_first.push(column);
+    }
+    for column in schema_diff.columns_only_in_second {
+        table_entry(&mut tables, &column.table_schema, &column.table_name).columns_only_in_second.push(column);
+    }
+    for difference in schema_diff.columns_with_different_definitions {
+        table_entry(&mut tables, &difference.table_schema, &difference.table_name)
+            .columns_with_different_definitions
+            .push(difference);
+    }
+    for index in index_diff.indexes_only_in_first {
+        table_entry(&mut tables, &index.table_schema, &index.table_name).indexes_only_in_first.push(index);
+    }
+    for index in index_diff.indexes_only_in_second {
+        table_entry(&mut tables, &index.table_schema, &index.table_name).indexes_only_in_second.push(index);
+    }
+    for difference in index_diff.indexes_with_different_definitions {
+        table_entry(&mut tables, &difference.table_schema, &difference.table_name)
+            .indexes_with_different_definitions
+            .push(difference);
+    }
+    for rename in index_diff.indexes_renamed {
+        table_entry(&mut tables, &rename.table_schema, &rename.table_name).indexes_renamed.push(rename);
+    }
+    for table in schema_diff.tables_only_in_first.iter().chain(&schema_diff.tables_only_in_second) {
+        table_entry(&mut tables, &table.schema, &table.table);
+    }
+
+    let forward = plan_statements(&tables, &schema_map1, &schema_map2, &index_map2, false);
+    let reverse = plan_statements(&tables, &schema_map2, &schema_map1, &index_map1, true);
+    let forward_script = join_statements(&forward);
+    let reverse_script = join_statements(&reverse);
+
+    let mut verification_errors = input_errors;
+    verification_errors.extend(verify_script("forward", &model1, &model2, &forward_script));
+    verification_errors.extend(verify_script("reverse", &model2, &model1, &reverse_script));
+
+    let mut forward_sql = statements_by_table(forward);
+    let mut reverse_sql = statements_by_table(reverse);
+    for (table_key, table) in tables.iter_mut() {
+        let in_first = schema_map1.contains_key(table_key) || index_map1.contains_key(table_key);
+        let in_second = schema_map2.contains_key(table_key) || index_map2.contains_key(table_key);
+        table.status = match (in_first, in_second) {
+            (true, false) => TableStatus::OnlyInFirst,
+            (false, true) => TableStatus::OnlyInSecond,
+            _ => TableStatus::Changed,
+        };
+        table.ddl_diff = table_diff(table_key, &schema_map1, &schema_map2, &index_map1, &index_map2, DEFAULT_CONTEXT_LINES);
+        table.forward_sql = forward_sql.remove(table_key).unwrap_or_default();
+        table.reverse_sql = reverse_sql.remove(table_key).unwrap_or_default();
+    }
+
+    FullComparison {
+        format_version: FORMAT_VERSION,
+        tables: tables.into_values().collect(),
+        forward_script,
+        reverse_script,
+        verification_errors,
+    }
+}
+
+// Index rows whose table has no columns on their side but has some on the other cannot be created or
+// dropped with the table, they are left out with one error per table. An export without any columns
+// is an index-only comparison and is kept as it is
+fn indexes_with_columns(
+    indexes: Vec<IndexInfo>,
+    columns: &[ColumnInfo],
+    other_columns: &[ColumnInfo],
+    side: &str,
+    errors: &mut Vec<String>
+) -> Vec<IndexInfo> {
+    if columns.is_empty() {
+        return indexes;
+    }
+    let tables: HashSet<(&str, &str)> = columns.iter().map(|column| (column.table_schema.as_str(), column.table_name.as_str())).collect();
+    let other_tables: HashSet<(&str, &str)> = other_columns.iter().map(|column| (column.table_schema.as_str(), column.table_name.as_str())).collect();
+    let mut left_out: BTreeSet<String> = BTreeSet::new();
+    let kept = indexes
+        .into_iter()
+        .filter(|index| {
+            let table = (index.table_schema.as_str(), index.table_name.as_str());
+            let orphan = !tables.contains(&table) && other_tables.contains(&table);
+            if orphan {
+                left_out.insert(format!("`{}`.{}", index.table_schema, index.table_name));
+            }
+            !orphan
+        })
+        .collect();
+    for table_key in left_out {
+        errors.push(format!("Indexes of {} in the {} export are left out, the table is not in its column export", table_key, side));
+    }
+    kept
+}
+
+// One statement of a combined script
+struct Step {
+    phase: usize,
+    table_key: String,
+    sql: String,
+}
+
+// Statements that turn the "from" maps into the "to" maps, sorted by phase and then by table.
+// The per-table diffs are taken from first to second, reverse reads them the other way round
+fn plan_statements(
+    tables: &BTreeMap<String, TableComparison>,
+    schema_from: &SchemaMap,
+    schema_to: &SchemaMap,
+    index_to: &IndexMapByTable,
+    reverse: bool
+) -> Vec<Step> {
+    let no_indexes = IndexMap::new();
+
+    let mut steps = Vec::new();
+    for (table_key, table) in tables {
+        let mut push = |phase: usize, sql: String| {
+            steps.push(Step { phase, table_key: table_key.clone(), sql });
+        };
+        let indexes_to = index_to.get(table_key).unwrap_or(&no_indexes);
+
+        match (schema_from.get(table_key), schema_to.get(table_key)) {
+            // DROP TABLE takes its indexes with it, and indexes of a table without columns on the other
+            // side are never created on it
+            (Some(_), None) => push(DROP_TABLE, format!("DROP TABLE {};", table_key)),
+            (None, Some(columns)) => {
+                let definitions: Vec<String> = columns.values().map(format_column_definition).collect();
+                push(CREATE_TABLE, format!("CREATE TABLE {} ({});", table_key, definitions.join(", ")));
+                for (name, index_list) in indexes_to {
+                    push(CREATE_TABLE, create_index_sql(table_key, name, index_list));
+                }
+            }
+            _ => {
+                let (indexes_only_in_from, indexes_only_in_to) = if reverse {
+                    (&table.indexes_only_in_second, &table.indexes_only_in_first)
+                } else {
+                    (&table.indexes_only_in_first, &table.indexes_only_in_second)
+                };
+                let (columns_only_in_from, columns_only_in_to) = if reverse {
+                    (&table.columns_only_in_second, &table.columns_only_in_first)
+                } else {
+                    (&table.columns_only_in_first, &table.columns_only_in_second)
+                };
+
+                let mut dropped: Vec<&String> = indexes_only_in_from.iter().map(|index| &index.index_name).collect();
+                let mut added: Vec<&String> = indexes_only_in_to.iter().map(|index| &index.index_name).collect();
+                let mut modified: Vec<&String> = table
+                    .indexes_with_different_definitions
+                    .iter()
+                    .map(|difference| &difference.index_name)
+                    .collect();
+                // One statement per index, not per indexed column
+                for names in [&mut dropped, &mut added, &mut modified] {
+                    names.sort();
+                    names.dedup();
+                }
+
+                for name in dropped.iter().chain(&modified) {
+                    push(DROP_INDEX, drop_index_sql(table_key, name));
+                }
+                for rename in &table.indexes_renamed {
+                    let (old_name, new_name) = if reverse {
+                        (&rename.new_index_name, &rename.old_index_name)
+                    } else {
+                        (&rename.old_index_name, &rename.new_index_name)
+                    };
+                    push(RENAME_INDEX, format!("ALTER TABLE {} RENAME INDEX `{}` TO `{}`;", table_key, old_name, new_name));
+                }
+                for column in columns_only_in_from {
+                    push(DROP_COLUMN, format!("ALTER TABLE {} DROP COLUMN {};", table_key, column.column_name));
+                }
+                for column in columns_only_in_to {
+                    push(ADD_COLUMN, format!("ALTER TABLE {} ADD COLUMN {};", table_key, format_column_definition(column)));
+                }
+                for difference in &table.columns_with_different_definitions {
+                    let definition = if reverse { &difference.first } else { &difference.second };
+                    push(MODIFY_COLUMN, format!("ALTER TABLE {} MODIFY COLUMN {};", table_key, format_column_definition(definition)));
+                }
+                for name in modified.iter().chain(&added) {
+                    if let Some(index_list) = indexes_to.get(*name) {
+                        push(ADD_INDEX, create_index_sql(table_key, name, index_list));
+                    }
+                }
+            }
+        }
+    }
+
+    // Stable, so statements of one table keep the order they were pushed in
+    steps.sort_by_key(|step| step.phase);
+    steps
+}
+
+fn create_index_sql(table_key: &str, name: &str, index_list: &[IndexInfo]) -> String {
+    let mut sorted: Vec<&IndexInfo> = index_list.iter().collect();
+    sorted.sort_by_key(|index| index.seq_in_index);
+    let columns: Vec<String> = sorted
+        .iter()
+        .map(|index| match index.sub_part {
+            Some(sub_part) => format!("{}({})", index.column_name, sub_part),
+            None => index.column_name.clone(),
+        })
+        .collect();
+
+    if name == "PRIMARY" {
+        format!("ALTER TABLE {} ADD PRIMARY KEY ({});", table_key, columns.join(", "))
+    } else if sorted.first().is_some_and(|index| index.non_unique == Some(0)) {
+        format!("CREATE UNIQUE INDEX `{}` ON {} ({});", name, table_key, columns.join(", "))
+    } else {
+        format!("CREATE INDEX `{}` ON {} ({});", name, table_key, columns.join(", "))
+    }
+}
+
+fn drop_index_sql(table_key: &str, name: &str) -> String {
+    if name == "PRIMARY" {
+        format!("ALTER TABLE {} DROP PRIMARY KEY;", table_key)
+    } else {
+        format!("DROP INDEX `{}` ON {};", name, table_key)
+    }
+}
+
+fn join_statements(steps: &[Step]) -> String {
+    steps.iter().map(|step| step.sql.as_str()).collect::<Vec<&str>>().join("\n")
+}
+
+// Statements of each table, in script order
+fn statements_by_table(steps: Vec<Step>) -> HashMap<String, Vec<String>> {
+    let mut tables: HashMap<String, Vec<String>> = HashMap::new();
+    for step in steps {
+        tables.entry(step.table_key).or_default().push(step.sql);
+    }
+    tables
+}
+
+fn table_entry<'a>(tables: &'a mut BTreeMap<String, TableComparison>, schema: &str, table: &str) -> &'a mut TableComparison {
+    tables
+        .entry(format!("`{}`.{}", schema, table))
+        .or_insert_with(|| TableComparison {
+            schema: schema.to_string(),
+            table: table.to_string(),
+            status: TableStatus::Changed,
+            columns_only_in_first: Vec::new(),
+            columns_only_in_second: Vec::new(),
+            columns_with_different_definitions: Vec::new(),
+            indexes_only_in_first: Vec::new(),
+            indexes_only_in_second: Vec::new(),
+            indexes_with_different_definitions: Vec::new(),
+            indexes_renamed: Vec::new(),
+            ddl_diff: String::new(),
+            forward_sql: Vec::new(),
+            reverse_sql: Vec::new(),
+        })
+}
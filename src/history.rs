@@ -0,0 +1,170 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use super::format_column_definition;
+use super::full::{TableComparison, TableStatus, compare_columns_and_indexes};
+use super::index::IndexInfo;
+use super::output::FORMAT_VERSION;
+use super::snapshot::{Snapshot, SnapshotComparison};
+
+// A commit that changed a snapshot file
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Revision {
+    pub commit: String,
+    pub author: String,
+    /// Author date in ISO 8601
+    pub date: String,
+    pub subject: String,
+}
+
+// What one commit changed in one table
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TableChange {
+    pub revision: Revision,
+    pub status: TableStatus,
+    /// One line per column or index change, e.g. "changed column `status`: status varchar(16) NULL -> status varchar(16) NOT NULL"
+    pub changes: Vec<String>,
+    /// Statements that apply the change, in script order
+    pub forward_sql: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TableHistory {
+    pub schema: String,
+    pub table: String,
+    /// Oldest first
+    pub changes: Vec<TableChange>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SchemaHistory {
+    pub format_version: u32,
+    /// Every table changed by one of the revisions, in `schema`.table order
+    pub tables: Vec<TableHistory>,
+}
+
+// A snapshot file compared between two revisions, with the history of every table in between
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RevisionComparison {
+    pub path: String,
+    pub first_revision: String,
+    pub second_revision: String,
+    pub comparison: SnapshotComparison,
+    pub history: SchemaHistory,
+}
+
+// Compare every revision with the one before it, starting from base, and collect the changes per
+// table. Revisions are expected oldest first
+pub fn schema_history(base: Snapshot, revisions: Vec<(Revision, Snapshot)>) -> SchemaHistory {
+    let mut tables: BTreeMap<String, TableHistory> = BTreeMap::new();
+    let mut previous = base;
+    for (revision, snapshot) in revisions {
+        // A created table is described by its column count, which the comparison does not list
+        let mut column_counts: BTreeMap<(&str, &str), usize> = BTreeMap::new();
+        for column in &snapshot.columns {
+            *column_counts.entry((&column.table_schema, &column.table_name)).or_default() += 1;
+        }
+        let comparison = compare_columns_and_indexes(
+            previous.columns,
+            snapshot.columns.clone(),
+            previous.indexes,
+            snapshot.indexes.clone(),
+        );
+        for table in comparison.tables {
+            let column_count = column_counts.get(&(table.schema.as_str(), table.table.as_str())).copied().unwrap_or_default();
+            let changes = describe_table(&table, column_count);
+            tables
+                .entry(format!("`{}`.{}", table.schema, table.table))
+                .or_insert_with(|| TableHistory { schema: table.schema.clone(), table: table.table.clone(), changes: Vec::new() })
+                .changes
+                .push(TableChange { revision: revision.clone(), status: table.status, changes, forward_sql: table.forward_sql });
+        }
+        previous = snapshot;
+    }
+    SchemaHistory { format_version: FORMAT_VERSION, tables: tables.into_values().collect() }
+}
+
+impl SchemaHistory {
+    // Only the tables whose name or `schema`.table / schema.table matches
+    pub fn filter_table(mut self, name: &str) -> Self {
+        self.tables.retain(|history| {
+            history.table == name
+                || format!("`{}`.{}", history.schema, history.table) == name
+                || format!("{}.{}", history.schema, history.table) == name
+        });
+        self
+    }
+
+    // A section meant to follow a report, so its headings start at level 2
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("## Schema history\n");
+        if self.tables.is_empty() {
+            out.push_str("\nNo table changed in this range.\n");
+            return out;
+        }
+        for history in &self.tables {
+            out.push_str(&format!("\n### `{}`.{}\n\n", history.schema, history.table));
+            for change in &history.changes {
+                let revision = &change.revision;
+                let short = revision.commit.get(..7).unwrap_or(&revision.commit);
+                let date = revision.date.get(..10).unwrap_or(&revision.date);
+                out.push_str(&format!("- `{}` {} {}: {}\n", short, date, revision.author, revision.subject));
+                for line in &change.changes {
+                    out.push_str(&format!("  - {}\n", line));
+                }
+            }
+        }
+        out
+    }
+}
+
+fn describe_table(table: &TableComparison, column_count: usize) -> Vec<String> {
+    match table.status {
+        TableStatus::OnlyInSecond => return vec![format!("created table with {} column(s)", column_count)],
+        TableStatus::OnlyInFirst => return vec!["dropped table".to_string()],
+        TableStatus::Changed => {}
+    }
+    let mut changes = Vec::new();
+    for column in &table.columns_only_in_second {
+        changes.push(format!("added column `{}`: {}", column.column_name, format_column_definition(column)));
+    }
+    for column in &table.columns_only_in_first {
+        changes.push(format!("dropped column `{}`", column.column_name));
+    }
+    for difference in &table.columns_with_different_definitions {
+        changes.push(format!(
+            "changed column `{}`: {} -> {}",
+            difference.column_name,
+            format_column_definition(&difference.first),
+            format_column_definition(&difference.second)
+        ));
+    }
+    for (name, columns) in index_columns(&table.indexes_only_in_second) {
+        changes.push(format!("added index `{}` ({})", name, columns));
+    }
+    for (name, columns) in index_columns(&table.indexes_only_in_first) {
+        changes.push(format!("dropped index `{}` ({})", name, columns));
+    }
+    for difference in &table.indexes_with_different_definitions {
+        changes.push(format!("changed index `{}`", difference.index_name));
+    }
+    for rename in &table.indexes_renamed {
+        changes.push(format!("renamed index `{}` to `{}`", rename.old_index_name, rename.new_index_name));
+    }
+    changes
+}
+
+// Index rows grouped by index, with their columns in index order
+fn index_columns(indexes: &[IndexInfo]) -> Vec<(String, String)> {
+    let mut grouped: BTreeMap<&str, Vec<(Option<u32>, &str)>> = BTreeMap::new();
+    for index in indexes {
+        grouped.entry(&index.index_name).or_default().push((index.seq_in_index, &index.column_name));
+    }
+    grouped
+        .into_iter()
+        .map(|(name, mut columns)| {
+            columns.sort();
+            let columns: Vec<&str> = columns.into_iter().map(|(_, column)| column).collect();
+            (name.to_string(), columns.join(", "))
+        })
+        .collect()
+}
@@ -1,8 +1,11 @@
 use serde::{Deserialize, Deserializer, Serialize};
+use schemars::JsonSchema;
 use std::collections::{HashMap, HashSet};
 use indexmap::IndexMap;
 use wasm_bindgen::prelude::*;
-use super::{MariaDBJson};
+use super::model::{SchemaModel, verify_sql};
+use super::output::IndexComparison;
+use super::parallel;
 
 // Custom deserializer to handle both string and integer representations for u32
 fn deserialize_optional_string_as_u32<'de, D>(deserializer: D) -> Result<Option<u32>, D::Error>
@@ -82,7 +85,7 @@ where
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, JsonSchema)]
 pub struct IndexInfo {
     pub table_schema: String,
     pub table_name: String,
@@ -207,34 +210,62 @@ impl IndexInfo {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 pub struct IndexDifference {
+    pub table_schema: String,
     pub table_name: String,
     pub index_name: String,
     pub first: IndexInfo,
     pub second: IndexInfo,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+// Index with the same definition on both sides that only differs by name
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct IndexRename {
+    pub table_schema: String,
+    pub table_name: String,
+    pub old_index_name: String,
+    pub new_index_name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, JsonSchema)]
 pub struct IndexDiff{
+    /// One entry per indexed column of indexes that only exist in the first export
     pub indexes_only_in_first: Vec<IndexInfo>,
+    /// One entry per indexed column of indexes that only exist in the second export
     pub indexes_only_in_second: Vec<IndexInfo>,
+    /// Indexes present on both sides with a different definition, first column of each side
     pub indexes_with_different_definitions: Vec<IndexDifference>,
+    /// Indexes with the same definition but a different name
+    #[serde(default)]
+    pub indexes_renamed: Vec<IndexRename>,
 }
 
 // What i should do
 #[wasm_bindgen]
 pub fn compare_indexs(json1: &str, json2: &str) -> String {
     // Parse index information if available
-    let indexes1 = match create_index_info(json1) {
-        Ok(indexes) => indexes,
-        Err(_) => vec![], // If parsing fails, proceed with empty indexes
-    };
+    // If parsing fails, proceed with empty indexes
+    let indexes1 = create_index_info(json1).unwrap_or_default();
 
-    let indexes2 = match create_index_info(json2) {
-        Ok(indexes) => indexes,
-        Err(_) => vec![], // If parsing fails, proceed with empty indexes
-    };
+    // If parsing fails, proceed with empty indexes
+    let indexes2 = create_index_info(json2).unwrap_or_default();
+
+    let result = compare_indexes(indexes1, indexes2);
+
+    // Serialize the result to JSON
+    match serde_json::to_string(&result) {
+        Ok(result) => result,
+        Err(e) => format!("Error serializing result: {}", e),
+    }
+
+}
+
+// Diff, SQL and self-check of two index exports, shared by the wasm exports
+pub fn compare_indexes(indexes1: Vec<IndexInfo>, indexes2: Vec<IndexInfo>) -> IndexComparison {
+    // Models used to check that the generated SQL really turns one schema into the other
+    let model1 = SchemaModel::new().with_indexes(&indexes1);
+    let model2 = SchemaModel::new().with_indexes(&indexes2);
 
     // Create <table, index>
     let index_map1 = build_index_map(indexes1);
@@ -246,134 +277,131 @@ pub fn compare_indexs(json1: &str, json2: &str) -> String {
     // Generate SQL diff statements
     let sql_statements = generate_sql_index_diff(&diff);
 
-    // Create a result object that includes both the diff and SQL statements
-    let result = serde_json::json!({
-        "diff": diff,
-        "sql": sql_statements
-    });
-
-    // Serialize the result to JSON
-    match serde_json::to_string(&result) {
-        Ok(result) => result,
-        Err(e) => format!("Error serializing result: {}", e),
-    }
+    let verification_errors = verify_sql(&model1, &model2, &sql_statements).err().unwrap_or_default();
 
+    // Create a result object that includes both the diff and SQL statements
+    IndexComparison::new(diff, sql_statements, verification_errors)
 }
 
 pub fn compare_index_maps(
     index_map1: &HashMap<String, IndexMap<String, Vec<IndexInfo>>>,
     index_map2: &HashMap<String, IndexMap<String, Vec<IndexInfo>>>
 ) -> IndexDiff{
+    // Same table-level split as compare_schema_maps, joined in table key order
+    let table_keys = parallel::table_keys(index_map1, index_map2);
+    let tables = parallel::map_tables(&table_keys, |table_key| {
+        compare_table_indexes(index_map1.get(table_key), index_map2.get(table_key))
+    });
 
-    // Compare indexes
-    let index_tables1: HashSet<&String> = index_map1.keys().collect();
-    let index_tables2: HashSet<&String> = index_map2.keys().collect();
-
-    let mut indexes_only_in_first: Vec<IndexInfo> = Vec::new();
-    let mut indexes_only_in_second: Vec<IndexInfo> = Vec::new();
-    let mut indexes_with_different_definitions: Vec<IndexDifference> = Vec::new();
-
-    // Find common tables for index comparison
-    let common_index_tables: HashSet<&String> = index_tables1.intersection(&index_tables2).cloned().collect();
-
-    for table_key in common_index_tables {
-        let indexes1_opt = index_map1.get(table_key);
-        let indexes2_opt = index_map2.get(table_key);
-
-        let indexes1 = match indexes1_opt {
-            Some(idx_map) => idx_map,
-            None => continue,
-        };
-
-        let indexes2 = match indexes2_opt {
-            Some(idx_map) => idx_map,
-            None => continue,
-        };
+    let mut diff = IndexDiff::default();
+    for table in tables {
+        diff.append(table);
+    }
+    diff
+}
 
-        let index_names1: HashSet<&String> = indexes1.keys().collect();
-        let index_names2: HashSet<&String> = indexes2.keys().collect();
+impl IndexDiff {
+    pub(crate) fn append(&mut self, other: IndexDiff) {
+        self.indexes_only_in_first.extend(other.indexes_only_in_first);
+        self.indexes_only_in_second.extend(other.indexes_only_in_second);
+        self.indexes_with_different_definitions.extend(other.indexes_with_different_definitions);
+        self.indexes_renamed.extend(other.indexes_renamed);
+    }
+}
 
-        // Indexes only in first schema
-        for index_name in index_names1.difference(&index_names2) {
-            if let Some(index_list) = indexes1.get(*index_name) {
-                for index in index_list {
-                    indexes_only_in_first.push(index.clone());
-                }
-            }
+// The part of the index diff that concerns one table
+pub(crate) fn compare_table_indexes(
+    indexes1: Option<&IndexMap<String, Vec<IndexInfo>>>,
+    indexes2: Option<&IndexMap<String, Vec<IndexInfo>>>
+) -> IndexDiff {
+    let mut diff = IndexDiff::default();
+    let (indexes1, indexes2) = match (indexes1, indexes2) {
+        (Some(indexes1), Some(indexes2)) => (indexes1, indexes2),
+        // Handle indexes for tables that exist in only one schema
+        (Some(indexes1), None) => {
+            diff.indexes_only_in_first.extend(indexes1.values().flatten().cloned());
+            return diff;
         }
-
-        // Indexes only in second schema
-        for index_name in index_names2.difference(&index_names1) {
-            if let Some(index_list) = indexes2.get(*index_name) {
-                for index in index_list {
-                    indexes_only_in_second.push(index.clone());
-                }
-            }
+        (None, Some(indexes2)) => {
+            diff.indexes_only_in_second.extend(indexes2.values().flatten().cloned());
+            return diff;
         }
+        (None, None) => return diff,
+    };
 
-        // Compare common indexes
-        for index_name in index_names1.intersection(&index_names2) {
-            let idx1_list = indexes1.get(*index_name).unwrap();
-            let idx2_list = indexes2.get(*index_name).unwrap();
-
-            if !compare_index_lists(idx1_list, idx2_list) {
-                // Extract just the table name (without schema) for the IndexDifference
-                let table_name = table_key.split('.').nth(1).unwrap_or(table_key).to_string();
-
-                let first_idx = idx1_list.get(0).cloned().unwrap_or_else(|| IndexInfo::builder().build().unwrap());
-                let second_idx = idx2_list.get(0).cloned().unwrap_or_else(|| IndexInfo::builder().build().unwrap());
-
-                indexes_with_different_definitions.push(IndexDifference {
-                    table_name,
-                    index_name: (*index_name).clone(),
-                    first: first_idx,
-                    second: second_idx,
+    let mut names_only_in_first: Vec<&String> = indexes1.keys().filter(|name| !indexes2.contains_key(*name)).collect();
+    let mut names_only_in_second: Vec<&String> = indexes2.keys().filter(|name| !indexes1.contains_key(*name)).collect();
+    names_only_in_first.sort();
+    names_only_in_second.sort();
+
+    // An index that only differs by name is a rename rather than a drop plus a create
+    let mut renamed_in_second: HashSet<&String> = HashSet::new();
+    for old_name in &names_only_in_first {
+        let old_list = &indexes1[*old_name];
+        let new_name = names_only_in_second.iter().find(|new_name| {
+            !renamed_in_second.contains(*new_name)
+                && *old_name != "PRIMARY"
+                && **new_name != "PRIMARY"
+                && same_index_definition(old_list, &indexes2[**new_name])
+        });
+
+        match new_name {
+            Some(new_name) => {
+                renamed_in_second.insert(new_name);
+                let first = &old_list[0];
+                diff.indexes_renamed.push(IndexRename {
+                    table_schema: first.table_schema.clone(),
+                    table_name: first.table_name.clone(),
+                    old_index_name: (*old_name).clone(),
+                    new_index_name: (*new_name).clone(),
                 });
             }
+            // Indexes only in first schema
+            None => diff.indexes_only_in_first.extend(old_list.iter().cloned()),
         }
     }
 
-    // Handle indexes for tables that exist in only one schema
-    for table_key in index_tables1.difference(&index_tables2) {
-        if let Some(indexes) = index_map1.get(*table_key) {
-            for (_, index_list) in indexes {
-                for index in index_list {
-                    indexes_only_in_first.push(index.clone());
-                }
-            }
+    // Indexes only in second schema
+    for index_name in names_only_in_second {
+        if !renamed_in_second.contains(index_name) {
+            diff.indexes_only_in_second.extend(indexes2[index_name].iter().cloned());
         }
     }
 
-    for table_key in index_tables2.difference(&index_tables1) {
-        if let Some(indexes) = index_map2.get(*table_key) {
-            for (_, index_list) in indexes {
-                for index in index_list {
-                    indexes_only_in_second.push(index.clone());
-                }
-            }
+    // Compare common indexes
+    for (index_name, idx1_list) in indexes1 {
+        let Some(idx2_list) = indexes2.get(index_name) else {
+            continue;
+        };
+        if !compare_index_lists(idx1_list, idx2_list) {
+            let first_idx = idx1_list.first().cloned().unwrap_or_else(|| IndexInfo::builder().build().unwrap());
+            let second_idx = idx2_list.first().cloned().unwrap_or_else(|| IndexInfo::builder().build().unwrap());
+
+            diff.indexes_with_different_definitions.push(IndexDifference {
+                table_schema: first_idx.table_schema.clone(),
+                table_name: first_idx.table_name.clone(),
+                index_name: index_name.clone(),
+                first: first_idx,
+                second: second_idx,
+            });
         }
     }
-
-    IndexDiff{
-        indexes_only_in_first,
-        indexes_only_in_second,
-        indexes_with_different_definitions,
-    }
-
+    diff
 }
 
 pub fn generate_sql_index_diff(diff: &IndexDiff) -> HashMap<String, String> {
-    let mut sql_statements = HashMap::new();
+    let mut sql_statements: HashMap<String, String> = HashMap::new();
     // Handle indexes - Drop indexes that exist only in schema 1
     if !diff.indexes_only_in_first.is_empty() {
         for index in &diff.indexes_only_in_first {
-            let key = format!("Drop index in Schema 1 (Schema 1 only)");
+            let key = "Drop index in Schema 1 (Schema 1 only)".to_string();
             let value = format!(
                 "DROP INDEX `{}` ON `{}`.{};",
                 index.index_name, index.table_schema, index.table_name
             );
             if let Some(existing) = sql_statements.get_mut(&key) {
-                *existing = format!("{}\n{}", existing, value);
+                existing.push('\n');
+                existing.push_str(&value);
             } else {
                 sql_statements.insert(key, value);
             }
@@ -383,7 +411,7 @@ pub fn generate_sql_index_diff(diff: &IndexDiff) -> HashMap<String, String> {
     // Handle indexes - Add indexes that exist only in schema 2
     if !diff.indexes_only_in_second.is_empty() {
         for index in &diff.indexes_only_in_second {
-            let key = format!("Add index in Schema 1 (Schema 2 only)");
+            let key = "Add index in Schema 1 (Schema 2 only)".to_string();
             let is_unique = matches!(index.non_unique, Some(0));
             let value = if is_unique {
                 format!(
@@ -397,7 +425,8 @@ pub fn generate_sql_index_diff(diff: &IndexDiff) -> HashMap<String, String> {
                 )
             };
             if let Some(existing) = sql_statements.get_mut(&key) {
-                *existing = format!("{}\n{}", existing, value);
+                existing.push('\n');
+                existing.push_str(&value);
             } else {
                 sql_statements.insert(key, value);
             }
@@ -407,7 +436,7 @@ pub fn generate_sql_index_diff(diff: &IndexDiff) -> HashMap<String, String> {
     // Handle indexes - Modify indexes that have different definitions
     if !diff.indexes_with_different_definitions.is_empty() {
         for diff_item in &diff.indexes_with_different_definitions {
-            let key = format!("Modify index in Schema 1");
+            let key = "Modify index in Schema 1".to_string();
             // For now, drop and recreate the index with new definition
             let drop_value = format!(
                 "DROP INDEX `{}` ON `{}`.{};",
@@ -427,7 +456,25 @@ pub fn generate_sql_index_diff(diff: &IndexDiff) -> HashMap<String, String> {
             };
             let value = format!("{}\n{}", drop_value, create_value);
             if let Some(existing) = sql_statements.get_mut(&key) {
-                *existing = format!("{}\n{}", existing, value);
+                existing.push('\n');
+                existing.push_str(&value);
+            } else {
+                sql_statements.insert(key, value);
+            }
+        }
+    }
+
+    // Handle indexes - Rename indexes that only differ by name
+    if !diff.indexes_renamed.is_empty() {
+        for rename in &diff.indexes_renamed {
+            let key = "Rename index in Schema 1".to_string();
+            let value = format!(
+                "ALTER TABLE `{}`.{} RENAME INDEX `{}` TO `{}`;",
+                rename.table_schema, rename.table_name, rename.old_index_name, rename.new_index_name
+            );
+            if let Some(existing) = sql_statements.get_mut(&key) {
+                existing.push('\n');
+                existing.push_str(&value);
             } else {
                 sql_statements.insert(key, value);
             }
@@ -437,7 +484,7 @@ pub fn generate_sql_index_diff(diff: &IndexDiff) -> HashMap<String, String> {
     // Handle indexes - Add indexes that exist only in schema 1 (for schema 2)
     if !diff.indexes_only_in_first.is_empty() {
         for index in &diff.indexes_only_in_first {
-            let key = format!("Add index in Schema 2 (Schema 1 only)");
+            let key = "Add index in Schema 2 (Schema 1 only)".to_string();
             let is_unique = matches!(index.non_unique, Some(0));
             let value = if is_unique {
                 format!(
@@ -451,7 +498,8 @@ pub fn generate_sql_index_diff(diff: &IndexDiff) -> HashMap<String, String> {
                 )
             };
             if let Some(existing) = sql_statements.get_mut(&key) {
-                *existing = format!("{}\n{}", existing, value);
+                existing.push('\n');
+                existing.push_str(&value);
             } else {
                 sql_statements.insert(key, value);
             }
@@ -461,13 +509,14 @@ pub fn generate_sql_index_diff(diff: &IndexDiff) -> HashMap<String, String> {
     // Handle indexes - Drop indexes that exist only in schema 2
     if !diff.indexes_only_in_second.is_empty() {
         for index in &diff.indexes_only_in_second {
-            let key = format!("Drop index in Schema 2 (Schema 2 only)");
+            let key = "Drop index in Schema 2 (Schema 2 only)".to_string();
             let value = format!(
                 "DROP INDEX `{}` ON `{}`.{};",
                 index.index_name, index.table_schema, index.table_name
             );
             if let Some(existing) = sql_statements.get_mut(&key) {
-                *existing = format!("{}\n{}", existing, value);
+                existing.push('\n');
+                existing.push_str(&value);
             } else {
                 sql_statements.insert(key, value);
             }
@@ -477,7 +526,7 @@ pub fn generate_sql_index_diff(diff: &IndexDiff) -> HashMap<String, String> {
     // Handle indexes - Modify indexes that have different definitions (for schema 2)
     if !diff.indexes_with_different_definitions.is_empty() {
         for diff_item in &diff.indexes_with_different_definitions {
-            let key = format!("Modify index in Schema 2");
+            let key = "Modify index in Schema 2".to_string();
             // For now, drop and recreate the index with new definition
             let drop_value = format!(
                 "DROP INDEX `{}` ON `{}`.{};",
@@ -497,7 +546,25 @@ pub fn generate_sql_index_diff(diff: &IndexDiff) -> HashMap<String, String> {
             };
             let value = format!("{}\n{}", drop_value, create_value);
             if let Some(existing) = sql_statements.get_mut(&key) {
-                *existing = format!("{}\n{}", existing, value);
+                existing.push('\n');
+                existing.push_str(&value);
+            } else {
+                sql_statements.insert(key, value);
+            }
+        }
+    }
+
+    // Handle indexes - Rename indexes back to their schema 1 names (for schema 2)
+    if !diff.indexes_renamed.is_empty() {
+        for rename in &diff.indexes_renamed {
+            let key = "Rename index in Schema 2".to_string();
+            let value = format!(
+                "ALTER TABLE `{}`.{} RENAME INDEX `{}` TO `{}`;",
+                rename.table_schema, rename.table_name, rename.new_index_name, rename.old_index_name
+            );
+            if let Some(existing) = sql_statements.get_mut(&key) {
+                existing.push('\n');
+                existing.push_str(&value);
             } else {
                 sql_statements.insert(key, value);
             }
@@ -516,22 +583,22 @@ pub fn build_index_map(indexes: Vec<IndexInfo>) -> HashMap<String, IndexMap<Stri
         let table_key = format!("`{}`.{}", index.table_schema, index.table_name);
 
         // Group indexes by table and index name
-        let index_map_entry = index_map.entry(table_key).or_insert_with(IndexMap::new);
-        let index_list = index_map_entry.entry(index.index_name.clone()).or_insert_with(Vec::new);
+        let index_map_entry = index_map.entry(table_key).or_default();
+        let index_list = index_map_entry.entry(index.index_name.clone()).or_default();
         index_list.push(index);
     }
     index_map
 }
 
 // Function to compare index lists for equality
-fn compare_index_lists(list1: &Vec<IndexInfo>, list2: &Vec<IndexInfo>) -> bool {
+pub(crate) fn compare_index_lists(list1: &[IndexInfo], list2: &[IndexInfo]) -> bool {
     if list1.len() != list2.len() {
         return false;
     }
 
     // Sort both lists by column name and sequence to ensure consistent comparison
-    let mut sorted_list1 = list1.clone();
-    let mut sorted_list2 = list2.clone();
+    let mut sorted_list1 = list1.to_vec();
+    let mut sorted_list2 = list2.to_vec();
 
     sorted_list1.sort_by(|a, b| a.seq_in_index.cmp(&b.seq_in_index).then_with(|| a.column_name.cmp(&b.column_name)));
     sorted_list2.sort_by(|a, b| a.seq_in_index.cmp(&b.seq_in_index).then_with(|| a.column_name.cmp(&b.column_name)));
@@ -553,21 +620,39 @@ fn compare_index_lists(list1: &Vec<IndexInfo>, list2: &Vec<IndexInfo>) -> bool {
     true
 }
 
+// Function to compare index definitions ignoring the index name, used to detect renames
+fn same_index_definition(list1: &[IndexInfo], list2: &[IndexInfo]) -> bool {
+    if list1.len() != list2.len() {
+        return false;
+    }
+
+    let mut sorted_list1 = list1.to_vec();
+    let mut sorted_list2 = list2.to_vec();
+
+    sorted_list1.sort_by(|a, b| a.seq_in_index.cmp(&b.seq_in_index).then_with(|| a.column_name.cmp(&b.column_name)));
+    sorted_list2.sort_by(|a, b| a.seq_in_index.cmp(&b.seq_in_index).then_with(|| a.column_name.cmp(&b.column_name)));
+
+    sorted_list1.iter().zip(sorted_list2.iter()).all(|(idx1, idx2)| {
+        idx1.column_name == idx2.column_name &&
+        idx1.sub_part == idx2.sub_part &&
+        idx1.collation == idx2.collation &&
+        idx1.nullable == idx2.nullable &&
+        idx1.index_type == idx2.index_type &&
+        idx1.non_unique == idx2.non_unique
+    })
+}
+
 pub fn create_index_info(json: &str) -> Result<Vec<IndexInfo>, String>{
+     if super::snapshot::is_snapshot(json) {
+         return super::snapshot::load_snapshot(json).map(|snapshot| snapshot.indexes);
+     }
      match serde_json::from_str::<Vec<IndexInfo>>(json) {
-        Ok(columns) => return Ok(columns),
+        Ok(columns) => Ok(columns),
         Err(_) => {
-            match serde_json::from_str::<Vec<MariaDBJson<IndexInfo>>>(json){
-                Ok(column) => {
-                        for i in 1..column.len(){
-                            if column[i].data.is_some() {return Ok(column[i].data.clone().unwrap());}
-                        }
-                        return Err("Cannot find data".to_string());
-                },
-                Err(e) => return Err(format!("Error parsing first JSON: {}", e)),
-            }
+            // phpMyAdmin / MariaDB export with header, database and table blocks
+            super::phpmyadmin::parse_export::<IndexInfo>(json).map(|export| export.rows)
         }
-    };
+    }
 }
 
 #[cfg(test)]
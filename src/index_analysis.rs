@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use indexmap::IndexMap;
+use wasm_bindgen::prelude::*;
+use super::index::{IndexInfo, build_index_map, create_index_info};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Redundancy {
+    // Same columns in the same order as another index
+    Duplicate,
+    // Columns are a left-prefix of another index on the same table
+    LeftPrefix,
+    // Unique index on the same columns as the PRIMARY key
+    DuplicatesPrimary,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RedundantIndex {
+    pub table_schema: String,
+    pub table_name: String,
+    pub index_name: String,
+    pub columns: Vec<String>,
+    pub redundancy: Redundancy,
+    pub covered_by: String,
+    pub sql: String,
+}
+
+// Columns of one index in seq_in_index order, with their prefix length
+struct IndexShape<'a> {
+    name: &'a str,
+    position: usize,
+    columns: Vec<(&'a str, Option<u64>)>,
+    unique: bool,
+    primary: bool,
+    index_type: &'a str,
+    first: &'a IndexInfo,
+}
+
+#[wasm_bindgen]
+pub fn analyze_indexes(json: &str) -> String {
+    let indexes = match create_index_info(json) {
+        Ok(indexes) => indexes,
+        Err(e) => return e,
+    };
+
+    let index_map = build_index_map(indexes);
+    let findings = analyze_index_map(&index_map);
+
+    let sql = findings
+        .iter()
+        .map(|finding| finding.sql.clone())
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    let result = serde_json::json!({
+        "findings": findings,
+        "sql": sql
+    });
+
+    match serde_json::to_string(&result) {
+        Ok(result) => result,
+        Err(e) => format!("Error serializing result: {}", e),
+    }
+}
+
+pub fn analyze_index_map(index_map: &HashMap<String, IndexMap<String, Vec<IndexInfo>>>) -> Vec<RedundantIndex> {
+    let mut table_keys: Vec<&String> = index_map.keys().collect();
+    table_keys.sort();
+
+    let mut findings = Vec::new();
+    for table_key in table_keys {
+        let shapes: Vec<IndexShape> = index_map[table_key]
+            .iter()
+            .enumerate()
+            .filter_map(|(position, (name, index_list))| index_shape(name, position, index_list))
+            .collect();
+
+        let mut reported: HashSet<&str> = HashSet::new();
+        for shape in &shapes {
+            if shape.primary {
+                continue;
+            }
+            let covering = shapes
+                .iter()
+                .filter(|other| other.name != shape.name && !reported.contains(other.name))
+                .find_map(|other| redundancy(shape, other).map(|kind| (kind, other.name)));
+
+            if let Some((kind, covered_by)) = covering {
+                reported.insert(shape.name);
+                findings.push(RedundantIndex {
+                    table_schema: shape.first.table_schema.clone(),
+                    table_name: shape.first.table_name.clone(),
+                    index_name: shape.name.to_string(),
+                    columns: shape.columns.iter().map(|(column, _)| column.to_string()).collect(),
+                    redundancy: kind,
+                    covered_by: covered_by.to_string(),
+                    sql: format!(
+                        "DROP INDEX `{}` ON `{}`.{};",
+                        shape.name, shape.first.table_schema, shape.first.table_name
+                    ),
+                });
+            }
+        }
+    }
+    findings
+}
+
+fn index_shape<'a>(name: &'a str, position: usize, index_list: &'a [IndexInfo]) -> Option<IndexShape<'a>> {
+    let first = index_list.first()?;
+    let mut sorted: Vec<&IndexInfo> = index_list.iter().collect();
+    sorted.sort_by_key(|index| index.seq_in_index);
+    Some(IndexShape {
+        name,
+        position,
+        columns: sorted.iter().map(|index| (index.column_name.as_str(), index.sub_part)).collect(),
+        unique: matches!(first.non_unique, Some(0)),
+        primary: name == "PRIMARY",
+        index_type: first.index_type.as_str(),
+        first,
+    })
+}
+
+// Why `shape` is made redundant by `other`, if it is
+fn redundancy(shape: &IndexShape, other: &IndexShape) -> Option<Redundancy> {
+    if shape.index_type != other.index_type {
+        return None;
+    }
+
+    if other.primary && shape.unique {
+        let mut columns = shape.columns.clone();
+        let mut primary_columns = other.columns.clone();
+        columns.sort();
+        primary_columns.sort();
+        if columns == primary_columns {
+            return Some(Redundancy::DuplicatesPrimary);
+        }
+    }
+
+    if shape.columns == other.columns {
+        // A unique index is only covered by another unique index, and of two
+        // identical indexes the one declared second is the one to drop
+        let keep_other = other.primary
+            || (other.unique && !shape.unique)
+            || (other.unique == shape.unique && other.position < shape.position);
+        return if keep_other { Some(Redundancy::Duplicate) } else { None };
+    }
+
+    // Left-prefix lookups only work for B-tree indexes, and a unique prefix still enforces a constraint
+    if !shape.unique
+        && shape.index_type.eq_ignore_ascii_case("BTREE")
+        && shape.columns.len() < other.columns.len()
+        && other.columns.starts_with(&shape.columns)
+    {
+        return Some(Redundancy::LeftPrefix);
+    }
+
+    None
+}
@@ -0,0 +1,204 @@
+use std::io::{Cursor, Read};
+use serde::{Deserialize, Serialize};
+use serde::de::IgnoredAny;
+use flate2::read::MultiGzDecoder;
+use super::ColumnInfo;
+use super::encoding::{DecodedText, decode_bytes};
+use super::index::{IndexInfo, create_index_info};
+use super::phpmyadmin::{ExportBlock, ExportRow, parse_export};
+use super::snapshot::{Snapshot, is_snapshot, load_snapshot};
+use super::sql_dump::{DEFAULT_SCHEMA, parse_sql_dump};
+
+// Largest file a gzip member or zip entry may unpack to, a small archive can expand without bound
+pub const MAX_DECOMPRESSED_SIZE: u64 = 512 * 1024 * 1024;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Compression {
+    #[default]
+    None,
+    Gzip,
+    Zip,
+}
+
+// Detected from the magic bytes, the file name is not trusted
+pub fn detect_compression(bytes: &[u8]) -> Compression {
+    match bytes {
+        [0x1F, 0x8B, ..] => Compression::Gzip,
+        [b'P', b'K', 0x03, 0x04, ..] | [b'P', b'K', 0x05, 0x06, ..] => Compression::Zip,
+        _ => Compression::None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputFile {
+    pub name: String,
+    pub bytes: Vec<u8>,
+}
+
+impl InputFile {
+    fn is_sql(&self) -> bool {
+        self.name.to_ascii_lowercase().ends_with(".sql")
+    }
+}
+
+// Unpack an upload into the files it holds. A plain file is returned as is, a gzip file as its single
+// member and a zip archive as every .json and .sql entry in archive order
+pub fn decompress(name: &str, bytes: &[u8]) -> Result<(Compression, Vec<InputFile>), String> {
+    decompress_limited(name, bytes, MAX_DECOMPRESSED_SIZE)
+}
+
+// decompress with a different cap on the unpacked size of each file
+pub fn decompress_limited(name: &str, bytes: &[u8], limit: u64) -> Result<(Compression, Vec<InputFile>), String> {
+    let compression = detect_compression(bytes);
+    let files = match compression {
+        Compression::None => vec![InputFile { name: name.to_string(), bytes: bytes.to_vec() }],
+        Compression::Gzip => {
+            let mut decoder = MultiGzDecoder::new(bytes);
+            let data = read_limited(&mut decoder, limit).map_err(|e| format!("Error reading gzip file {}: {}", name, e))?;
+            // The original name is stored in the header by gzip -N, otherwise drop the extension
+            let inner_name = decoder
+                .header()
+                .and_then(|header| header.filename())
+                .map(|filename| String::from_utf8_lossy(filename).into_owned())
+                .unwrap_or_else(|| strip_extension(name, ".gz"));
+            vec![InputFile { name: inner_name, bytes: data }]
+        }
+        Compression::Zip => {
+            let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).map_err(|e| format!("Error reading zip file {}: {}", name, e))?;
+            let mut files = Vec::new();
+            for position in 0..archive.len() {
+                let mut entry = archive.by_index(position).map_err(|e| format!("Error reading zip file {}: {}", name, e))?;
+                let entry_name = entry.name().to_string();
+                let base_name = entry_name.rsplit('/').next().unwrap_or_default();
+                let lower = entry_name.to_ascii_lowercase();
+                // Resource forks added by the macOS archiver look like real entries
+                if entry.is_dir() || lower.starts_with("__macosx/") || base_name.starts_with("._") {
+                    continue;
+                }
+                if !lower.ends_with(".json") && !lower.ends_with(".sql") {
+                    continue;
+                }
+                let data = read_limited(&mut entry, limit).map_err(|e| format!("Error reading {} from {}: {}", entry_name, name, e))?;
+                files.push(InputFile { name: entry_name, bytes: data });
+            }
+            if files.is_empty() {
+                return Err(format!("Zip file {} contains no .json or .sql files", name));
+            }
+            files
+        }
+    };
+    Ok((compression, files))
+}
+
+// Read at most limit bytes, one byte more means the file is too large
+fn read_limited(reader: &mut impl Read, limit: u64) -> Result<Vec<u8>, String> {
+    let mut data = Vec::new();
+    reader.take(limit + 1).read_to_end(&mut data).map_err(|e| e.to_string())?;
+    if data.len() as u64 > limit {
+        return Err(format!("file unpacks to more than {} bytes", limit));
+    }
+    Ok(data)
+}
+
+fn strip_extension(name: &str, extension: &str) -> String {
+    match name.len().checked_sub(extension.len()) {
+        Some(position) if name.is_char_boundary(position) && name[position..].eq_ignore_ascii_case(extension) => name[..position].to_string(),
+        _ => name.to_string(),
+    }
+}
+
+// Read an upload of any supported kind, plain or compressed JSON exports, snapshots and
+// mysqldump files, into one snapshot holding the columns and indexes of every file
+pub fn read_input(name: &str, bytes: &[u8], encoding: Option<&str>) -> Result<Snapshot, String> {
+    let (_, files) = decompress(name, bytes)?;
+    merge_files(&files, encoding).map(|(snapshot, _)| snapshot)
+}
+
+// Columns and indexes of every file, with the decoding result of each
+fn merge_files(files: &[InputFile], encoding: Option<&str>) -> Result<(Snapshot, Vec<DecodedText>), String> {
+    let mut columns: Vec<ColumnInfo> = Vec::new();
+    let mut indexes: Vec<IndexInfo> = Vec::new();
+    let mut decoded_files = Vec::new();
+    for file in files {
+        let decoded = decode_bytes(&file.bytes, encoding)?;
+        let (file_columns, file_indexes) = read_file(file, &decoded.text)?;
+        columns.extend(file_columns);
+        indexes.extend(file_indexes);
+        decoded_files.push(decoded);
+    }
+    Ok((Snapshot::new(columns, indexes).seal(), decoded_files))
+}
+
+fn read_file(file: &InputFile, text: &str) -> Result<(Vec<ColumnInfo>, Vec<IndexInfo>), String> {
+    if file.is_sql() {
+        let dump = parse_sql_dump(text, DEFAULT_SCHEMA).map_err(|e| format!("{}: {}", file.name, e))?;
+        return Ok((dump.columns, dump.indexes));
+    }
+    read_json(text).map_err(|e| format!("{}: {}", file.name, e))
+}
+
+// A JSON file holds columns, indexes or both (a snapshot or a phpMyAdmin export with both tables)
+fn read_json(text: &str) -> Result<(Vec<ColumnInfo>, Vec<IndexInfo>), String> {
+    if is_snapshot(text) {
+        let snapshot = load_snapshot(text)?;
+        return Ok((snapshot.columns, snapshot.indexes));
+    }
+    match json_kind(text) {
+        JsonKind::Columns => Ok((super::create_column_info(text)?, Vec::new())),
+        JsonKind::Indexes => Ok((Vec::new(), create_index_info(text)?)),
+        JsonKind::PhpMyAdmin => match (parse_export::<ColumnInfo>(text), parse_export::<IndexInfo>(text)) {
+            (Ok(columns), Ok(indexes)) => Ok((columns.rows, indexes.rows)),
+            // The other kind is only missing when no block is named after its information_schema table
+            (Ok(columns), Err(_)) if !names_source::<IndexInfo>(&columns.blocks) => Ok((columns.rows, Vec::new())),
+            (Err(_), Ok(indexes)) if !names_source::<ColumnInfo>(&indexes.blocks) => Ok((Vec::new(), indexes.rows)),
+            (Err(e), _) | (_, Err(e)) => Err(e),
+        },
+    }
+}
+
+enum JsonKind {
+    Columns,
+    Indexes,
+    PhpMyAdmin,
+}
+
+// Told apart by the first element: phpMyAdmin blocks have a type, index rows an index_name.
+// Anything else is read as columns, whose parser reports what is wrong with it
+fn json_kind(text: &str) -> JsonKind {
+    #[derive(Deserialize)]
+    struct Probe {
+        r#type: Option<IgnoredAny>,
+        index_name: Option<IgnoredAny>,
+    }
+    match serde_json::from_str::<Vec<Probe>>(text).ok().and_then(|elements| elements.into_iter().next()) {
+        Some(Probe { r#type: Some(_), .. }) => JsonKind::PhpMyAdmin,
+        Some(Probe { index_name: Some(_), .. }) => JsonKind::Indexes,
+        _ => JsonKind::Columns,
+    }
+}
+
+fn names_source<T: ExportRow>(blocks: &[ExportBlock]) -> bool {
+    blocks.iter().any(|block| block.name.as_deref().is_some_and(|name| name.eq_ignore_ascii_case(T::SOURCE_TABLE)))
+}
+
+// Decode an upload for the comparison entry points. A single JSON file is passed through as its text,
+// anything that has to be merged or converted (zip archives, SQL dumps) becomes a snapshot document
+pub fn decode_upload(name: &str, bytes: &[u8], encoding: Option<&str>) -> Result<DecodedText, String> {
+    let (compression, files) = decompress(name, bytes)?;
+    let file_names: Vec<String> = files.iter().map(|file| file.name.clone()).collect();
+
+    if let [file] = files.as_slice() {
+        if !file.is_sql() {
+            let decoded = decode_bytes(&file.bytes, encoding)?;
+            return Ok(DecodedText { compression, files: file_names, ..decoded });
+        }
+    }
+
+    let (snapshot, decoded_files) = merge_files(&files, encoding)?;
+    let had_replacements = decoded_files.iter().any(|decoded| decoded.had_replacements);
+    // The encoding reported is the one of the first file
+    let first = decoded_files.into_iter().next().ok_or_else(|| format!("{} holds no files", name))?;
+    let text = serde_json::to_string(&snapshot).map_err(|e| format!("Error serializing snapshot: {}", e))?;
+    Ok(DecodedText { text, had_replacements, compression, files: file_names, ..first })
+}
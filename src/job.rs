@@ -0,0 +1,503 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use serde_json::value::RawValue;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::{JsFuture, future_to_promise};
+use web_sys::AbortSignal;
+use js_sys::{Function, Promise};
+use super::{ColumnInfo, SchemaDiff, compare_table_columns, generate_sql_diff, snapshot, stream};
+use super::index::{IndexDiff, IndexInfo, build_index_map, compare_table_indexes, create_index_info, generate_sql_index_diff};
+use super::js::{db_diff_error, to_js_value};
+use super::model::{SchemaModel, verify_sql};
+use super::output::{IndexComparison, SchemaComparison};
+use super::parallel;
+
+// Work done between two progress reports, small enough to keep a tab responsive
+const ROWS_PER_STEP: usize = 10_000;
+const TABLES_PER_STEP: usize = 500;
+// The self-check builds the model of each side, then applies both scripts
+const VERIFY_STEPS: u64 = 3;
+
+type IndexMapByTable = HashMap<String, IndexMap<String, Vec<IndexInfo>>>;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    // Bytes of an uploaded file read by the FileReader
+    Read,
+    // Rows of the first / second export parsed
+    ParseFirst,
+    ParseSecond,
+    // Tables compared
+    Compare,
+    // SQL generated from the diff
+    Sql,
+    // Steps of the self-check, see VERIFY_STEPS
+    Verify,
+    Done,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    pub phase: Phase,
+    pub done: u64,
+    // 0 when the total is not known, e.g. a read without a Content-Length
+    pub total: u64,
+}
+
+// Where the rows of one kind of export are collected while it is read a slice at a time
+trait Rows: Default {
+    type Row: DeserializeOwned;
+    type Map;
+    fn insert(&mut self, row: Self::Row);
+    fn finish(self) -> Self::Map;
+    // Snapshots and phpMyAdmin exports are read in one go, with the number of rows read
+    fn read_whole(json: &str) -> Result<(Self::Map, usize), String>;
+}
+
+impl Rows for stream::SchemaMapBuilder {
+    type Row = ColumnInfo;
+    type Map = HashMap<String, IndexMap<String, ColumnInfo>>;
+
+    fn insert(&mut self, row: ColumnInfo) {
+        stream::SchemaMapBuilder::insert(self, row);
+    }
+
+    fn finish(self) -> Self::Map {
+        stream::SchemaMapBuilder::finish(self)
+    }
+
+    fn read_whole(json: &str) -> Result<(Self::Map, usize), String> {
+        let map = stream::schema_map_from_json(json)?;
+        let columns = map.values().map(IndexMap::len).sum();
+        Ok((map, columns))
+    }
+}
+
+// Index rows are grouped by table and index once all of them are read
+impl Rows for Vec<IndexInfo> {
+    type Row = IndexInfo;
+    type Map = IndexMapByTable;
+
+    fn insert(&mut self, row: IndexInfo) {
+        self.push(row);
+    }
+
+    fn finish(self) -> Self::Map {
+        build_index_map(self)
+    }
+
+    fn read_whole(json: &str) -> Result<(Self::Map, usize), String> {
+        let indexes = create_index_info(json)?;
+        let count = indexes.len();
+        Ok((build_index_map(indexes), count))
+    }
+}
+
+// One export being read into its map a slice of rows at a time
+struct Side<R: Rows> {
+    json: String,
+    // Byte ranges of the rows of a plain export, None until the first step
+    rows: Option<Vec<Range<usize>>>,
+    next: usize,
+    builder: R,
+    map: Option<R::Map>,
+    rows_read: usize,
+}
+
+impl<R: Rows> Side<R> {
+    fn new(json: String) -> Self {
+        Side { json, rows: None, next: 0, builder: R::default(), map: None, rows_read: 0 }
+    }
+
+    fn parse_step(&mut self, budget: usize) -> Result<(u64, u64), String> {
+        if self.map.is_none() && self.rows.is_none() {
+            self.rows = row_ranges::<R::Row>(&self.json);
+            if self.rows.is_none() {
+                return self.read_whole();
+            }
+        }
+        let Some(rows) = &self.rows else {
+            return Ok((self.rows_read as u64, self.rows_read as u64));
+        };
+
+        let end = rows.len().min(self.next + budget);
+        for range in &rows[self.next..end] {
+            match serde_json::from_str::<R::Row>(&self.json[range.clone()]) {
+                Ok(row) => self.builder.insert(row),
+                // Let the one-pass reader produce the same result or error as the synchronous entry point
+                Err(_) => return self.read_whole(),
+            }
+        }
+        self.next = end;
+        let total = rows.len();
+        if end == total {
+            self.map = Some(std::mem::take(&mut self.builder).finish());
+            self.rows = None;
+            self.rows_read = total;
+        }
+        Ok((end as u64, total as u64))
+    }
+
+    fn read_whole(&mut self) -> Result<(u64, u64), String> {
+        let (map, rows_read) = R::read_whole(&self.json)?;
+        self.map = Some(map);
+        self.rows = None;
+        self.rows_read = rows_read;
+        Ok((rows_read as u64, rows_read as u64))
+    }
+}
+
+// Where each row of a plain export starts and ends, None for other kinds of input
+fn row_ranges<T: DeserializeOwned>(json: &str) -> Option<Vec<Range<usize>>> {
+    if snapshot::is_snapshot(json) {
+        return None;
+    }
+    let rows: Vec<&RawValue> = serde_json::from_str(json).ok()?;
+    // The first element of a phpMyAdmin export is a header or table block, not a row
+    if rows.first().is_some_and(|row| serde_json::from_str::<T>(row.get()).is_err()) {
+        return None;
+    }
+    let base = json.as_ptr() as usize;
+    Some(
+        rows.iter()
+            .map(|row| {
+                let start = row.get().as_ptr() as usize - base;
+                start..start + row.get().len()
+            })
+            .collect(),
+    )
+}
+
+// SQL and self-check of a finished diff, the last phases of both jobs
+#[derive(Default)]
+struct Check {
+    sql: HashMap<String, String>,
+    model1: Option<SchemaModel>,
+    model2: Option<SchemaModel>,
+    verification_errors: Vec<String>,
+}
+
+impl Check {
+    // Build the model of the first side, then of the second, then apply both scripts and drop the
+    // models. Returns the number of steps done out of VERIFY_STEPS
+    fn verify_step(&mut self, model: impl FnOnce(bool) -> SchemaModel) -> u64 {
+        if self.model1.is_none() {
+            self.model1 = Some(model(true));
+            return 1;
+        }
+        if self.model2.is_none() {
+            self.model2 = Some(model(false));
+            return 2;
+        }
+        if let (Some(model1), Some(model2)) = (self.model1.take(), self.model2.take()) {
+            self.verification_errors = verify_sql(&model1, &model2, &self.sql).err().unwrap_or_default();
+        }
+        VERIFY_STEPS
+    }
+}
+
+// compare_schemas split into steps, so that a caller can report progress and stop between them.
+// Each step parses a slice of rows, compares a slice of tables, generates the SQL or does one
+// step of the self-check
+pub struct ComparisonJob {
+    phase: Phase,
+    first: Side<stream::SchemaMapBuilder>,
+    second: Side<stream::SchemaMapBuilder>,
+    table_keys: Vec<String>,
+    next_table: usize,
+    diff: SchemaDiff,
+    // Tables the self-check models, the ones with a difference
+    model_tables: HashSet<String>,
+    check: Check,
+    rows_per_step: usize,
+    tables_per_step: usize,
+}
+
+impl ComparisonJob {
+    pub fn new(json1: String, json2: String) -> Self {
+        ComparisonJob {
+            phase: Phase::ParseFirst,
+            first: Side::new(json1),
+            second: Side::new(json2),
+            table_keys: Vec::new(),
+            next_table: 0,
+            diff: SchemaDiff::default(),
+            model_tables: HashSet::new(),
+            check: Check::default(),
+            rows_per_step: ROWS_PER_STEP,
+            tables_per_step: TABLES_PER_STEP,
+        }
+    }
+
+    pub fn step_size(mut self, rows: usize, tables: usize) -> Self {
+        self.rows_per_step = rows.max(1);
+        self.tables_per_step = tables.max(1);
+        self
+    }
+
+    // The phase the next step works on, or the one that failed
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.phase == Phase::Done
+    }
+
+    pub fn step(&mut self) -> Result<Progress, String> {
+        let phase = self.phase;
+        let (done, total) = match phase {
+            Phase::ParseFirst | Phase::ParseSecond => {
+                let side = if phase == Phase::ParseFirst { &mut self.first } else { &mut self.second };
+                let (done, total) = side.parse_step(self.rows_per_step)?;
+                if side.map.is_some() {
+                    self.phase = if phase == Phase::ParseFirst { Phase::ParseSecond } else { Phase::Compare };
+                }
+                (done, total)
+            }
+            Phase::Compare => {
+                let (Some(map1), Some(map2)) = (&self.first.map, &self.second.map) else {
+                    return Err("Exports are not parsed".to_string());
+                };
+                if self.next_table == 0 && self.table_keys.is_empty() {
+                    self.table_keys = parallel::table_keys(map1, map2).into_iter().cloned().collect();
+                }
+                let end = self.table_keys.len().min(self.next_table + self.tables_per_step);
+                let keys: Vec<&String> = self.table_keys[self.next_table..end].iter().collect();
+                for table in parallel::map_tables(&keys, |table_key| compare_table_columns(map1.get(table_key), map2.get(table_key))) {
+                    self.diff.append(table);
+                }
+                self.next_table = end;
+                if end == self.table_keys.len() {
+                    self.phase = Phase::Sql;
+                }
+                (end as u64, self.table_keys.len() as u64)
+            }
+            Phase::Sql => {
+                self.check.sql = generate_sql_diff(&self.diff);
+                self.model_tables = self.diff.table_keys();
+                self.phase = Phase::Verify;
+                (1, 1)
+            }
+            Phase::Verify => {
+                let (Some(map1), Some(map2)) = (&self.first.map, &self.second.map) else {
+                    return Err("Exports are not parsed".to_string());
+                };
+                let model_tables = &self.model_tables;
+                let done = self.check.verify_step(|first| SchemaModel::from_schema_map_tables(if first { map1 } else { map2 }, model_tables));
+                if done == VERIFY_STEPS {
+                    self.phase = Phase::Done;
+                }
+                (done, VERIFY_STEPS)
+            }
+            Phase::Read | Phase::Done => (0, 0),
+        };
+        Ok(Progress { phase, done, total })
+    }
+
+    // Run the remaining steps and build the result compare_schemas would return
+    pub fn finish(mut self) -> Result<SchemaComparison, String> {
+        while !self.is_done() {
+            self.step()?;
+        }
+        Ok(SchemaComparison::new(self.diff, self.check.sql, self.check.verification_errors))
+    }
+}
+
+// compare_indexs_value split into the same steps as ComparisonJob
+pub struct IndexComparisonJob {
+    phase: Phase,
+    first: Side<Vec<IndexInfo>>,
+    second: Side<Vec<IndexInfo>>,
+    table_keys: Vec<String>,
+    next_table: usize,
+    diff: IndexDiff,
+    check: Check,
+    rows_per_step: usize,
+    tables_per_step: usize,
+}
+
+impl IndexComparisonJob {
+    pub fn new(json1: String, json2: String) -> Self {
+        IndexComparisonJob {
+            phase: Phase::ParseFirst,
+            first: Side::new(json1),
+            second: Side::new(json2),
+            table_keys: Vec::new(),
+            next_table: 0,
+            diff: IndexDiff::default(),
+            check: Check::default(),
+            rows_per_step: ROWS_PER_STEP,
+            tables_per_step: TABLES_PER_STEP,
+        }
+    }
+
+    pub fn step_size(mut self, rows: usize, tables: usize) -> Self {
+        self.rows_per_step = rows.max(1);
+        self.tables_per_step = tables.max(1);
+        self
+    }
+
+    // The phase the next step works on, or the one that failed
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.phase == Phase::Done
+    }
+
+    pub fn step(&mut self) -> Result<Progress, String> {
+        let phase = self.phase;
+        let (done, total) = match phase {
+            Phase::ParseFirst | Phase::ParseSecond => {
+                let side = if phase == Phase::ParseFirst { &mut self.first } else { &mut self.second };
+                let (done, total) = side.parse_step(self.rows_per_step)?;
+                if side.map.is_some() {
+                    self.phase = if phase == Phase::ParseFirst { Phase::ParseSecond } else { Phase::Compare };
+                }
+                (done, total)
+            }
+            Phase::Compare => {
+                let (Some(map1), Some(map2)) = (&self.first.map, &self.second.map) else {
+                    return Err("Exports are not parsed".to_string());
+                };
+                if self.next_table == 0 && self.table_keys.is_empty() {
+                    self.table_keys = parallel::table_keys(map1, map2).into_iter().cloned().collect();
+                }
+                let end = self.table_keys.len().min(self.next_table + self.tables_per_step);
+                let keys: Vec<&String> = self.table_keys[self.next_table..end].iter().collect();
+                for table in parallel::map_tables(&keys, |table_key| compare_table_indexes(map1.get(table_key), map2.get(table_key))) {
+                    self.diff.append(table);
+                }
+                self.next_table = end;
+                if end == self.table_keys.len() {
+                    self.phase = Phase::Sql;
+                }
+                (end as u64, self.table_keys.len() as u64)
+            }
+            Phase::Sql => {
+                self.check.sql = generate_sql_index_diff(&self.diff);
+                self.phase = Phase::Verify;
+                (1, 1)
+            }
+            Phase::Verify => {
+                let (Some(map1), Some(map2)) = (&self.first.map, &self.second.map) else {
+                    return Err("Exports are not parsed".to_string());
+                };
+                let done = self.check.verify_step(|first| {
+                    let map = if first { map1 } else { map2 };
+                    SchemaModel::new().with_indexes(map.values().flat_map(|indexes| indexes.values().flatten()))
+                });
+                if done == VERIFY_STEPS {
+                    self.phase = Phase::Done;
+                }
+                (done, VERIFY_STEPS)
+            }
+            Phase::Read | Phase::Done => (0, 0),
+        };
+        Ok(Progress { phase, done, total })
+    }
+
+    // Run the remaining steps and build the result compare_indexs_value would return
+    pub fn finish(mut self) -> Result<IndexComparison, String> {
+        while !self.is_done() {
+            self.step()?;
+        }
+        Ok(IndexComparison::new(self.diff, self.check.sql, self.check.verification_errors))
+    }
+}
+
+// Same result as compare_schemas_value without blocking the thread it runs on. Between steps the
+// promise yields to the event loop, calls on_progress with a Progress and checks the AbortSignal,
+// rejecting with a DbDiffError of kind "aborted" once it fires. Works in a Web Worker as well
+#[wasm_bindgen(unchecked_return_type = "Promise<SchemaComparison>")]
+pub fn compare_schemas_async(
+    json1: String,
+    json2: String,
+    #[wasm_bindgen(unchecked_param_type = "((progress: Progress) => void) | undefined")] on_progress: Option<Function>,
+    signal: Option<AbortSignal>,
+) -> Promise {
+    future_to_promise(async move {
+        let mut job = ComparisonJob::new(json1, json2);
+        loop {
+            check_aborted(signal.as_ref())?;
+            let progress = job.step().map_err(|e| input_error(job.phase(), &e))?;
+            report_progress(on_progress.as_ref(), &progress)?;
+            if job.is_done() {
+                break;
+            }
+            yield_to_event_loop().await;
+        }
+        let result = job.finish().map_err(|e| db_diff_error("first_input", &e))?;
+        report_progress(on_progress.as_ref(), &Progress { phase: Phase::Done, done: 1, total: 1 })?;
+        to_js_value(&result)
+    })
+}
+
+// Same result as compare_indexs_value, with the progress and abort handling of compare_schemas_async
+#[wasm_bindgen(unchecked_return_type = "Promise<IndexComparison>")]
+pub fn compare_indexs_async(
+    json1: String,
+    json2: String,
+    #[wasm_bindgen(unchecked_param_type = "((progress: Progress) => void) | undefined")] on_progress: Option<Function>,
+    signal: Option<AbortSignal>,
+) -> Promise {
+    future_to_promise(async move {
+        let mut job = IndexComparisonJob::new(json1, json2);
+        loop {
+            check_aborted(signal.as_ref())?;
+            let progress = job.step().map_err(|e| input_error(job.phase(), &e))?;
+            report_progress(on_progress.as_ref(), &progress)?;
+            if job.is_done() {
+                break;
+            }
+            yield_to_event_loop().await;
+        }
+        let result = job.finish().map_err(|e| db_diff_error("first_input", &e))?;
+        report_progress(on_progress.as_ref(), &Progress { phase: Phase::Done, done: 1, total: 1 })?;
+        to_js_value(&result)
+    })
+}
+
+fn check_aborted(signal: Option<&AbortSignal>) -> Result<(), JsValue> {
+    if signal.is_some_and(AbortSignal::aborted) {
+        return Err(db_diff_error("aborted", "Comparison aborted"));
+    }
+    Ok(())
+}
+
+// Only parsing fails, on the side the job was reading
+fn input_error(phase: Phase, message: &str) -> JsValue {
+    match phase {
+        Phase::ParseSecond => db_diff_error("second_input", message),
+        _ => db_diff_error("first_input", message),
+    }
+}
+
+pub(crate) fn report_progress(on_progress: Option<&Function>, progress: &Progress) -> Result<(), JsValue> {
+    match on_progress {
+        Some(callback) => callback.call1(&JsValue::NULL, &to_js_value(progress)?).map(|_| ()),
+        None => Ok(()),
+    }
+}
+
+// A macrotask boundary, so that pending events such as an abort() are handled before the next step.
+// setTimeout is looked up on globalThis, which is the window in a page and the scope in a worker
+async fn yield_to_event_loop() {
+    let promise = Promise::new(&mut |resolve, _reject| {
+        let global = js_sys::global();
+        let set_timeout = js_sys::Reflect::get(&global, &JsValue::from_str("setTimeout"))
+            .ok()
+            .and_then(|set_timeout| set_timeout.dyn_into::<Function>().ok());
+        let scheduled = set_timeout.is_some_and(|set_timeout| set_timeout.call2(&global, &resolve, &JsValue::from(0)).is_ok());
+        if !scheduled {
+            let _ = resolve.call0(&JsValue::NULL);
+        }
+    });
+    let _ = JsFuture::from(promise).await;
+}
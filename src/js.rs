@@ -0,0 +1,211 @@
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+use super::{compare_columns, create_column_info};
+use super::ddl_diff::{parse_columns, parse_indexes};
+use super::full::compare_columns_and_indexes;
+use super::index::{compare_indexes, create_index_info};
+
+// Hand written to match the serde output of the types in output.rs,
+// tests/js_value_test.rs checks it against the generated JSON Schema
+pub const TYPESCRIPT_DEFINITIONS: &str = r#"
+export interface ColumnInfo {
+    table_schema: string;
+    table_name: string;
+    column_name: string;
+    data_type: string;
+    column_type: string;
+    is_nullable: string;
+    column_default: string | null;
+    extra: string | null;
+    column_comment: string | null;
+    character_maximum_length: number | null;
+    character_set_name: string | null;
+    collation_name: string | null;
+}
+
+export interface TableDefinition {
+    schema: string;
+    table: string;
+    definition: string;
+}
+
+export interface ColumnDifference {
+    table_schema: string;
+    table_name: string;
+    column_name: string;
+    first: ColumnInfo;
+    second: ColumnInfo;
+}
+
+export interface SchemaDiff {
+    tables_only_in_first: TableDefinition[];
+    tables_only_in_second: TableDefinition[];
+    columns_only_in_first: ColumnInfo[];
+    columns_only_in_second: ColumnInfo[];
+    columns_with_different_definitions: ColumnDifference[];
+}
+
+export interface IndexInfo {
+    table_schema: string;
+    table_name: string;
+    index_name: string;
+    column_name: string;
+    seq_in_index: number | null;
+    collation: string | null;
+    cardinality: number | null;
+    sub_part: number | null;
+    packed: string | null;
+    nullable: string;
+    index_type: string;
+    non_unique: number | null;
+}
+
+export interface IndexDifference {
+    table_schema: string;
+    table_name: string;
+    index_name: string;
+    first: IndexInfo;
+    second: IndexInfo;
+}
+
+export interface IndexRename {
+    table_schema: string;
+    table_name: string;
+    old_index_name: string;
+    new_index_name: string;
+}
+
+export interface IndexDiff {
+    indexes_only_in_first: IndexInfo[];
+    indexes_only_in_second: IndexInfo[];
+    indexes_with_different_definitions: IndexDifference[];
+    indexes_renamed: IndexRename[];
+}
+
+export interface SchemaComparison {
+    format_version: number;
+    diff: SchemaDiff;
+    sql: Record<string, string>;
+    verification_errors: string[];
+}
+
+export interface IndexComparison {
+    format_version: number;
+    diff: IndexDiff;
+    sql: Record<string, string>;
+    verification_errors: string[];
+}
+
+export type TableStatus = "only_in_first" | "only_in_second" | "changed";
+
+export interface TableComparison {
+    schema: string;
+    table: string;
+    status: TableStatus;
+    columns_only_in_first: ColumnInfo[];
+    columns_only_in_second: ColumnInfo[];
+    columns_with_different_definitions: ColumnDifference[];
+    indexes_only_in_first: IndexInfo[];
+    indexes_only_in_second: IndexInfo[];
+    indexes_with_different_definitions: IndexDifference[];
+    indexes_renamed: IndexRename[];
+    ddl_diff: string;
+    forward_sql: string[];
+    reverse_sql: string[];
+}
+
+export interface FullComparison {
+    format_version: number;
+    tables: TableComparison[];
+    forward_script: string;
+    reverse_script: string;
+    verification_errors: string[];
+}
+
+export interface SnapshotMetadata {
+    flavor: string | null;
+    server_version: string | null;
+    captured_at: string | null;
+    host: string | null;
+    content_hash: string | null;
+}
+
+export interface SnapshotComparison {
+    format_version: number;
+    first: SnapshotMetadata;
+    second: SnapshotMetadata;
+    comparison: FullComparison;
+}
+
+export interface DecodedText {
+    text: string;
+    encoding: string;
+    detected_by: "override" | "bom" | "utf8" | "heuristic";
+    had_replacements: boolean;
+    compression: "none" | "gzip" | "zip";
+    files: string[];
+}
+
+export interface Progress {
+    phase: "read" | "parse_first" | "parse_second" | "compare" | "sql" | "verify" | "done";
+    done: number;
+    total: number;
+}
+
+export type DbDiffErrorKind = "first_input" | "second_input" | "serialize" | "encoding" | "input" | "aborted";
+
+export interface DbDiffError extends Error {
+    name: "DbDiffError";
+    kind: DbDiffErrorKind;
+}
+"#;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_APPEND_CONTENT: &'static str = TYPESCRIPT_DEFINITIONS;
+
+// Same result as compare_schemas, as a plain JS object instead of a JSON string
+#[wasm_bindgen(unchecked_return_type = "SchemaComparison")]
+pub fn compare_schemas_value(json1: &str, json2: &str) -> Result<JsValue, JsValue> {
+    let columns1 = create_column_info(json1).map_err(|e| db_diff_error("first_input", &e))?;
+    let columns2 = create_column_info(json2).map_err(|e| db_diff_error("second_input", &e))?;
+    to_js_value(&compare_columns(columns1, columns2))
+}
+
+// Same result as compare_indexs, but invalid input throws instead of being compared as empty
+#[wasm_bindgen(unchecked_return_type = "IndexComparison")]
+pub fn compare_indexs_value(json1: &str, json2: &str) -> Result<JsValue, JsValue> {
+    let indexes1 = create_index_info(json1).map_err(|e| db_diff_error("first_input", &e))?;
+    let indexes2 = create_index_info(json2).map_err(|e| db_diff_error("second_input", &e))?;
+    to_js_value(&compare_indexes(indexes1, indexes2))
+}
+
+// Same result as compare_full, either export pair may be empty
+#[wasm_bindgen(unchecked_return_type = "FullComparison")]
+pub fn compare_full_value(
+    columns_json1: &str,
+    columns_json2: &str,
+    indexes_json1: &str,
+    indexes_json2: &str
+) -> Result<JsValue, JsValue> {
+    let columns1 = parse_columns(columns_json1).map_err(|e| db_diff_error("first_input", &e))?;
+    let columns2 = parse_columns(columns_json2).map_err(|e| db_diff_error("second_input", &e))?;
+    let indexes1 = parse_indexes(indexes_json1).map_err(|e| db_diff_error("first_input", &e))?;
+    let indexes2 = parse_indexes(indexes_json2).map_err(|e| db_diff_error("second_input", &e))?;
+    to_js_value(&compare_columns_and_indexes(columns1, columns2, indexes1, indexes2))
+}
+
+pub(crate) fn to_js_value<T: Serialize>(value: &T) -> Result<JsValue, JsValue> {
+    // Plain objects for maps and null for None, the same shape JSON.parse would give
+    let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+    value
+        .serialize(&serializer)
+        .map_err(|e| db_diff_error("serialize", &format!("Error serializing result: {}", e)))
+}
+
+pub(crate) fn db_diff_error(kind: &str, message: &str) -> JsValue {
+    let error = js_sys::Error::new(message);
+    error.set_name("DbDiffError");
+    // Setting a property on a fresh Error object cannot fail
+    let _ = js_sys::Reflect::set(&error, &JsValue::from_str("kind"), &JsValue::from_str(kind));
+    error.into()
+}
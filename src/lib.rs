@@ -1,14 +1,43 @@
 use serde::{Deserialize, Deserializer, Serialize};
-use serde_json;
+use schemars::JsonSchema;
 use wasm_bindgen::prelude::*;
 use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
 use indexmap::IndexMap;
-use web_sys::{File, FileReader};
-use wasm_bindgen::JsCast;
+use web_sys::File;
 use js_sys::Promise;
-use encoding_rs;
 
+pub mod baseline;
+pub mod ci;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod cli;
+pub mod ddl_diff;
+pub mod encoding;
+pub mod full;
+pub mod history;
 pub mod index;
+pub mod index_analysis;
+pub mod input;
+pub mod job;
+pub mod js;
+pub mod lint;
+#[cfg(all(feature = "mysql", not(target_arch = "wasm32")))]
+pub mod live;
+#[cfg(all(feature = "postgres", not(target_arch = "wasm32")))]
+pub mod live_postgres;
+pub mod merge;
+pub mod migration;
+pub mod model;
+pub mod naming;
+pub mod online;
+pub mod output;
+mod parallel;
+pub mod phpmyadmin;
+pub mod report;
+pub mod risk;
+pub mod snapshot;
+pub mod sql_dump;
+pub mod stream;
 // Custom deserializer to handle both string and integer representations
 fn deserialize_optional_string_as_int<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
 where
@@ -36,7 +65,7 @@ where
 }
 
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, JsonSchema)]
 pub struct ColumnInfo {
     pub table_schema: String,
     pub table_name: String,
@@ -49,6 +78,42 @@ pub struct ColumnInfo {
     pub column_comment: Option<String>,
     #[serde(deserialize_with = "deserialize_optional_string_as_int")]
     pub character_maximum_length: Option<u64>,
+    #[serde(default)]
+    pub character_set_name: Option<String>,
+    #[serde(default)]
+    pub collation_name: Option<String>,
+}
+
+// Character set and collation are only read by the lint rules. format_column_definition does not
+// render them, so they stay out of the comparison or a collation change would emit a no-op MODIFY
+impl PartialEq for ColumnInfo {
+    fn eq(&self, other: &Self) -> bool {
+        self.table_schema == other.table_schema
+            && self.table_name == other.table_name
+            && self.column_name == other.column_name
+            && self.data_type == other.data_type
+            && self.column_type == other.column_type
+            && self.is_nullable == other.is_nullable
+            && self.column_default == other.column_default
+            && self.extra == other.extra
+            && self.column_comment == other.column_comment
+            && self.character_maximum_length == other.character_maximum_length
+    }
+}
+
+impl Hash for ColumnInfo {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.table_schema.hash(state);
+        self.table_name.hash(state);
+        self.column_name.hash(state);
+        self.data_type.hash(state);
+        self.column_type.hash(state);
+        self.is_nullable.hash(state);
+        self.column_default.hash(state);
+        self.extra.hash(state);
+        self.column_comment.hash(state);
+        self.character_maximum_length.hash(state);
+    }
 }
 
 impl ColumnInfo{
@@ -64,6 +129,8 @@ impl ColumnInfo{
             extra: None,
             character_maximum_length: None,
             column_comment: None,
+            character_set_name: None,
+            collation_name: None,
         }
     }
 
@@ -87,6 +154,16 @@ impl ColumnInfo{
         self
     }
 
+    pub fn set_character_set_name(mut self, data: String) -> Self {
+        self.character_set_name = Some(data);
+        self
+    }
+
+    pub fn set_collation_name(mut self, data: String) -> Self {
+        self.collation_name = Some(data);
+        self
+    }
+
 }
 
 #[cfg(test)]
@@ -126,18 +203,39 @@ pub struct MariaDBJson <T> {
     pub data: Option<Vec<T>>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Default, JsonSchema)]
 pub struct SchemaDiff {
-    pub tables_only_in_first: Vec<(String, String)>, // (schema, table_name)
-    pub tables_only_in_second: Vec<(String, String)>, // (schema, table_name)
+    /// Tables that only exist in the first export
+    pub tables_only_in_first: Vec<TableDefinition>,
+    /// Tables that only exist in the second export
+    pub tables_only_in_second: Vec<TableDefinition>,
+    /// Columns of common tables that only exist in the first export
     pub columns_only_in_first: Vec<ColumnInfo>,
+    /// Columns of common tables that only exist in the second export
     pub columns_only_in_second: Vec<ColumnInfo>,
+    /// Columns present on both sides with any differing property
     pub columns_with_different_definitions: Vec<ColumnDifference>,
 }
 
+// Table that exists on one side only, with its column definitions joined by ","
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct TableDefinition {
+    pub schema: String,
+    pub table: String,
+    /// Column definitions as used in CREATE TABLE, each followed by ",", e.g. "id int(11) NOT NULL,"
+    pub definition: String,
+}
+
+impl TableDefinition {
+    // `schema`.table, the key used by build_schema_map and in the generated SQL
+    pub fn key(&self) -> String {
+        format!("`{}`.{}", self.schema, self.table)
+    }
+}
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
 pub struct ColumnDifference {
+    pub table_schema: String,
     pub table_name: String,
     pub column_name: String,
     pub first: ColumnInfo,
@@ -146,174 +244,188 @@ pub struct ColumnDifference {
 
 #[wasm_bindgen]
 pub fn compare_schemas(json1: &str, json2: &str) -> String {
-    // Parse the JSON strings into vectors of ColumnInfo
-    let columns1 = match create_column_info(json1) {
-        Ok(column) => column,
+    // Parse the JSON strings straight into <table, column> maps
+    let map1 = match stream::schema_map_from_json(json1) {
+        Ok(map) => map,
         Err(e) => return e,
     };
 
-    let columns2 =  match create_column_info(json2) {
-        Ok(column) => column,
+    let map2 = match stream::schema_map_from_json(json2) {
+        Ok(map) => map,
         Err(e) => return e,
     };
 
+    let result = compare_column_maps(&map1, &map2);
+
+    // Serialize the result to JSON
+    match serde_json::to_string(&result) {
+        Ok(result) => result,
+        Err(e) => format!("Error serializing result: {}", e),
+    }
+}
+
+// Diff, SQL and self-check of two column exports, shared by the wasm exports
+pub fn compare_columns(columns1: Vec<ColumnInfo>, columns2: Vec<ColumnInfo>) -> output::SchemaComparison {
     // Convert to maps for easier comparison
     // Create <table, column>
-    let map1 = build_schema_map(columns1);
-    let map2 = build_schema_map(columns2);
+    compare_column_maps(&build_schema_map(columns1), &build_schema_map(columns2))
+}
 
+pub fn compare_column_maps(
+    map1: &HashMap<String, IndexMap<String, ColumnInfo>>,
+    map2: &HashMap<String, IndexMap<String, ColumnInfo>>
+) -> output::SchemaComparison {
     // Perform the comparison
-    let diff = compare_schema_maps(&map1, &map2);
+    let diff = compare_schema_maps(map1, map2);
+    schema_comparison(map1, map2, diff)
+}
+
+// SQL and self-check for a diff of the two maps
+pub(crate) fn schema_comparison(
+    map1: &HashMap<String, IndexMap<String, ColumnInfo>>,
+    map2: &HashMap<String, IndexMap<String, ColumnInfo>>,
+    diff: SchemaDiff
+) -> output::SchemaComparison {
+    // Models used to check that the generated SQL really turns one schema into the other. The SQL
+    // only touches the tables of the diff and every other table is the same on both sides
+    let table_keys = diff.table_keys();
+    let model1 = model::SchemaModel::from_schema_map_tables(map1, &table_keys);
+    let model2 = model::SchemaModel::from_schema_map_tables(map2, &table_keys);
 
     // Generate SQL diff statements
     let sql_statements = generate_sql_diff(&diff);
 
-    // Create a result object that includes both the diff and SQL statements
-    let result = serde_json::json!({
-        "diff": diff,
-        "sql": sql_statements
-    });
+    let verification_errors = model::verify_sql(&model1, &model2, &sql_statements).err().unwrap_or_default();
 
-    // Serialize the result to JSON
-    match serde_json::to_string(&result) {
-        Ok(result) => result,
-        Err(e) => format!("Error serializing result: {}", e),
-    }
+    // Create a result object that includes both the diff and SQL statements
+    output::SchemaComparison::new(diff, sql_statements, verification_errors)
 }
 
 pub fn create_column_info(json: &str) -> Result<Vec<ColumnInfo>, String>{
+     if snapshot::is_snapshot(json) {
+         return snapshot::load_snapshot(json).map(|snapshot| snapshot.columns);
+     }
      match serde_json::from_str::<Vec<ColumnInfo>>(json) {
-        Ok(columns) => return Ok(columns),
+        Ok(columns) => Ok(columns),
         Err(_) => {
-            match serde_json::from_str::<Vec<MariaDBJson<ColumnInfo>>>(json){
-                Ok(column) => {
-                        for i in 1..column.len(){
-                            if column[i].data.is_some() {return Ok(column[i].data.clone().unwrap());}
-                        }
-                        return Err("Cannot find data".to_string());
-                },
-                Err(e) => return Err(format!("Error parsing first JSON: {}", e)),
-            }
+            // phpMyAdmin / MariaDB export with header, database and table blocks
+            phpmyadmin::parse_export::<ColumnInfo>(json).map(|export| export.rows)
         }
-    };
+    }
 }
 
 pub fn build_schema_map(columns: Vec<ColumnInfo>) -> HashMap<String, IndexMap<String, ColumnInfo>> {
-    let mut schema_map: HashMap<String, IndexMap<String, ColumnInfo>> = HashMap::new();
-
+    // Use table_schema.table_name as the key to distinguish tables with the same name in different schemas
+    let mut builder = stream::SchemaMapBuilder::new();
     for column in columns {
-        // Use table_schema.table_name as the key to distinguish tables with the same name in different schemas
-        let table_key = format!("`{}`.{}", column.table_schema, column.table_name);
-        let column_name = column.column_name.clone();
-
-        schema_map
-            .entry(table_key)
-            .or_insert_with(IndexMap::new)
-            .insert(column_name, column);
+        builder.insert(column);
     }
-    schema_map
+    builder.finish()
 }
 
 pub fn compare_schema_maps(
     map1: &HashMap<String, IndexMap<String, ColumnInfo>>,
     map2: &HashMap<String, IndexMap<String, ColumnInfo>>
 ) -> SchemaDiff {
-    // extract key from map1, map2
-    // Kmap1 - Kmap2
-    // Kmap2 - Kmap1
-    let tables1: HashSet<&String> = map1.keys().collect();
-    let tables2: HashSet<&String> = map2.keys().collect();
-
-    // Find tables only in first schema
-    let tables_only_in_first: Vec<String> = tables1.difference(&tables2)
-        .map(|s| s.to_string())
-        .collect();
-
-    // Find tables only in second schema
-    let tables_only_in_second: Vec<String> = tables2.difference(&tables1)
-        .map(|s| s.to_string())
-        .collect();
-
-    // Find common tables
-    let common_tables: HashSet<&String> = tables1.intersection(&tables2).cloned().collect();
-
-    let mut columns_only_in_first: Vec<ColumnInfo> = Vec::new();
-    let mut columns_only_in_second: Vec<ColumnInfo> = Vec::new();
-    let mut columns_with_different_definitions: Vec<ColumnDifference> = Vec::new();
-
-    // Compare columns in common tables
-    for table_key in common_tables {
-        let columns1 = &map1[table_key];
-        let columns2 = &map2[table_key];
-
-        let column_names1: HashSet<&String> = columns1.keys().collect();
-        let column_names2: HashSet<&String> = columns2.keys().collect();
-
-        // Columns only in first schema
-        for col_name in column_names1.difference(&column_names2) {
-            columns_only_in_first.push(columns1[*col_name].clone());
-        }
+    // Tables are compared independently, in parallel with the "parallel" feature, and the
+    // results are joined in table key order so the output does not depend on scheduling
+    let table_keys = parallel::table_keys(map1, map2);
+    let tables = parallel::map_tables(&table_keys, |table_key| compare_table_columns(map1.get(table_key), map2.get(table_key)));
+
+    let mut diff = SchemaDiff::default();
+    for table in tables {
+        diff.append(table);
+    }
+    diff
+}
 
-        // Columns only in second schema
-        for col_name in column_names2.difference(&column_names1) {
-            columns_only_in_second.push(columns2[*col_name].clone());
-        }
+impl SchemaDiff {
+    // `schema`.table of every table with a difference
+    pub(crate) fn table_keys(&self) -> HashSet<String> {
+        let tables: HashSet<(&str, &str)> = self
+            .tables_only_in_first
+            .iter()
+            .chain(&self.tables_only_in_second)
+            .map(|table| (table.schema.as_str(), table.table.as_str()))
+            .chain(self.columns_only_in_first.iter().chain(&self.columns_only_in_second).map(|column| (column.table_schema.as_str(), column.table_name.as_str())))
+            .chain(self.columns_with_different_definitions.iter().map(|difference| (difference.table_schema.as_str(), difference.table_name.as_str())))
+            .collect();
+        tables.into_iter().map(|(schema, table)| format!("`{}`.{}", schema, table)).collect()
+    }
 
-        // Compare common columns
-        for col_name in column_names1.intersection(&column_names2) {
-            let col1 = &columns1[*col_name];
-            let col2 = &columns2[*col_name];
+    pub(crate) fn append(&mut self, other: SchemaDiff) {
+        self.tables_only_in_first.extend(other.tables_only_in_first);
+        self.tables_only_in_second.extend(other.tables_only_in_second);
+        self.columns_only_in_first.extend(other.columns_only_in_first);
+        self.columns_only_in_second.extend(other.columns_only_in_second);
+        self.columns_with_different_definitions.extend(other.columns_with_different_definitions);
+    }
+}
 
-            if col1 != col2 {
-                // Extract just the table name (without schema) for the ColumnDifference
-                let table_name = table_key.split('.').nth(1).unwrap_or(table_key).to_string();
+// The part of the diff that concerns one table.
+// Only rows that end up in the diff are cloned, everything else is compared by reference
+pub(crate) fn compare_table_columns(
+    columns1: Option<&IndexMap<String, ColumnInfo>>,
+    columns2: Option<&IndexMap<String, ColumnInfo>>
+) -> SchemaDiff {
+    let mut diff = SchemaDiff::default();
+    let (columns1, columns2) = match (columns1, columns2) {
+        (Some(columns1), Some(columns2)) => (columns1, columns2),
+        // Table only in first schema
+        (Some(columns1), None) => {
+            diff.tables_only_in_first.push(table_definition(columns1));
+            return diff;
+        }
+        // Table only in second schema
+        (None, Some(columns2)) => {
+            diff.tables_only_in_second.push(table_definition(columns2));
+            return diff;
+        }
+        (None, None) => return diff,
+    };
 
-                columns_with_different_definitions.push(ColumnDifference {
-                    table_name,
-                    column_name: (*col_name).clone(),
+    for (col_name, col1) in columns1 {
+        match columns2.get(col_name) {
+            // Columns only in first schema
+            None => diff.columns_only_in_first.push(col1.clone()),
+            // Compare common columns
+            Some(col2) if col1 != col2 => {
+                diff.columns_with_different_definitions.push(ColumnDifference {
+                    table_schema: col1.table_schema.clone(),
+                    table_name: col1.table_name.clone(),
+                    column_name: col_name.clone(),
                     first: col1.clone(),
                     second: col2.clone(),
                 });
             }
+            Some(_) => {}
         }
     }
 
-    // For tables that exist only in one schema, we need to extract both schema and table name
-    let tables_only_in_first_with_schema: Vec<(String, String)> = tables_only_in_first
-        .iter()
-        .map(|key| {
-            // create fieldname
-            // [0: db name, 1:table name] -> [0: db_name.table_name, 1:sql field]
-            let table = map1.get(key).expect("get column info");
-            let sql = generate_sql_create_table(table);
-            (key.clone(), sql.expect("translate data to sql"))
-        })
-        .collect();
-
-    let tables_only_in_second_with_schema: Vec<(String, String)> = tables_only_in_second
-        .iter()
-        .map(|key| {
-            let table = map2.get(key).expect("get column info");
-            let sql = generate_sql_create_table(table);
-            (key.clone(), sql.expect("translate data to sql"))
-        })
-        .collect();
-
-    SchemaDiff {
-        tables_only_in_first: tables_only_in_first_with_schema,
-        tables_only_in_second: tables_only_in_second_with_schema,
-        columns_only_in_first,
-        columns_only_in_second,
-        columns_with_different_definitions,
+    // Columns only in second schema
+    for (col_name, col2) in columns2 {
+        if !columns1.contains_key(col_name) {
+            diff.columns_only_in_second.push(col2.clone());
+        }
     }
+    diff
 }
 
+    fn table_definition(data: &IndexMap<String, ColumnInfo>) -> TableDefinition {
+        let first = data.values().next().expect("get column info");
+        TableDefinition {
+            schema: first.table_schema.clone(),
+            table: first.table_name.clone(),
+            definition: generate_sql_create_table(data).expect("translate data to sql"),
+        }
+    }
+
     fn generate_sql_create_table(data: &IndexMap<String,ColumnInfo>) -> Option<String>{
         let mut sql = String::new();
         for (_, value) in data {
             // set default for int
-            let mut data = format_column_definition(&value);
-            data.push_str(",");
+            let mut data = format_column_definition(value);
+            data.push(',');
             sql+=&data;
         }
         if sql.is_empty(){
@@ -324,17 +436,17 @@ pub fn compare_schema_maps(
     }
 
     pub fn generate_sql_diff(diff: &SchemaDiff) -> HashMap<String, String> {
-    let mut sql_statements = HashMap::new();
+    let mut sql_statements: HashMap<String, String> = HashMap::new();
 
     // Changes needed to transform schema 1 into schema 2
     // Drop tables that exist only in schema 1
     if !diff.tables_only_in_first.is_empty() {
-        for (table_name, _) in &diff.tables_only_in_first {
-            // println!("{}", table_name);
-            let key = format!("Drop table in Schema 1 (Schema 1 only)");
-            let value = format!("DROP TABLE {};", table_name);
+        for table in &diff.tables_only_in_first {
+            let key = "Drop table in Schema 1 (Schema 1 only)".to_string();
+            let value = format!("DROP TABLE {};", table.key());
             if let Some(existing) = sql_statements.get_mut(&key) {
-                *existing = format!("{}\n{}", existing, value);
+                existing.push('\n');
+                existing.push_str(&value);
             } else {
                 sql_statements.insert(key, value);
             }
@@ -344,11 +456,12 @@ pub fn compare_schema_maps(
     // Create tables that exist only in schema 2
     // please provide collapse CREATE TABLE Field name
     if !diff.tables_only_in_second.is_empty() {
-        for (table_name, table_column) in &diff.tables_only_in_second {
-            let key = format!("Create table in Schema 1 (Schema 2 only)");
-            let value = format!("-- CREATE TABLE {} ({});", table_name, table_column);
+        for table in &diff.tables_only_in_second {
+            let key = "Create table in Schema 1 (Schema 2 only)".to_string();
+            let value = format!("-- CREATE TABLE {} ({});", table.key(), table.definition);
             if let Some(existing) = sql_statements.get_mut(&key) {
-                *existing = format!("{}\n{}", existing, value);
+                existing.push('\n');
+                existing.push_str(&value);
             } else {
                 sql_statements.insert(key, value);
             }
@@ -358,13 +471,14 @@ pub fn compare_schema_maps(
     // Drop columns that exist only in schema 1
     if !diff.columns_only_in_first.is_empty() {
         for column in &diff.columns_only_in_first {
-            let key = format!("Drop column in Schema 1 (Schema 1 only)");
+            let key = "Drop column in Schema 1 (Schema 1 only)".to_string();
             let value = format!(
                 "ALTER TABLE `{}`.{} DROP COLUMN {};",
                 column.table_schema, column.table_name, column.column_name
             );
             if let Some(existing) = sql_statements.get_mut(&key) {
-                *existing = format!("{}\n{}", existing, value);
+                existing.push('\n');
+                existing.push_str(&value);
             } else {
                 sql_statements.insert(key, value);
             }
@@ -375,13 +489,14 @@ pub fn compare_schema_maps(
     if !diff.columns_only_in_second.is_empty() {
         for column in &diff.columns_only_in_second {
             let column_def = format_column_definition(column);
-            let key = format!("Add column in Schema 1 (Schema 2 only)");
+            let key = "Add column in Schema 1 (Schema 2 only)".to_string();
             let value = format!(
                 "ALTER TABLE `{}`.{} ADD COLUMN {};",
                 column.table_schema, column.table_name, column_def
             );
             if let Some(existing) = sql_statements.get_mut(&key) {
-                *existing = format!("{}\n{}", existing, value);
+                existing.push('\n');
+                existing.push_str(&value);
             } else {
                 sql_statements.insert(key, value);
             }
@@ -393,13 +508,14 @@ pub fn compare_schema_maps(
         for diff_item in &diff.columns_with_different_definitions {
             // Generate a MODIFY statement based on the second schema (target)
             let column_def = format_column_definition(&diff_item.second);
-            let key = format!("Modify column in Schema 1");
+            let key = "Modify column in Schema 1".to_string();
             let value = format!(
                 "ALTER TABLE `{}`.{} MODIFY COLUMN {};",
                 diff_item.second.table_schema, diff_item.table_name, column_def
             );
             if let Some(existing) = sql_statements.get_mut(&key) {
-                *existing = format!("{}\n{}", existing, value);
+                existing.push('\n');
+                existing.push_str(&value);
             } else {
                 sql_statements.insert(key, value);
             }
@@ -409,11 +525,12 @@ pub fn compare_schema_maps(
     // Changes needed to transform schema 2 into schema 1 (reverse of above)
     // Drop tables that exist only in schema 2
     if !diff.tables_only_in_second.is_empty() {
-        for (table_name, _) in &diff.tables_only_in_second {
-            let key = format!("Drop table in Schema 2 (Schema 2 only)");
-            let value = format!("DROP TABLE {};", table_name);
+        for table in &diff.tables_only_in_second {
+            let key = "Drop table in Schema 2 (Schema 2 only)".to_string();
+            let value = format!("DROP TABLE {};", table.key());
             if let Some(existing) = sql_statements.get_mut(&key) {
-                *existing = format!("{}\n{}", existing, value);
+                existing.push('\n');
+                existing.push_str(&value);
             } else {
                 sql_statements.insert(key, value);
             }
@@ -422,11 +539,12 @@ pub fn compare_schema_maps(
 
     // Create tables that exist only in schema 1
     if !diff.tables_only_in_first.is_empty() {
-        for (table_name, table_column) in &diff.tables_only_in_first {
-            let key = format!("Create table in Schema 2 (Schema 1 only)");
-            let value = format!("-- CREATE TABLE {} ({});", table_name, table_column);
+        for table in &diff.tables_only_in_first {
+            let key = "Create table in Schema 2 (Schema 1 only)".to_string();
+            let value = format!("-- CREATE TABLE {} ({});", table.key(), table.definition);
             if let Some(existing) = sql_statements.get_mut(&key) {
-                *existing = format!("{}\n{}", existing, value);
+                existing.push('\n');
+                existing.push_str(&value);
             } else {
                 sql_statements.insert(key, value);
             }
@@ -437,13 +555,14 @@ pub fn compare_schema_maps(
     if !diff.columns_only_in_first.is_empty() {
         for column in &diff.columns_only_in_first {
             let column_def = format_column_definition(column);
-            let key = format!("Add column in Schema 2 (Schema 1 only)");
+            let key = "Add column in Schema 2 (Schema 1 only)".to_string();
             let value = format!(
                 "ALTER TABLE `{}`.{} ADD COLUMN {};",
                 column.table_schema, column.table_name, column_def
             );
             if let Some(existing) = sql_statements.get_mut(&key) {
-                *existing = format!("{}\n{}", existing, value);
+                existing.push('\n');
+                existing.push_str(&value);
             } else {
                 sql_statements.insert(key, value);
             }
@@ -453,13 +572,14 @@ pub fn compare_schema_maps(
     // Drop columns that exist only in schema 2
     if !diff.columns_only_in_second.is_empty() {
         for column in &diff.columns_only_in_second {
-            let key = format!("Drop column in Schema 2 (Schema 2 only)");
+            let key = "Drop column in Schema 2 (Schema 2 only)".to_string();
             let value = format!(
                 "ALTER TABLE `{}`.{} DROP COLUMN {};",
                 column.table_schema, column.table_name, column.column_name
             );
             if let Some(existing) = sql_statements.get_mut(&key) {
-                *existing = format!("{}\n{}", existing, value);
+                existing.push('\n');
+                existing.push_str(&value);
             } else {
                 sql_statements.insert(key, value);
             }
@@ -471,13 +591,14 @@ pub fn compare_schema_maps(
         for diff_item in &diff.columns_with_different_definitions {
             // Generate a MODIFY statement based on the first schema (target)
             let column_def = format_column_definition(&diff_item.first);
-            let key = format!("Modify column in Schema 2");
+            let key = "Modify column in Schema 2".to_string();
             let value = format!(
                 "ALTER TABLE `{}`.{} MODIFY COLUMN {};",
                 diff_item.first.table_schema, diff_item.table_name, column_def
             );
             if let Some(existing) = sql_statements.get_mut(&key) {
-                *existing = format!("{}\n{}", existing, value);
+                existing.push('\n');
+                existing.push_str(&value);
             } else {
                 sql_statements.insert(key, value);
             }
@@ -488,7 +609,7 @@ pub fn compare_schema_maps(
     }
 
 
-fn format_column_definition(column: &ColumnInfo) -> String {
+pub(crate) fn format_column_definition(column: &ColumnInfo) -> String {
     // fieldname, datatype, null, default
     let field_name = &column.column_name;
     let column_type = column.column_type.to_lowercase();
@@ -510,7 +631,7 @@ fn format_column_definition(column: &ColumnInfo) -> String {
 
     let comment = match &column.column_comment{
         Some(d) => {
-            if d!="" {
+            if !d.is_empty() {
                 format!(" COMMENT '{}'", d).to_string()
             }else{
                 "".to_string()
@@ -524,36 +645,17 @@ fn format_column_definition(column: &ColumnInfo) -> String {
 }
 
 #[wasm_bindgen]
-pub fn process_uploaded_file(file: File) -> Promise {
-    let file_reader = FileReader::new().unwrap();
-
-    // Create a promise to handle the async operation
-    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
-        let file_reader_clone = file_reader.clone();
-        let onloaded_closure = Closure::wrap(Box::new(move |_event: web_sys::ProgressEvent| {
-            let result = file_reader_clone.result().unwrap();
-            let array_buffer = result.dyn_into::<js_sys::ArrayBuffer>().unwrap();
-            let data = js_sys::Uint8Array::new(&array_buffer).to_vec();
-
-            // Try to decode as UTF-8 first For Thai decoder
-            let rust_string = match String::from_utf8(data.clone()) {
-                Ok(s) => s,
-                Err(_) => {
-                    // If UTF-8 fails, try to decode as WINDOWS_874 (which covers TIS-620 for Thai)
-                    let (cow, _encoding_used, _had_errors) = encoding_rs::WINDOWS_874.decode(&data);
-                    cow.into_owned()
-                }
-            };
-            // let rust_string = encoding_rs::WINDOWS_874.decode(&data).to_owned();
-            // web_sys::console::log_1(&format!("File content (string): {:?}", rust_string).into());
-            let js_string = js_sys::JsString::from(rust_string);
-            resolve.call1(&resolve, &js_string).unwrap();
-        }) as Box<dyn FnMut(web_sys::ProgressEvent)>);
-
-        file_reader.set_onloadend(Some(onloaded_closure.as_ref().unchecked_ref()));
-        onloaded_closure.forget();
-    });
-
-    file_reader.read_as_array_buffer(&file).unwrap();
-    Promise::from(promise)
+pub fn process_uploaded_file(
+    file: File,
+    #[wasm_bindgen(unchecked_param_type = "((progress: Progress) => void) | undefined")] on_progress: Option<js_sys::Function>,
+) -> Promise {
+    encoding::read_uploaded_file(file, None, on_progress, |decoded| {
+        if decoded.had_replacements {
+            web_sys::console::warn_1(&format!(
+                "Decoded as {} with replacement characters, pick the encoding with decode_uploaded_file",
+                decoded.encoding
+            ).into());
+        }
+        Ok(js_sys::JsString::from(decoded.text).into())
+    })
 }
@@ -0,0 +1,502 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use indexmap::IndexMap;
+use wasm_bindgen::prelude::*;
+use super::{ColumnInfo, build_schema_map, create_column_info};
+use super::index::{IndexInfo, build_index_map, create_index_info};
+use super::naming::{NamingConfig, NamingConvention};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+// A problem found by a rule, before the configured severity is attached
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LintViolation {
+    pub table_schema: String,
+    pub table_name: String,
+    pub object: Option<String>, // column or index name, None for table-level findings
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LintFinding {
+    pub rule_id: String,
+    pub severity: Severity,
+    pub table_schema: String,
+    pub table_name: String,
+    pub object: Option<String>,
+    pub message: String,
+}
+
+// Per-rule severity overrides and rules to skip, keyed by rule id
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct LintConfig {
+    #[serde(default)]
+    pub severity: HashMap<String, Severity>,
+    #[serde(default)]
+    pub disabled: Vec<String>,
+    // Naming rules are only checked when configured
+    #[serde(default)]
+    pub naming: Option<NamingConfig>,
+}
+
+// The schema being linted, in the same shape build_schema_map / build_index_map produce
+pub struct LintContext<'a> {
+    pub schema_map: &'a HashMap<String, IndexMap<String, ColumnInfo>>,
+    pub index_map: &'a HashMap<String, IndexMap<String, Vec<IndexInfo>>>,
+}
+
+impl LintContext<'_> {
+    // Table keys in a stable order so findings come out deterministically
+    pub fn table_keys(&self) -> Vec<&String> {
+        let mut keys: Vec<&String> = self.schema_map.keys().collect();
+        keys.sort();
+        keys
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = &ColumnInfo> {
+        self.table_keys()
+            .into_iter()
+            .flat_map(move |key| self.schema_map[key].values())
+    }
+
+    pub fn has_indexes(&self) -> bool {
+        !self.index_map.is_empty()
+    }
+}
+
+pub trait LintRule {
+    fn id(&self) -> &'static str;
+    fn description(&self) -> &'static str;
+    fn default_severity(&self) -> Severity;
+    fn check(&self, context: &LintContext) -> Vec<LintViolation>;
+}
+
+pub struct Linter {
+    rules: Vec<Box<dyn LintRule>>,
+    config: LintConfig,
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Linter {
+    // Linter with every built-in rule at its default severity
+    pub fn new() -> Self {
+        Linter {
+            rules: vec![
+                Box::new(MissingPrimaryKey),
+                Box::new(VarcharWithoutLength),
+                Box::new(MixedCollations),
+                Box::new(NullableUniqueIndex),
+                Box::new(FloatForMoney),
+                Box::new(UnindexedIdColumn),
+                Box::new(MissingComment),
+                Box::new(Utf8mb3Usage),
+            ],
+            config: LintConfig::default(),
+        }
+    }
+
+    pub fn empty() -> Self {
+        Linter {
+            rules: Vec::new(),
+            config: LintConfig::default(),
+        }
+    }
+
+    pub fn with_rule(mut self, rule: Box<dyn LintRule>) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn with_config(mut self, config: LintConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn rules(&self) -> &[Box<dyn LintRule>] {
+        &self.rules
+    }
+
+    pub fn run(&self, context: &LintContext) -> Vec<LintFinding> {
+        let mut findings = Vec::new();
+        for rule in &self.rules {
+            if self.config.disabled.iter().any(|id| id == rule.id()) {
+                continue;
+            }
+            let severity = self
+                .config
+                .severity
+                .get(rule.id())
+                .copied()
+                .unwrap_or_else(|| rule.default_severity());
+            for violation in rule.check(context) {
+                findings.push(LintFinding {
+                    rule_id: rule.id().to_string(),
+                    severity,
+                    table_schema: violation.table_schema,
+                    table_name: violation.table_name,
+                    object: violation.object,
+                    message: violation.message,
+                });
+            }
+        }
+        findings
+    }
+}
+
+#[wasm_bindgen]
+pub fn lint_schema(columns_json: &str, indexes_json: &str, config_json: &str) -> String {
+    let columns = match create_column_info(columns_json) {
+        Ok(column) => column,
+        Err(e) => return e,
+    };
+
+    // Index data is optional, rules that need it are skipped when it is missing
+    let indexes = if indexes_json.trim().is_empty() {
+        vec![]
+    } else {
+        match create_index_info(indexes_json) {
+            Ok(indexes) => indexes,
+            Err(e) => return e,
+        }
+    };
+
+    let config = if config_json.trim().is_empty() {
+        LintConfig::default()
+    } else {
+        match serde_json::from_str::<LintConfig>(config_json) {
+            Ok(config) => config,
+            Err(e) => return format!("Error parsing lint config: {}", e),
+        }
+    };
+
+    let schema_map = build_schema_map(columns);
+    let index_map = build_index_map(indexes);
+    let context = LintContext {
+        schema_map: &schema_map,
+        index_map: &index_map,
+    };
+    let mut linter = Linter::new();
+    if let Some(naming) = &config.naming {
+        match NamingConvention::new(naming) {
+            Ok(rule) => linter = linter.with_rule(Box::new(rule)),
+            Err(e) => return e,
+        }
+    }
+    let findings = linter.with_config(config).run(&context);
+
+    let result = serde_json::json!({
+        "findings": findings
+    });
+
+    match serde_json::to_string(&result) {
+        Ok(result) => result,
+        Err(e) => format!("Error serializing result: {}", e),
+    }
+}
+
+fn column_violation(column: &ColumnInfo, message: String) -> LintViolation {
+    LintViolation {
+        table_schema: column.table_schema.clone(),
+        table_name: column.table_name.clone(),
+        object: Some(column.column_name.clone()),
+        message,
+    }
+}
+
+pub struct MissingPrimaryKey;
+
+impl LintRule for MissingPrimaryKey {
+    fn id(&self) -> &'static str {
+        "missing-primary-key"
+    }
+
+    fn description(&self) -> &'static str {
+        "Table has no PRIMARY key"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn check(&self, context: &LintContext) -> Vec<LintViolation> {
+        if !context.has_indexes() {
+            return vec![];
+        }
+        context
+            .table_keys()
+            .into_iter()
+            .filter(|key| {
+                !context
+                    .index_map
+                    .get(*key)
+                    .is_some_and(|indexes| indexes.contains_key("PRIMARY"))
+            })
+            .filter_map(|key| context.schema_map[key].values().next())
+            .map(|column| LintViolation {
+                table_schema: column.table_schema.clone(),
+                table_name: column.table_name.clone(),
+                object: None,
+                message: format!("Table {} has no primary key", column.table_name),
+            })
+            .collect()
+    }
+}
+
+pub struct VarcharWithoutLength;
+
+impl LintRule for VarcharWithoutLength {
+    fn id(&self) -> &'static str {
+        "varchar-without-length"
+    }
+
+    fn description(&self) -> &'static str {
+        "varchar column declared without a length"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn check(&self, context: &LintContext) -> Vec<LintViolation> {
+        context
+            .columns()
+            .filter(|column| {
+                column.data_type.eq_ignore_ascii_case("varchar")
+                    && !column.column_type.contains('(')
+                    && column.character_maximum_length.is_none()
+            })
+            .map(|column| column_violation(column, format!("Column {} is varchar without a length", column.column_name)))
+            .collect()
+    }
+}
+
+pub struct MixedCollations;
+
+impl LintRule for MixedCollations {
+    fn id(&self) -> &'static str {
+        "mixed-collations"
+    }
+
+    fn description(&self) -> &'static str {
+        "Columns of one table use different collations"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, context: &LintContext) -> Vec<LintViolation> {
+        let mut violations = Vec::new();
+        for key in context.table_keys() {
+            let table = &context.schema_map[key];
+            let collations: BTreeSet<&str> = table
+                .values()
+                .filter_map(|column| column.collation_name.as_deref())
+                .collect();
+            if collations.len() > 1 {
+                if let Some(column) = table.values().next() {
+                    violations.push(LintViolation {
+                        table_schema: column.table_schema.clone(),
+                        table_name: column.table_name.clone(),
+                        object: None,
+                        message: format!(
+                            "Table {} mixes collations: {}",
+                            column.table_name,
+                            collations.into_iter().collect::<Vec<&str>>().join(", ")
+                        ),
+                    });
+                }
+            }
+        }
+        violations
+    }
+}
+
+pub struct NullableUniqueIndex;
+
+impl LintRule for NullableUniqueIndex {
+    fn id(&self) -> &'static str {
+        "nullable-unique-index"
+    }
+
+    fn description(&self) -> &'static str {
+        "Unique index contains a nullable column, so it allows duplicate NULL rows"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, context: &LintContext) -> Vec<LintViolation> {
+        let mut table_keys: Vec<&String> = context.index_map.keys().collect();
+        table_keys.sort();
+
+        let mut violations = Vec::new();
+        for key in table_keys {
+            let columns = context.schema_map.get(key);
+            for (index_name, index_list) in &context.index_map[key] {
+                if index_name == "PRIMARY" || !index_list.iter().all(|index| index.non_unique == Some(0)) {
+                    continue;
+                }
+                let nullable: Vec<&str> = index_list
+                    .iter()
+                    .filter(|index| {
+                        let column_nullable = columns
+                            .and_then(|table| table.get(&index.column_name))
+                            .is_some_and(|column| column.is_nullable.eq_ignore_ascii_case("YES"));
+                        index.nullable.eq_ignore_ascii_case("YES") || column_nullable
+                    })
+                    .map(|index| index.column_name.as_str())
+                    .collect();
+                if let (Some(index), false) = (index_list.first(), nullable.is_empty()) {
+                    violations.push(LintViolation {
+                        table_schema: index.table_schema.clone(),
+                        table_name: index.table_name.clone(),
+                        object: Some(index_name.clone()),
+                        message: format!(
+                            "Unique index {} contains nullable columns: {}",
+                            index_name,
+                            nullable.join(", ")
+                        ),
+                    });
+                }
+            }
+        }
+        violations
+    }
+}
+
+pub struct FloatForMoney;
+
+const MONEY_WORDS: [&str; 9] = ["price", "amount", "cost", "total", "balance", "money", "fee", "salary", "payment"];
+
+impl LintRule for FloatForMoney {
+    fn id(&self) -> &'static str {
+        "float-for-money"
+    }
+
+    fn description(&self) -> &'static str {
+        "Money-like column stored as an approximate float type"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, context: &LintContext) -> Vec<LintViolation> {
+        context
+            .columns()
+            .filter(|column| {
+                let data_type = column.data_type.to_lowercase();
+                let name = column.column_name.to_lowercase();
+                matches!(data_type.as_str(), "float" | "double" | "real")
+                    && MONEY_WORDS.iter().any(|word| name.contains(word))
+            })
+            .map(|column| column_violation(
+                column,
+                format!("Column {} looks like money but is {}, use decimal", column.column_name, column.data_type),
+            ))
+            .collect()
+    }
+}
+
+pub struct UnindexedIdColumn;
+
+impl LintRule for UnindexedIdColumn {
+    fn id(&self) -> &'static str {
+        "unindexed-id-column"
+    }
+
+    fn description(&self) -> &'static str {
+        "Column named like *_id is not the leading column of any index"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, context: &LintContext) -> Vec<LintViolation> {
+        if !context.has_indexes() {
+            return vec![];
+        }
+        context
+            .columns()
+            .filter(|column| column.column_name.to_lowercase().ends_with("_id"))
+            .filter(|column| {
+                let key = format!("`{}`.{}", column.table_schema, column.table_name);
+                !context.index_map.get(&key).is_some_and(|indexes| {
+                    indexes.values().any(|index_list| {
+                        index_list
+                            .iter()
+                            .min_by_key(|index| index.seq_in_index)
+                            .is_some_and(|index| index.column_name == column.column_name)
+                    })
+                })
+            })
+            .map(|column| column_violation(column, format!("Column {} is not the leading column of any index", column.column_name)))
+            .collect()
+    }
+}
+
+pub struct MissingComment;
+
+impl LintRule for MissingComment {
+    fn id(&self) -> &'static str {
+        "missing-comment"
+    }
+
+    fn description(&self) -> &'static str {
+        "Column has no comment"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Info
+    }
+
+    fn check(&self, context: &LintContext) -> Vec<LintViolation> {
+        context
+            .columns()
+            .filter(|column| column.column_comment.as_deref().unwrap_or_default().trim().is_empty())
+            .map(|column| column_violation(column, format!("Column {} has no comment", column.column_name)))
+            .collect()
+    }
+}
+
+pub struct Utf8mb3Usage;
+
+impl LintRule for Utf8mb3Usage {
+    fn id(&self) -> &'static str {
+        "utf8mb3-charset"
+    }
+
+    fn description(&self) -> &'static str {
+        "Column uses the deprecated 3-byte utf8 character set"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, context: &LintContext) -> Vec<LintViolation> {
+        context
+            .columns()
+            .filter(|column| {
+                let charset = column.character_set_name.as_deref().unwrap_or_default().to_lowercase();
+                let collation = column.collation_name.as_deref().unwrap_or_default().to_lowercase();
+                charset == "utf8" || charset == "utf8mb3" || collation.starts_with("utf8_") || collation.starts_with("utf8mb3_")
+            })
+            .map(|column| column_violation(column, format!("Column {} uses utf8mb3, use utf8mb4", column.column_name)))
+            .collect()
+    }
+}
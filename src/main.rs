@@ -0,0 +1,23 @@
+// Native command line front end, see db_diff::cli
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    use std::io::Write;
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match db_diff::cli::run_with_status(&args) {
+        // A closed pipe, e.g. `| head`, is not an error
+        Ok((output, status)) => {
+            let _ = writeln!(std::io::stdout().lock(), "{}", output.trim_end());
+            if status != 0 {
+                std::process::exit(status);
+            }
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
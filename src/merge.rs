@@ -0,0 +1,344 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use indexmap::IndexMap;
+use wasm_bindgen::prelude::*;
+use super::{ColumnInfo, build_schema_map, compare_schema_maps, create_column_info};
+use super::index::{IndexInfo, build_index_map, compare_index_lists, compare_index_maps, create_index_info};
+
+// How a change made on one or both branches relates to the common ancestor
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeOrigin {
+    OnlyOurs,
+    OnlyTheirs,
+    SameOnBoth,
+    Conflicting,
+}
+
+// State of one column on every side of the merge, None means the column does not exist there
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ColumnMergeChange {
+    pub table_key: String,
+    pub column_name: String,
+    pub origin: MergeOrigin,
+    pub base: Option<ColumnInfo>,
+    pub ours: Option<ColumnInfo>,
+    pub theirs: Option<ColumnInfo>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SchemaMerge {
+    pub changes: Vec<ColumnMergeChange>,
+    pub conflicts: Vec<ColumnMergeChange>,
+    pub merged: HashMap<String, IndexMap<String, ColumnInfo>>,
+}
+
+// State of one index on every side of the merge, None means the index does not exist there
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IndexMergeChange {
+    pub table_key: String,
+    pub index_name: String,
+    pub origin: MergeOrigin,
+    pub base: Option<Vec<IndexInfo>>,
+    pub ours: Option<Vec<IndexInfo>>,
+    pub theirs: Option<Vec<IndexInfo>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct IndexMerge {
+    pub changes: Vec<IndexMergeChange>,
+    pub conflicts: Vec<IndexMergeChange>,
+    pub merged: HashMap<String, IndexMap<String, Vec<IndexInfo>>>,
+}
+
+#[wasm_bindgen]
+pub fn merge_schemas(base: &str, ours: &str, theirs: &str) -> String {
+    let base = match create_column_info(base) {
+        Ok(column) => column,
+        Err(e) => return e,
+    };
+    let ours = match create_column_info(ours) {
+        Ok(column) => column,
+        Err(e) => return e,
+    };
+    let theirs = match create_column_info(theirs) {
+        Ok(column) => column,
+        Err(e) => return e,
+    };
+
+    let merge = merge_schema_maps(
+        &build_schema_map(base),
+        &build_schema_map(ours),
+        &build_schema_map(theirs),
+    );
+
+    // Return the merged schema in the same flat shape as the export so it can be compared again
+    let mut table_keys: Vec<&String> = merge.merged.keys().collect();
+    table_keys.sort();
+    let merged: Vec<&ColumnInfo> = table_keys
+        .iter()
+        .flat_map(|key| merge.merged[*key].values())
+        .collect();
+
+    let result = serde_json::json!({
+        "changes": merge.changes,
+        "conflicts": merge.conflicts,
+        "merged": merged
+    });
+
+    match serde_json::to_string(&result) {
+        Ok(result) => result,
+        Err(e) => format!("Error serializing result: {}", e),
+    }
+}
+
+#[wasm_bindgen]
+pub fn merge_indexs(base: &str, ours: &str, theirs: &str) -> String {
+    // If parsing fails, proceed with empty indexes
+    let base = create_index_info(base).unwrap_or_default();
+    let ours = create_index_info(ours).unwrap_or_default();
+    let theirs = create_index_info(theirs).unwrap_or_default();
+
+    let merge = merge_index_maps(
+        &build_index_map(base),
+        &build_index_map(ours),
+        &build_index_map(theirs),
+    );
+
+    let mut table_keys: Vec<&String> = merge.merged.keys().collect();
+    table_keys.sort();
+    let merged: Vec<&IndexInfo> = table_keys
+        .iter()
+        .flat_map(|key| merge.merged[*key].values().flatten())
+        .collect();
+
+    let result = serde_json::json!({
+        "changes": merge.changes,
+        "conflicts": merge.conflicts,
+        "merged": merged
+    });
+
+    match serde_json::to_string(&result) {
+        Ok(result) => result,
+        Err(e) => format!("Error serializing result: {}", e),
+    }
+}
+
+pub fn merge_schema_maps(
+    base: &HashMap<String, IndexMap<String, ColumnInfo>>,
+    ours: &HashMap<String, IndexMap<String, ColumnInfo>>,
+    theirs: &HashMap<String, IndexMap<String, ColumnInfo>>
+) -> SchemaMerge {
+    let ours_changes = column_changes(base, ours);
+    let theirs_changes = column_changes(base, theirs);
+
+    let keys: BTreeSet<&(String, String)> = ours_changes.keys().chain(theirs_changes.keys()).collect();
+    let mut keys: Vec<&(String, String)> = keys.into_iter().collect();
+    // Keep new columns in the order they were declared on the branch that added them
+    keys.sort_by_key(|(table_key, column_name)| {
+        let position = [ours, theirs, base]
+            .iter()
+            .find_map(|map| map.get(table_key).and_then(|table| table.get_index_of(column_name)))
+            .unwrap_or(usize::MAX);
+        (table_key.clone(), position)
+    });
+
+    let mut merged = base.clone();
+    let mut changes: Vec<ColumnMergeChange> = Vec::new();
+    let mut conflicts: Vec<ColumnMergeChange> = Vec::new();
+
+    for key in keys {
+        let (table_key, column_name) = key;
+        let base_column = base.get(table_key).and_then(|table| table.get(column_name)).cloned();
+        let (origin, target) = match (ours_changes.get(key), theirs_changes.get(key)) {
+            (Some(o), None) => (MergeOrigin::OnlyOurs, o.clone()),
+            (None, Some(t)) => (MergeOrigin::OnlyTheirs, t.clone()),
+            (Some(o), Some(t)) if o == t => (MergeOrigin::SameOnBoth, o.clone()),
+            _ => (MergeOrigin::Conflicting, base_column.clone()),
+        };
+
+        let change = ColumnMergeChange {
+            table_key: table_key.clone(),
+            column_name: column_name.clone(),
+            origin,
+            base: base_column.clone(),
+            ours: ours_changes.get(key).cloned().unwrap_or_else(|| base_column.clone()),
+            theirs: theirs_changes.get(key).cloned().unwrap_or_else(|| base_column.clone()),
+        };
+
+        if origin == MergeOrigin::Conflicting {
+            // Leave the ancestor definition in place until the conflict is resolved by hand
+            conflicts.push(change.clone());
+        } else {
+            match target {
+                Some(column) => {
+                    merged
+                        .entry(table_key.clone())
+                        .or_default()
+                        .insert(column_name.clone(), column);
+                }
+                None => {
+                    if let Some(table) = merged.get_mut(table_key) {
+                        table.shift_remove(column_name);
+                        if table.is_empty() {
+                            merged.remove(table_key);
+                        }
+                    }
+                }
+            }
+        }
+        changes.push(change);
+    }
+
+    SchemaMerge {
+        changes,
+        conflicts,
+        merged,
+    }
+}
+
+pub fn merge_index_maps(
+    base: &HashMap<String, IndexMap<String, Vec<IndexInfo>>>,
+    ours: &HashMap<String, IndexMap<String, Vec<IndexInfo>>>,
+    theirs: &HashMap<String, IndexMap<String, Vec<IndexInfo>>>
+) -> IndexMerge {
+    let ours_changes = index_changes(base, ours);
+    let theirs_changes = index_changes(base, theirs);
+
+    let keys: BTreeSet<&(String, String)> = ours_changes.keys().chain(theirs_changes.keys()).collect();
+
+    let mut merged = base.clone();
+    let mut changes: Vec<IndexMergeChange> = Vec::new();
+    let mut conflicts: Vec<IndexMergeChange> = Vec::new();
+
+    for key in keys {
+        let (table_key, index_name) = key;
+        let base_index = base.get(table_key).and_then(|table| table.get(index_name)).cloned();
+        let (origin, target) = match (ours_changes.get(key), theirs_changes.get(key)) {
+            (Some(o), None) => (MergeOrigin::OnlyOurs, o.clone()),
+            (None, Some(t)) => (MergeOrigin::OnlyTheirs, t.clone()),
+            (Some(Some(o)), Some(Some(t))) if compare_index_lists(o, t) => (MergeOrigin::SameOnBoth, Some(o.clone())),
+            (Some(None), Some(None)) => (MergeOrigin::SameOnBoth, None),
+            _ => (MergeOrigin::Conflicting, base_index.clone()),
+        };
+
+        let change = IndexMergeChange {
+            table_key: table_key.clone(),
+            index_name: index_name.clone(),
+            origin,
+            base: base_index.clone(),
+            ours: ours_changes.get(key).cloned().unwrap_or_else(|| base_index.clone()),
+            theirs: theirs_changes.get(key).cloned().unwrap_or_else(|| base_index.clone()),
+        };
+
+        if origin == MergeOrigin::Conflicting {
+            conflicts.push(change.clone());
+        } else {
+            match target {
+                Some(index_list) => {
+                    merged
+                        .entry(table_key.clone())
+                        .or_default()
+                        .insert(index_name.clone(), index_list);
+                }
+                None => {
+                    if let Some(table) = merged.get_mut(table_key) {
+                        table.shift_remove(index_name);
+                        if table.is_empty() {
+                            merged.remove(table_key);
+                        }
+                    }
+                }
+            }
+        }
+        changes.push(change);
+    }
+
+    IndexMerge {
+        changes,
+        conflicts,
+        merged,
+    }
+}
+
+// Collect the target state of every column a branch touched, keyed by (table key, column name)
+fn column_changes(
+    base: &HashMap<String, IndexMap<String, ColumnInfo>>,
+    branch: &HashMap<String, IndexMap<String, ColumnInfo>>
+) -> HashMap<(String, String), Option<ColumnInfo>> {
+    let diff = compare_schema_maps(base, branch);
+    let mut changes: HashMap<(String, String), Option<ColumnInfo>> = HashMap::new();
+
+    for table in &diff.tables_only_in_first {
+        let table_key = table.key();
+        for column_name in base[&table_key].keys() {
+            changes.insert((table_key.clone(), column_name.clone()), None);
+        }
+    }
+
+    for table in &diff.tables_only_in_second {
+        let table_key = table.key();
+        for (column_name, column) in &branch[&table_key] {
+            changes.insert((table_key.clone(), column_name.clone()), Some(column.clone()));
+        }
+    }
+
+    for column in diff.columns_only_in_first {
+        let table_key = format!("`{}`.{}", column.table_schema, column.table_name);
+        changes.insert((table_key, column.column_name), None);
+    }
+
+    for column in diff.columns_only_in_second {
+        let table_key = format!("`{}`.{}", column.table_schema, column.table_name);
+        changes.insert((table_key, column.column_name.clone()), Some(column));
+    }
+
+    for diff_item in diff.columns_with_different_definitions {
+        let table_key = format!("`{}`.{}", diff_item.second.table_schema, diff_item.second.table_name);
+        changes.insert((table_key, diff_item.column_name), Some(diff_item.second));
+    }
+
+    changes
+}
+
+// Collect the target state of every index a branch touched, keyed by (table key, index name)
+fn index_changes(
+    base: &HashMap<String, IndexMap<String, Vec<IndexInfo>>>,
+    branch: &HashMap<String, IndexMap<String, Vec<IndexInfo>>>
+) -> HashMap<(String, String), Option<Vec<IndexInfo>>> {
+    let diff = compare_index_maps(base, branch);
+    let mut changes: HashMap<(String, String), Option<Vec<IndexInfo>>> = HashMap::new();
+
+    for index in diff.indexes_only_in_first {
+        let table_key = format!("`{}`.{}", index.table_schema, index.table_name);
+        changes.insert((table_key, index.index_name), None);
+    }
+
+    for rename in &diff.indexes_renamed {
+        let table_key = format!("`{}`.{}", rename.table_schema, rename.table_name);
+        changes.insert((table_key, rename.old_index_name.clone()), None);
+    }
+
+    let changed_in_branch = diff
+        .indexes_only_in_second
+        .iter()
+        .map(|index| (&index.table_schema, &index.table_name, &index.index_name))
+        .chain(
+            diff.indexes_with_different_definitions
+                .iter()
+                .map(|diff_item| (&diff_item.second.table_schema, &diff_item.second.table_name, &diff_item.index_name)),
+        )
+        .chain(
+            diff.indexes_renamed
+                .iter()
+                .map(|rename| (&rename.table_schema, &rename.table_name, &rename.new_index_name)),
+        );
+
+    for (table_schema, table_name, index_name) in changed_in_branch {
+        let table_key = format!("`{}`.{}", table_schema, table_name);
+        let index_list = branch.get(&table_key).and_then(|table| table.get(index_name)).cloned();
+        changes.insert((table_key, index_name.clone()), index_list);
+    }
+
+    changes
+}
@@ -0,0 +1,251 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use super::ci::escape_xml;
+use super::ddl_diff::{parse_columns, parse_indexes};
+use super::full::{FullComparison, compare_columns_and_indexes};
+use super::output::FORMAT_VERSION;
+
+// Migration framework the files are laid out for
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum MigrationTool {
+    /// V<version>__<description>.sql and the undo migration U<version>__<description>.sql
+    Flyway,
+    /// <version>_<description>.up.sql / .down.sql
+    GolangMigrate,
+    /// <version>_<description>.up.sql / .down.sql, sqlx's reversible migrations
+    Sqlx,
+    /// <version>_<description>.xml, one changeset per table with its rollback
+    #[serde(alias = "liquibase-xml")]
+    Liquibase,
+    /// <version>_<description>.yaml, the same changelog as YAML
+    LiquibaseYaml,
+}
+
+impl MigrationTool {
+    pub fn parse(tool: &str) -> Result<MigrationTool, String> {
+        match tool.trim().to_lowercase().as_str() {
+            "flyway" => Ok(MigrationTool::Flyway),
+            "golang-migrate" | "migrate" => Ok(MigrationTool::GolangMigrate),
+            "sqlx" => Ok(MigrationTool::Sqlx),
+            "liquibase" | "liquibase-xml" => Ok(MigrationTool::Liquibase),
+            "liquibase-yaml" | "liquibase-yml" => Ok(MigrationTool::LiquibaseYaml),
+            other => Err(format!("Unknown migration tool: {}", other)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MigrationOptions {
+    pub tool: MigrationTool,
+    /// Flyway accepts digits separated by dots or underscores, golang-migrate and sqlx digits only,
+    /// e.g. a timestamp such as 20240501120000
+    #[serde(default = "default_version")]
+    pub version: String,
+    /// Free text, file names use it with every run of other characters than letters and digits as _
+    #[serde(default = "default_description")]
+    pub description: String,
+    /// Author of the Liquibase changesets
+    #[serde(default = "default_author")]
+    pub author: String,
+}
+
+fn default_version() -> String {
+    "1".to_string()
+}
+
+fn default_description() -> String {
+    "schema diff".to_string()
+}
+
+fn default_author() -> String {
+    "db-diff".to_string()
+}
+
+impl MigrationOptions {
+    pub fn new(tool: MigrationTool) -> Self {
+        MigrationOptions {
+            tool,
+            version: default_version(),
+            description: default_description(),
+            author: default_author(),
+        }
+    }
+
+    pub fn version<S: Into<String>>(mut self, version: S) -> Self {
+        self.version = version.into();
+        self
+    }
+
+    pub fn description<S: Into<String>>(mut self, description: S) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    pub fn author<S: Into<String>>(mut self, author: S) -> Self {
+        self.author = author.into();
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MigrationFile {
+    /// File name, relative to the migrations directory of the tool
+    pub path: String,
+    pub contents: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MigrationOutput {
+    pub format_version: u32,
+    pub files: Vec<MigrationFile>,
+    /// Verification errors of the comparison, the migration is incomplete when this is not empty
+    pub verification_errors: Vec<String>,
+}
+
+// Files applying the forward script of a comparison, with the reverse script as their undo /
+// down migration or rollback
+pub fn migration_files(comparison: &FullComparison, options: &MigrationOptions) -> Result<Vec<MigrationFile>, String> {
+    if comparison.forward_script.is_empty() && comparison.reverse_script.is_empty() {
+        return Err("No differences, there is nothing to migrate".to_string());
+    }
+    let version = options.version.trim();
+    let valid_version = match options.tool {
+        MigrationTool::Flyway => {
+            version.starts_with(|c: char| c.is_ascii_digit()) && version.chars().all(|c| c.is_ascii_digit() || c == '.' || c == '_')
+        }
+        MigrationTool::GolangMigrate | MigrationTool::Sqlx => !version.is_empty() && version.chars().all(|c| c.is_ascii_digit()),
+        MigrationTool::Liquibase | MigrationTool::LiquibaseYaml => !version.is_empty() && !version.contains(['/', '\\']),
+    };
+    if !valid_version {
+        return Err(format!("Invalid migration version: {}", options.version));
+    }
+    let name = file_description(&options.description);
+    if name.is_empty() {
+        return Err("The migration description needs at least one letter or digit".to_string());
+    }
+
+    let forward = format!("{}\n", comparison.forward_script);
+    let reverse = format!("{}\n", comparison.reverse_script);
+    let file = |path: String, contents: String| MigrationFile { path, contents };
+    Ok(match options.tool {
+        MigrationTool::Flyway => vec![
+            file(format!("V{}__{}.sql", version, name), forward),
+            file(format!("U{}__{}.sql", version, name), reverse),
+        ],
+        MigrationTool::GolangMigrate | MigrationTool::Sqlx => vec![
+            file(format!("{}_{}.up.sql", version, name), forward),
+            file(format!("{}_{}.down.sql", version, name), reverse),
+        ],
+        MigrationTool::Liquibase => vec![file(format!("{}_{}.xml", version, name), liquibase_xml(comparison, version, options))],
+        MigrationTool::LiquibaseYaml => vec![file(format!("{}_{}.yaml", version, name), liquibase_yaml(comparison, version, options))],
+    })
+}
+
+fn file_description(description: &str) -> String {
+    let mut name = String::new();
+    for c in description.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            name.push(c);
+        } else if !name.is_empty() && !name.ends_with('_') {
+            name.push('_');
+        }
+    }
+    name.trim_end_matches('_').to_string()
+}
+
+// Changesets of one table each, in `schema`.table order. The planner orders statements by
+// phase over all tables, per table that order is kept; no statement depends on another table
+fn changesets(comparison: &FullComparison) -> Vec<(String, &[String], &[String])> {
+    comparison
+        .tables
+        .iter()
+        .filter(|table| !table.forward_sql.is_empty())
+        .map(|table| (format!("{}.{}", table.schema, table.table), table.forward_sql.as_slice(), table.reverse_sql.as_slice()))
+        .collect()
+}
+
+fn liquibase_xml(comparison: &FullComparison, version: &str, options: &MigrationOptions) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<databaseChangeLog
+    xmlns=\"http://www.liquibase.org/xml/ns/dbchangelog\"
+    xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\"
+    xsi:schemaLocation=\"http://www.liquibase.org/xml/ns/dbchangelog http://www.liquibase.org/xml/ns/dbchangelog/dbchangelog-latest.xsd\">
+",
+    );
+    for (table, forward, reverse) in changesets(comparison) {
+        xml.push_str(&format!(
+            "  <changeSet id=\"{}\" author=\"{}\">\n    <comment>{}</comment>\n    <sql>\n{}\n    </sql>\n",
+            escape_xml(&format!("{}-{}", version, table)),
+            escape_xml(&options.author),
+            escape_xml(&options.description),
+            indent(&escape_xml(&forward.join("\n")), 6)
+        ));
+        if !reverse.is_empty() {
+            xml.push_str(&format!("    <rollback>\n      <sql>\n{}\n      </sql>\n    </rollback>\n", indent(&escape_xml(&reverse.join("\n")), 8)));
+        }
+        xml.push_str("  </changeSet>\n");
+    }
+    xml.push_str("</databaseChangeLog>\n");
+    xml
+}
+
+// Scalars are written as JSON strings, which YAML reads as double quoted strings, and SQL as
+// literal blocks so no statement needs escaping
+fn liquibase_yaml(comparison: &FullComparison, version: &str, options: &MigrationOptions) -> String {
+    let quote = |text: &str| serde_json::to_string(text).unwrap_or_default();
+    let mut yaml = String::from("databaseChangeLog:\n");
+    for (table, forward, reverse) in changesets(comparison) {
+        yaml.push_str(&format!(
+            "  - changeSet:\n      id: {}\n      author: {}\n      comment: {}\n      changes:\n        - sql:\n            sql: |-\n{}\n",
+            quote(&format!("{}-{}", version, table)),
+            quote(&options.author),
+            quote(&options.description),
+            indent(&forward.join("\n"), 14)
+        ));
+        if !reverse.is_empty() {
+            yaml.push_str(&format!("      rollback:\n        - sql:\n            sql: |-\n{}\n", indent(&reverse.join("\n"), 14)));
+        }
+    }
+    yaml
+}
+
+fn indent(text: &str, width: usize) -> String {
+    text.lines().map(|line| format!("{}{}", " ".repeat(width), line)).collect::<Vec<String>>().join("\n")
+}
+
+// Migration files for both export pairs, options_json as MigrationOptions, e.g.
+// {"tool": "flyway", "version": "2", "description": "add audit columns"}
+#[wasm_bindgen]
+pub fn generate_migration(columns_json1: &str, columns_json2: &str, indexes_json1: &str, indexes_json2: &str, options_json: &str) -> String {
+    let options: MigrationOptions = match serde_json::from_str(options_json) {
+        Ok(options) => options,
+        Err(e) => return format!("Error parsing migration options: {}", e),
+    };
+    let result = migration_output(columns_json1, columns_json2, indexes_json1, indexes_json2, &options);
+    match result {
+        Ok(output) => match serde_json::to_string(&output) {
+            Ok(result) => result,
+            Err(e) => format!("Error serializing result: {}", e),
+        },
+        Err(e) => e,
+    }
+}
+
+fn migration_output(
+    columns_json1: &str,
+    columns_json2: &str,
+    indexes_json1: &str,
+    indexes_json2: &str,
+    options: &MigrationOptions
+) -> Result<MigrationOutput, String> {
+    let comparison = compare_columns_and_indexes(
+        parse_columns(columns_json1)?,
+        parse_columns(columns_json2)?,
+        parse_indexes(indexes_json1)?,
+        parse_indexes(indexes_json2)?,
+    );
+    let files = migration_files(&comparison, options)?;
+    Ok(MigrationOutput { format_version: FORMAT_VERSION, files, verification_errors: comparison.verification_errors })
+}
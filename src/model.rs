@@ -0,0 +1,651 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use indexmap::IndexMap;
+use super::{ColumnInfo, build_schema_map, compare_schema_maps, format_column_definition, generate_sql_diff};
+use super::index::{IndexInfo, build_index_map, compare_index_maps, generate_sql_index_diff};
+
+// Order in which the sections of generate_sql_diff / generate_sql_index_diff have to run
+// so that every statement finds the objects it depends on
+const FORWARD_SECTIONS: [&str; 9] = [
+    "Drop index in Schema 1 (Schema 1 only)",
+    "Rename index in Schema 1",
+    "Drop table in Schema 1 (Schema 1 only)",
+    "Create table in Schema 1 (Schema 2 only)",
+    "Drop column in Schema 1 (Schema 1 only)",
+    "Add column in Schema 1 (Schema 2 only)",
+    "Modify column in Schema 1",
+    "Modify index in Schema 1",
+    "Add index in Schema 1 (Schema 2 only)",
+];
+
+const REVERSE_SECTIONS: [&str; 9] = [
+    "Drop index in Schema 2 (Schema 2 only)",
+    "Rename index in Schema 2",
+    "Drop table in Schema 2 (Schema 2 only)",
+    "Create table in Schema 2 (Schema 1 only)",
+    "Drop column in Schema 2 (Schema 2 only)",
+    "Add column in Schema 2 (Schema 1 only)",
+    "Modify column in Schema 2",
+    "Modify index in Schema 2",
+    "Add index in Schema 2 (Schema 1 only)",
+];
+
+// A single DDL statement as emitted by the SQL generators.
+// Tables are kept in the `schema`.table form used as key by build_schema_map
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MigrationStatement {
+    CreateTable { table: String, columns: Vec<String> },
+    DropTable { table: String },
+    AddColumn { table: String, definition: String },
+    DropColumn { table: String, column: String },
+    ModifyColumn { table: String, definition: String },
+    ChangeColumn { table: String, column: String, definition: String },
+    CreateIndex { table: String, index: String, unique: bool, columns: Vec<String> },
+    DropIndex { table: String, index: String },
+    RenameIndex { table: String, index: String, new_index: String },
+}
+
+impl MigrationStatement {
+    pub fn table(&self) -> &str {
+        match self {
+            MigrationStatement::CreateTable { table, .. }
+            | MigrationStatement::DropTable { table }
+            | MigrationStatement::AddColumn { table, .. }
+            | MigrationStatement::DropColumn { table, .. }
+            | MigrationStatement::ModifyColumn { table, .. }
+            | MigrationStatement::ChangeColumn { table, .. }
+            | MigrationStatement::CreateIndex { table, .. }
+            | MigrationStatement::DropIndex { table, .. }
+            | MigrationStatement::RenameIndex { table, .. } => table,
+        }
+    }
+
+    pub fn parse(sql: &str) -> Result<MigrationStatement, String> {
+        let statement = sql.trim();
+        let statement = statement.strip_suffix(';').unwrap_or(statement).trim();
+        let upper = statement.to_ascii_uppercase();
+
+        if let Some(rest) = strip_keyword(statement, &upper, "DROP TABLE ") {
+            return Ok(MigrationStatement::DropTable { table: rest.trim().to_string() });
+        }
+
+        if let Some(rest) = strip_keyword(statement, &upper, "CREATE TABLE ") {
+            let (table, body) = rest
+                .split_once('(')
+                .ok_or_else(|| format!("Missing column list in: {}", sql))?;
+            let body = body
+                .trim_end()
+                .strip_suffix(')')
+                .ok_or_else(|| format!("Unterminated column list in: {}", sql))?;
+            let columns = split_top_level(body)
+                .into_iter()
+                .map(|definition| normalize_definition(&definition))
+                .filter(|definition| !definition.is_empty())
+                .collect();
+            return Ok(MigrationStatement::CreateTable { table: table.trim().to_string(), columns });
+        }
+
+        if let Some(rest) = strip_keyword(statement, &upper, "ALTER TABLE ") {
+            let (table, action) = rest
+                .trim_start()
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| format!("Missing ALTER TABLE action in: {}", sql))?;
+            let table = table.to_string();
+            let action = action.trim();
+            let action_upper = action.to_ascii_uppercase();
+
+            if let Some(definition) = strip_keyword(action, &action_upper, "ADD COLUMN ") {
+                return Ok(MigrationStatement::AddColumn { table, definition: normalize_definition(definition) });
+            }
+            if let Some(column) = strip_keyword(action, &action_upper, "DROP COLUMN ") {
+                return Ok(MigrationStatement::DropColumn { table, column: unquote(column.trim()) });
+            }
+            if let Some(definition) = strip_keyword(action, &action_upper, "MODIFY COLUMN ") {
+                return Ok(MigrationStatement::ModifyColumn { table, definition: normalize_definition(definition) });
+            }
+            if let Some(rest) = strip_keyword(action, &action_upper, "CHANGE COLUMN ") {
+                let (column, definition) = rest
+                    .trim_start()
+                    .split_once(char::is_whitespace)
+                    .ok_or_else(|| format!("Missing column definition in: {}", sql))?;
+                return Ok(MigrationStatement::ChangeColumn {
+                    table,
+                    column: unquote(column),
+                    definition: normalize_definition(definition),
+                });
+            }
+            if let Some(columns) = strip_keyword(action, &action_upper, "ADD PRIMARY KEY ") {
+                let columns = columns
+                    .trim()
+                    .strip_prefix('(')
+                    .and_then(|columns| columns.strip_suffix(')'))
+                    .ok_or_else(|| format!("Missing primary key columns in: {}", sql))?;
+                return Ok(MigrationStatement::CreateIndex {
+                    table,
+                    index: "PRIMARY".to_string(),
+                    unique: true,
+                    columns: index_columns(columns),
+                });
+            }
+            if action_upper == "DROP PRIMARY KEY" {
+                return Ok(MigrationStatement::DropIndex { table, index: "PRIMARY".to_string() });
+            }
+            if let Some(rest) = strip_keyword(action, &action_upper, "RENAME INDEX ") {
+                let (index, new_index) = split_on_keyword(rest, " TO ")
+                    .ok_or_else(|| format!("Missing TO clause in: {}", sql))?;
+                return Ok(MigrationStatement::RenameIndex {
+                    table,
+                    index: unquote(index.trim()),
+                    new_index: unquote(new_index.trim()),
+                });
+            }
+            return Err(format!("Unsupported ALTER TABLE statement: {}", sql));
+        }
+
+        let (unique, create_index) = if let Some(rest) = strip_keyword(statement, &upper, "CREATE UNIQUE INDEX ") {
+            (true, Some(rest))
+        } else {
+            (false, strip_keyword(statement, &upper, "CREATE INDEX "))
+        };
+        if let Some(rest) = create_index {
+            let (index, rest) = split_on_keyword(rest, " ON ")
+                .ok_or_else(|| format!("Missing ON clause in: {}", sql))?;
+            let (table, columns) = rest
+                .split_once('(')
+                .ok_or_else(|| format!("Missing index columns in: {}", sql))?;
+            let columns = columns
+                .trim_end()
+                .strip_suffix(')')
+                .ok_or_else(|| format!("Unterminated index columns in: {}", sql))?;
+            return Ok(MigrationStatement::CreateIndex {
+                table: table.trim().to_string(),
+                index: unquote(index.trim()),
+                unique,
+                columns: index_columns(columns),
+            });
+        }
+
+        if let Some(rest) = strip_keyword(statement, &upper, "DROP INDEX ") {
+            let (index, table) = split_on_keyword(rest, " ON ")
+                .ok_or_else(|| format!("Missing ON clause in: {}", sql))?;
+            return Ok(MigrationStatement::DropIndex { table: table.trim().to_string(), index: unquote(index.trim()) });
+        }
+
+        Err(format!("Unsupported statement: {}", sql))
+    }
+}
+
+// Parse a script produced by the SQL generators.
+// CREATE TABLE is emitted commented out for review, so it is read back as a statement
+pub fn parse_migration_script(script: &str) -> Result<Vec<MigrationStatement>, String> {
+    split_script(script).iter().map(|statement| MigrationStatement::parse(statement)).collect()
+}
+
+// Statements of a generated script, split on semicolons outside quotes so a comment holding a
+// newline stays in its statement. Comment lines between statements are skipped
+pub fn split_script(script: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    for line in script.lines() {
+        let mut line = line;
+        if quote.is_none() && current.trim().is_empty() {
+            let trimmed = line.trim_start();
+            match trimmed.strip_prefix("-- ") {
+                Some(rest) if rest.to_ascii_uppercase().starts_with("CREATE TABLE ") => line = rest,
+                _ if trimmed.starts_with("--") => continue,
+                _ => {}
+            }
+        }
+        if !current.is_empty() {
+            current.push('\n');
+        }
+        let mut chars = line.chars();
+        while let Some(c) = chars.next() {
+            current.push(c);
+            match quote {
+                Some(_) if c == '\\' => current.extend(chars.next()),
+                Some(q) if c == q => quote = None,
+                Some(_) => {}
+                None => match c {
+                    '\'' | '"' | '`' => quote = Some(c),
+                    ';' => {
+                        statements.push(current.trim().to_string());
+                        current.clear();
+                    }
+                    _ => {}
+                },
+            }
+        }
+    }
+    if !current.trim().is_empty() {
+        statements.push(current.trim().to_string());
+    }
+    statements
+}
+
+// Join the generated statements that turn schema 1 into schema 2, in executable order
+pub fn forward_script(sql_statements: &HashMap<String, String>) -> String {
+    ordered_script(sql_statements, &FORWARD_SECTIONS)
+}
+
+// Join the generated statements that turn schema 2 back into schema 1, in executable order
+pub fn reverse_script(sql_statements: &HashMap<String, String>) -> String {
+    ordered_script(sql_statements, &REVERSE_SECTIONS)
+}
+
+fn ordered_script(sql_statements: &HashMap<String, String>, sections: &[&str]) -> String {
+    sections
+        .iter()
+        .filter_map(|section| sql_statements.get(*section))
+        .cloned()
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IndexModel {
+    pub unique: bool,
+    pub columns: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableModel {
+    // column name -> definition as rendered by format_column_definition
+    pub columns: IndexMap<String, String>,
+    pub indexes: IndexMap<String, IndexModel>,
+}
+
+// In-memory schema that DDL statements can be applied to
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaModel {
+    pub tables: HashMap<String, TableModel>,
+}
+
+impl SchemaModel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_columns(columns: &[ColumnInfo]) -> Self {
+        let mut model = Self::new();
+        for column in columns {
+            let table_key = format!("`{}`.{}", column.table_schema, column.table_name);
+            model
+                .tables
+                .entry(table_key)
+                .or_default()
+                .columns
+                .insert(column.column_name.clone(), normalize_definition(&format_column_definition(column)));
+        }
+        model
+    }
+
+    pub fn from_schema_map(schema_map: &HashMap<String, IndexMap<String, ColumnInfo>>) -> Self {
+        let mut model = Self::new();
+        for (table_key, columns) in schema_map {
+            let table = model.tables.entry(table_key.clone()).or_default();
+            for (column_name, column) in columns {
+                table.columns.insert(column_name.clone(), normalize_definition(&format_column_definition(column)));
+            }
+        }
+        model
+    }
+
+    // Model of the given tables of the map only, keys missing from the map are skipped
+    pub fn from_schema_map_tables(schema_map: &HashMap<String, IndexMap<String, ColumnInfo>>, table_keys: &HashSet<String>) -> Self {
+        let mut model = Self::new();
+        for table_key in table_keys {
+            if let Some(columns) = schema_map.get(table_key) {
+                let table = model.tables.entry(table_key.clone()).or_default();
+                for (column_name, column) in columns {
+                    table.columns.insert(column_name.clone(), normalize_definition(&format_column_definition(column)));
+                }
+            }
+        }
+        model
+    }
+
+    pub fn with_indexes<'a>(mut self, indexes: impl IntoIterator<Item = &'a IndexInfo>) -> Self {
+        let mut sorted: Vec<&IndexInfo> = indexes.into_iter().collect();
+        sorted.sort_by_key(|index| index.seq_in_index);
+        for index in sorted {
+            let table_key = format!("`{}`.{}", index.table_schema, index.table_name);
+            let index_model = self
+                .tables
+                .entry(table_key)
+                .or_default()
+                .indexes
+                .entry(index.index_name.clone())
+                .or_insert_with(|| IndexModel { unique: matches!(index.non_unique, Some(0)), columns: Vec::new() });
+            index_model.columns.push(index.column_name.clone());
+        }
+        self
+    }
+
+    pub fn apply(&mut self, statement: &MigrationStatement) -> Result<(), String> {
+        match statement {
+            MigrationStatement::CreateTable { table, columns } => {
+                if self.tables.contains_key(table) {
+                    return Err(format!("Table {} already exists", table));
+                }
+                let mut table_model = TableModel::default();
+                for definition in columns {
+                    table_model.columns.insert(definition_name(definition), definition.clone());
+                }
+                self.tables.insert(table.clone(), table_model);
+            }
+            MigrationStatement::DropTable { table } => {
+                if self.tables.remove(table).is_none() {
+                    return Err(format!("Table {} does not exist", table));
+                }
+            }
+            MigrationStatement::AddColumn { table, definition } => {
+                let table_model = self.table_mut(table)?;
+                let column = definition_name(definition);
+                if table_model.columns.contains_key(&column) {
+                    return Err(format!("Column {} already exists in {}", column, table));
+                }
+                table_model.columns.insert(column, definition.clone());
+            }
+            MigrationStatement::DropColumn { table, column } => {
+                let table_model = self.table_mut(table)?;
+                if table_model.columns.shift_remove(column).is_none() {
+                    return Err(format!("Column {} does not exist in {}", column, table));
+                }
+                // Dropping a column removes it from every index, and drops indexes left without columns
+                for index in table_model.indexes.values_mut() {
+                    index.columns.retain(|name| name != column);
+                }
+                table_model.indexes.retain(|_, index| !index.columns.is_empty());
+            }
+            MigrationStatement::ModifyColumn { table, definition } => {
+                let table_model = self.table_mut(table)?;
+                let column = definition_name(definition);
+                match table_model.columns.get_mut(&column) {
+                    Some(existing) => *existing = definition.clone(),
+                    None => return Err(format!("Column {} does not exist in {}", column, table)),
+                }
+            }
+            MigrationStatement::ChangeColumn { table, column, definition } => {
+                let table_model = self.table_mut(table)?;
+                let new_name = definition_name(definition);
+                let position = table_model
+                    .columns
+                    .get_index_of(column)
+                    .ok_or_else(|| format!("Column {} does not exist in {}", column, table))?;
+                if new_name != *column && table_model.columns.contains_key(&new_name) {
+                    return Err(format!("Column {} already exists in {}", new_name, table));
+                }
+                table_model.columns.shift_remove(column);
+                table_model.columns.shift_insert(position, new_name.clone(), definition.clone());
+                for index in table_model.indexes.values_mut() {
+                    for name in index.columns.iter_mut() {
+                        if name == column {
+                            *name = new_name.clone();
+                        }
+                    }
+                }
+            }
+            MigrationStatement::CreateIndex { table, index, unique, columns } => {
+                // Index exports do not carry table definitions, so an index may introduce its table
+                let table_model = self.tables.entry(table.clone()).or_default();
+                if table_model.indexes.contains_key(index) {
+                    return Err(format!("Index {} already exists on {}", index, table));
+                }
+                if !table_model.columns.is_empty() {
+                    if let Some(missing) = columns.iter().find(|column| !table_model.columns.contains_key(*column)) {
+                        return Err(format!("Index {} references unknown column {} in {}", index, missing, table));
+                    }
+                }
+                table_model.indexes.insert(index.clone(), IndexModel { unique: *unique, columns: columns.clone() });
+            }
+            MigrationStatement::DropIndex { table, index } => {
+                let table_model = self.table_mut(table)?;
+                if table_model.indexes.shift_remove(index).is_none() {
+                    return Err(format!("Index {} does not exist on {}", index, table));
+                }
+                if table_model.columns.is_empty() && table_model.indexes.is_empty() {
+                    self.tables.remove(table);
+                }
+            }
+            MigrationStatement::RenameIndex { table, index, new_index } => {
+                let table_model = self.table_mut(table)?;
+                if table_model.indexes.contains_key(new_index) {
+                    return Err(format!("Index {} already exists on {}", new_index, table));
+                }
+                let position = table_model
+                    .indexes
+                    .get_index_of(index)
+                    .ok_or_else(|| format!("Index {} does not exist on {}", index, table))?;
+                if let Some(index_model) = table_model.indexes.shift_remove(index) {
+                    table_model.indexes.shift_insert(position, new_index.clone(), index_model);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Describe every difference to another model, ignoring column and index order
+    pub fn differences(&self, other: &SchemaModel) -> Vec<String> {
+        let mut differences = Vec::new();
+        let mut table_keys: Vec<&String> = self.tables.keys().chain(other.tables.keys()).collect();
+        table_keys.sort();
+        table_keys.dedup();
+
+        for table_key in table_keys {
+            table_differences(table_key, self.tables.get(table_key), other.tables.get(table_key), &mut differences);
+        }
+        differences
+    }
+
+    fn table_mut(&mut self, table: &str) -> Result<&mut TableModel, String> {
+        self.tables
+            .get_mut(table)
+            .ok_or_else(|| format!("Table {} does not exist", table))
+    }
+}
+
+// Differences between the two versions of one table, in the wording of SchemaModel::differences
+fn table_differences(table_key: &str, table1: Option<&TableModel>, table2: Option<&TableModel>, differences: &mut Vec<String>) {
+    let (table1, table2) = match (table1, table2) {
+        (Some(table1), Some(table2)) => (table1, table2),
+        (Some(_), None) => {
+            differences.push(format!("Table {} is only in the first schema", table_key));
+            return;
+        }
+        (None, Some(_)) => {
+            differences.push(format!("Table {} is only in the second schema", table_key));
+            return;
+        }
+        (None, None) => return,
+    };
+
+    for (column, definition) in &table1.columns {
+        match table2.columns.get(column) {
+            Some(other_definition) if other_definition == definition => {}
+            Some(other_definition) => differences.push(format!(
+                "Column {}.{} differs: {} / {}",
+                table_key, column, definition, other_definition
+            )),
+            None => differences.push(format!("Column {}.{} is only in the first schema", table_key, column)),
+        }
+    }
+    for column in table2.columns.keys() {
+        if !table1.columns.contains_key(column) {
+            differences.push(format!("Column {}.{} is only in the second schema", table_key, column));
+        }
+    }
+
+    for (index, index_model) in &table1.indexes {
+        match table2.indexes.get(index) {
+            Some(other_index) if other_index == index_model => {}
+            Some(other_index) => differences.push(format!(
+                "Index {} on {} differs: {:?} / {:?}",
+                index, table_key, index_model, other_index
+            )),
+            None => differences.push(format!("Index {} on {} is only in the first schema", index, table_key)),
+        }
+    }
+    for index in table2.indexes.keys() {
+        if !table1.indexes.contains_key(index) {
+            differences.push(format!("Index {} on {} is only in the second schema", index, table_key));
+        }
+    }
+}
+
+// Apply the generated forward and reverse column scripts and check that a re-diff is empty
+pub fn verify_roundtrip(columns1: Vec<ColumnInfo>, columns2: Vec<ColumnInfo>) -> Result<(), Vec<String>> {
+    let model1 = SchemaModel::from_columns(&columns1);
+    let model2 = SchemaModel::from_columns(&columns2);
+
+    let diff = compare_schema_maps(&build_schema_map(columns1), &build_schema_map(columns2));
+    let sql_statements = generate_sql_diff(&diff);
+
+    verify_sql(&model1, &model2, &sql_statements)
+}
+
+// Apply the generated forward and reverse index scripts and check that a re-diff is empty
+pub fn verify_index_roundtrip(indexes1: Vec<IndexInfo>, indexes2: Vec<IndexInfo>) -> Result<(), Vec<String>> {
+    let model1 = SchemaModel::new().with_indexes(&indexes1);
+    let model2 = SchemaModel::new().with_indexes(&indexes2);
+
+    let diff = compare_index_maps(&build_index_map(indexes1), &build_index_map(indexes2));
+    let sql_statements = generate_sql_index_diff(&diff);
+
+    verify_sql(&model1, &model2, &sql_statements)
+}
+
+// Apply the forward and reverse sections of generated SQL to the models of both schemas
+pub fn verify_sql(
+    model1: &SchemaModel,
+    model2: &SchemaModel,
+    sql_statements: &HashMap<String, String>
+) -> Result<(), Vec<String>> {
+    let mut errors = verify_script("forward", model1, model2, &forward_script(sql_statements));
+    errors.extend(verify_script("reverse", model2, model1, &reverse_script(sql_statements)));
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+// Apply one script to a copy of start and describe what still differs from target,
+// every message prefixed with the direction
+pub fn verify_script(direction: &str, start: &SchemaModel, target: &SchemaModel, script: &str) -> Vec<String> {
+    let statements = match parse_migration_script(script) {
+        Ok(statements) => statements,
+        Err(e) => return vec![format!("{}: {}", direction, e)],
+    };
+    let mut errors = Vec::new();
+    // Only the tables the script touches are copied, the others are compared where they are
+    let touched: HashSet<&str> = statements.iter().map(MigrationStatement::table).collect();
+    let mut model = SchemaModel {
+        tables: start
+            .tables
+            .iter()
+            .filter(|(table_key, _)| touched.contains(table_key.as_str()))
+            .map(|(table_key, table)| (table_key.clone(), table.clone()))
+            .collect(),
+    };
+    for statement in &statements {
+        if let Err(e) = model.apply(statement) {
+            errors.push(format!("{}: {}", direction, e));
+        }
+    }
+
+    let mut table_keys: Vec<&String> = start.tables.keys().chain(model.tables.keys()).chain(target.tables.keys()).collect();
+    table_keys.sort();
+    table_keys.dedup();
+    for table_key in table_keys {
+        let table = if touched.contains(table_key.as_str()) { model.tables.get(table_key) } else { start.tables.get(table_key) };
+        let mut differences = Vec::new();
+        table_differences(table_key, table, target.tables.get(table_key), &mut differences);
+        errors.extend(differences.into_iter().map(|difference| format!("{}: {}", direction, difference)));
+    }
+    errors
+}
+
+// Case-insensitive prefix match that returns the rest of the original string
+fn strip_keyword<'a>(statement: &'a str, upper: &str, keyword: &str) -> Option<&'a str> {
+    if upper.starts_with(keyword) {
+        Some(&statement[keyword.len()..])
+    } else {
+        None
+    }
+}
+
+fn split_on_keyword<'a>(statement: &'a str, keyword: &str) -> Option<(&'a str, &'a str)> {
+    let position = statement.to_ascii_uppercase().find(keyword)?;
+    Some((&statement[..position], &statement[position + keyword.len()..]))
+}
+
+// Split on commas that are not inside parentheses or quotes, e.g. decimal(10,2) or enum('a','b').
+// A backslash inside quotes escapes the next character, as in a mysqldump COMMENT 'it\'s'
+pub(crate) fn split_top_level(body: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    let mut chars = body.chars();
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(q) => {
+                current.push(c);
+                if c == '\\' {
+                    current.extend(chars.next());
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None => match c {
+                '\'' | '"' | '`' => {
+                    quote = Some(c);
+                    current.push(c);
+                }
+                '(' => {
+                    depth += 1;
+                    current.push(c);
+                }
+                ')' => {
+                    depth = depth.saturating_sub(1);
+                    current.push(c);
+                }
+                ',' if depth == 0 => parts.push(std::mem::take(&mut current)),
+                _ => current.push(c),
+            },
+        }
+    }
+    parts.push(current);
+    parts
+}
+
+// Index column list without prefix lengths, e.g. "note(10), id" -> ["note", "id"]
+fn index_columns(columns: &str) -> Vec<String> {
+    split_top_level(columns)
+        .iter()
+        .map(|column| {
+            let column = column.trim();
+            let column = match column.split_once('(') {
+                Some((name, length)) if length.trim_end_matches(')').chars().all(|c| c.is_ascii_digit()) => name,
+                _ => column,
+            };
+            unquote(column.trim())
+        })
+        .collect()
+}
+
+fn normalize_definition(definition: &str) -> String {
+    definition.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+pub(crate) fn definition_name(definition: &str) -> String {
+    unquote(definition.split_whitespace().next().unwrap_or_default())
+}
+
+fn unquote(identifier: &str) -> String {
+    identifier.trim_matches('`').to_string()
+}
@@ -0,0 +1,194 @@
+use serde::{Deserialize, Serialize};
+use regex::Regex;
+use wasm_bindgen::prelude::*;
+use super::{build_schema_map, create_column_info};
+use super::index::{IndexInfo, build_index_map, create_index_info};
+use super::lint::{LintContext, LintRule, LintViolation, Linter, Severity};
+
+// Regex per object kind, None skips the check for that kind.
+// Index patterns may use {table} and {columns} (column names joined by "_")
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct NamingConfig {
+    pub table: Option<String>,
+    pub column: Option<String>,
+    pub index: Option<String>,
+    pub unique_index: Option<String>,
+}
+
+impl Default for NamingConfig {
+    fn default() -> Self {
+        NamingConfig {
+            table: Some("^[a-z][a-z0-9_]*$".to_string()),
+            column: Some("^[a-z][a-z0-9_]*$".to_string()),
+            index: Some("^idx_{table}_{columns}$".to_string()),
+            unique_index: Some("^uk_".to_string()),
+        }
+    }
+}
+
+pub struct NamingConvention {
+    table: Option<Regex>,
+    column: Option<Regex>,
+    index: Option<String>,
+    unique_index: Option<String>,
+}
+
+impl NamingConvention {
+    pub fn new(config: &NamingConfig) -> Result<Self, String> {
+        // Index patterns are compiled per index once placeholders are filled in,
+        // so check here that they are valid with sample values
+        for pattern in [&config.index, &config.unique_index].into_iter().flatten() {
+            compile(&fill_placeholders(pattern, "t", &["c"]))?;
+        }
+        Ok(NamingConvention {
+            table: config.table.as_deref().map(compile).transpose()?,
+            column: config.column.as_deref().map(compile).transpose()?,
+            index: config.index.clone(),
+            unique_index: config.unique_index.clone(),
+        })
+    }
+}
+
+impl LintRule for NamingConvention {
+    fn id(&self) -> &'static str {
+        "naming-convention"
+    }
+
+    fn description(&self) -> &'static str {
+        "Table, column or index name does not follow the naming convention"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, context: &LintContext) -> Vec<LintViolation> {
+        let mut violations = Vec::new();
+
+        for key in context.table_keys() {
+            let table = &context.schema_map[key];
+            let first = match table.values().next() {
+                Some(column) => column,
+                None => continue,
+            };
+            if let Some(pattern) = &self.table {
+                if !pattern.is_match(&first.table_name) {
+                    violations.push(LintViolation {
+                        table_schema: first.table_schema.clone(),
+                        table_name: first.table_name.clone(),
+                        object: None,
+                        message: format!("Table name {} does not match {}", first.table_name, pattern.as_str()),
+                    });
+                }
+            }
+            if let Some(pattern) = &self.column {
+                for column in table.values().filter(|column| !pattern.is_match(&column.column_name)) {
+                    violations.push(LintViolation {
+                        table_schema: column.table_schema.clone(),
+                        table_name: column.table_name.clone(),
+                        object: Some(column.column_name.clone()),
+                        message: format!("Column name {} does not match {}", column.column_name, pattern.as_str()),
+                    });
+                }
+            }
+        }
+
+        let mut index_tables: Vec<&String> = context.index_map.keys().collect();
+        index_tables.sort();
+        for key in index_tables {
+            for (index_name, index_list) in &context.index_map[key] {
+                if index_name == "PRIMARY" {
+                    continue;
+                }
+                let unique = index_list.iter().all(|index| index.non_unique == Some(0));
+                let template = if unique { &self.unique_index } else { &self.index };
+                let (template, first) = match (template, index_list.first()) {
+                    (Some(template), Some(first)) => (template, first),
+                    _ => continue,
+                };
+
+                let mut sorted: Vec<&IndexInfo> = index_list.iter().collect();
+                sorted.sort_by_key(|index| index.seq_in_index);
+                let columns: Vec<&str> = sorted.iter().map(|index| index.column_name.as_str()).collect();
+                let pattern = match compile(&fill_placeholders(template, &first.table_name, &columns)) {
+                    Ok(pattern) => pattern,
+                    Err(_) => continue,
+                };
+
+                if !pattern.is_match(index_name) {
+                    violations.push(LintViolation {
+                        table_schema: first.table_schema.clone(),
+                        table_name: first.table_name.clone(),
+                        object: Some(index_name.clone()),
+                        message: format!("Index name {} does not match {}", index_name, pattern.as_str()),
+                    });
+                }
+            }
+        }
+
+        violations
+    }
+}
+
+#[wasm_bindgen]
+pub fn check_naming(columns_json: &str, indexes_json: &str, naming_json: &str) -> String {
+    let columns = if columns_json.trim().is_empty() {
+        vec![]
+    } else {
+        match create_column_info(columns_json) {
+            Ok(column) => column,
+            Err(e) => return e,
+        }
+    };
+
+    let indexes = if indexes_json.trim().is_empty() {
+        vec![]
+    } else {
+        match create_index_info(indexes_json) {
+            Ok(indexes) => indexes,
+            Err(e) => return e,
+        }
+    };
+
+    let config = if naming_json.trim().is_empty() {
+        NamingConfig::default()
+    } else {
+        match serde_json::from_str::<NamingConfig>(naming_json) {
+            Ok(config) => config,
+            Err(e) => return format!("Error parsing naming config: {}", e),
+        }
+    };
+
+    let rule = match NamingConvention::new(&config) {
+        Ok(rule) => rule,
+        Err(e) => return e,
+    };
+
+    let schema_map = build_schema_map(columns);
+    let index_map = build_index_map(indexes);
+    let context = LintContext {
+        schema_map: &schema_map,
+        index_map: &index_map,
+    };
+    let findings = Linter::empty().with_rule(Box::new(rule)).run(&context);
+
+    let result = serde_json::json!({
+        "findings": findings
+    });
+
+    match serde_json::to_string(&result) {
+        Ok(result) => result,
+        Err(e) => format!("Error serializing result: {}", e),
+    }
+}
+
+fn compile(pattern: &str) -> Result<Regex, String> {
+    Regex::new(pattern).map_err(|e| format!("Error parsing naming pattern {}: {}", pattern, e))
+}
+
+fn fill_placeholders(template: &str, table: &str, columns: &[&str]) -> String {
+    template
+        .replace("{table}", &regex::escape(table))
+        .replace("{columns}", &regex::escape(&columns.join("_")))
+}
@@ -0,0 +1,236 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use super::ddl_diff::{parse_columns, parse_indexes};
+use super::full::{FullComparison, TableComparison, TableStatus, compare_columns_and_indexes};
+use super::index::IndexInfo;
+use super::output::FORMAT_VERSION;
+
+// Tool that copies a table in the background and swaps it in, instead of a locking ALTER TABLE
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum OnlineTool {
+    GhOst,
+    #[serde(alias = "pt-osc")]
+    PtOnlineSchemaChange,
+}
+
+impl OnlineTool {
+    pub fn parse(tool: &str) -> Result<OnlineTool, String> {
+        match tool.trim().to_lowercase().as_str() {
+            "gh-ost" | "ghost" => Ok(OnlineTool::GhOst),
+            "pt-osc" | "pt-online-schema-change" => Ok(OnlineTool::PtOnlineSchemaChange),
+            other => Err(format!("Unknown online schema change tool: {}", other)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct OnlineOptions {
+    pub tool: OnlineTool,
+    /// Tables with fewer estimated rows get a plain ALTER TABLE. The estimate is the highest index
+    /// cardinality of the table in Schema 1, tables without one always use the tool
+    #[serde(default)]
+    pub min_rows: Option<u64>,
+    /// Appended to every tool command as is, e.g. "--host=db1 --user=ghost --ask-pass"
+    #[serde(default)]
+    pub tool_options: String,
+}
+
+impl OnlineOptions {
+    pub fn new(tool: OnlineTool) -> Self {
+        OnlineOptions { tool, min_rows: None, tool_options: String::new() }
+    }
+
+    pub fn min_rows(mut self, min_rows: u64) -> Self {
+        self.min_rows = Some(min_rows);
+        self
+    }
+
+    pub fn tool_options<S: Into<String>>(mut self, tool_options: S) -> Self {
+        self.tool_options = tool_options.into();
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeMethod {
+    /// A gh-ost / pt-online-schema-change command
+    Online,
+    /// SQL run directly, for new, dropped and small tables
+    Plain,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TableChangePlan {
+    pub schema: String,
+    pub table: String,
+    pub method: ChangeMethod,
+    /// Why the method was chosen, e.g. "estimated 2500000 rows"
+    pub reason: String,
+    pub estimated_rows: Option<u64>,
+    /// Every column and index change of the table in one clause, empty for new and dropped tables
+    pub alter: String,
+    /// The shell command for online changes, the SQL statements otherwise
+    pub command: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OnlinePlan {
+    pub format_version: u32,
+    /// Every changed table, in `schema`.table order
+    pub tables: Vec<TableChangePlan>,
+}
+
+// One change per table of the forward script. indexes1 are the indexes of Schema 1, the server
+// being changed, and only used for the row estimates
+pub fn plan_online_changes(comparison: &FullComparison, indexes1: &[IndexInfo], options: &OnlineOptions) -> OnlinePlan {
+    let tables = comparison
+        .tables
+        .iter()
+        .filter(|table| !table.forward_sql.is_empty())
+        .map(|table| plan_table(table, indexes1, options))
+        .collect();
+    OnlinePlan { format_version: FORMAT_VERSION, tables }
+}
+
+fn plan_table(table: &TableComparison, indexes1: &[IndexInfo], options: &OnlineOptions) -> TableChangePlan {
+    let estimated_rows = indexes1
+        .iter()
+        .filter(|index| index.table_schema == table.schema && index.table_name == table.table)
+        .filter_map(|index| index.cardinality)
+        .max();
+    let plan = |method: ChangeMethod, reason: String, alter: String, command: String| TableChangePlan {
+        schema: table.schema.clone(),
+        table: table.table.clone(),
+        method,
+        reason,
+        estimated_rows,
+        alter,
+        command,
+    };
+
+    // CREATE TABLE and DROP TABLE do not copy any rows
+    let reason = match table.status {
+        TableStatus::OnlyInFirst => Some("dropped table".to_string()),
+        TableStatus::OnlyInSecond => Some("new table".to_string()),
+        TableStatus::Changed => None,
+    };
+    if let Some(reason) = reason {
+        return plan(ChangeMethod::Plain, reason, String::new(), table.forward_sql.join("\n"));
+    }
+
+    let table_key = format!("`{}`.{}", table.schema, table.table);
+    let alter = table.forward_sql.iter().map(|sql| alter_clause(sql, &table_key)).collect::<Vec<String>>().join(", ");
+    let small = match (options.min_rows, estimated_rows) {
+        (Some(min_rows), Some(rows)) if rows < min_rows => Some(format!("estimated {} rows, below {}", rows, min_rows)),
+        _ => None,
+    };
+    if let Some(reason) = small {
+        let command = format!("ALTER TABLE {} {};", table_key, alter);
+        return plan(ChangeMethod::Plain, reason, alter, command);
+    }
+
+    let reason = match estimated_rows {
+        Some(rows) => format!("estimated {} rows", rows),
+        None => "row count unknown".to_string(),
+    };
+    let mut command = match options.tool {
+        OnlineTool::GhOst => format!(
+            "gh-ost --database={} --table={} --alter={}",
+            shell_quote(&table.schema),
+            shell_quote(&table.table),
+            shell_quote(&alter)
+        ),
+        OnlineTool::PtOnlineSchemaChange => format!(
+            "pt-online-schema-change --alter {} {}",
+            shell_quote(&alter),
+            shell_quote(&format!("D={},t={}", table.schema, table.table))
+        ),
+    };
+    if !options.tool_options.trim().is_empty() {
+        command.push(' ');
+        command.push_str(options.tool_options.trim());
+    }
+    command.push_str(" --execute");
+    plan(ChangeMethod::Online, reason, alter, command)
+}
+
+// The statement of the forward script as a clause of ALTER TABLE table_key
+fn alter_clause(sql: &str, table_key: &str) -> String {
+    let statement = sql.trim().trim_end_matches(';');
+    if let Some(action) = statement.strip_prefix(&format!("ALTER TABLE {} ", table_key)) {
+        return action.to_string();
+    }
+    let on_table = format!(" ON {}", table_key);
+    if let Some(rest) = statement.strip_prefix("DROP INDEX ") {
+        return format!("DROP INDEX {}", rest.trim_end_matches(on_table.as_str()));
+    }
+    for (prefix, clause) in [("CREATE UNIQUE INDEX ", "ADD UNIQUE INDEX"), ("CREATE INDEX ", "ADD INDEX")] {
+        if let Some((name, columns)) = statement.strip_prefix(prefix).and_then(|rest| rest.split_once(&format!("{} ", on_table))) {
+            return format!("{} {} {}", clause, name, columns);
+        }
+    }
+    statement.to_string()
+}
+
+// POSIX shell quoting, plain words are left as they are
+fn shell_quote(text: &str) -> String {
+    let plain = !text.is_empty() && text.chars().all(|c| c.is_ascii_alphanumeric() || "_-.,=/:@%+".contains(c));
+    if plain {
+        return text.to_string();
+    }
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+impl OnlinePlan {
+    // A shell script running the changes in table order. Plain SQL goes through the mysql client,
+    // which like the tools reads its connection settings from ~/.my.cnf
+    pub fn to_script(&self) -> String {
+        let mut script = String::from("#!/bin/sh\nset -e\n");
+        for table in &self.tables {
+            script.push_str(&format!("\n# `{}`.{}: {}\n", table.schema, table.table, table.reason));
+            match table.method {
+                ChangeMethod::Online => script.push_str(&table.command),
+                ChangeMethod::Plain => script.push_str(&format!("mysql -e {}", shell_quote(&table.command))),
+            }
+            script.push('\n');
+        }
+        script
+    }
+}
+
+// Online schema change plan for both export pairs, options_json as OnlineOptions, e.g.
+// {"tool": "gh-ost", "min_rows": 1000000}
+#[wasm_bindgen]
+pub fn online_schema_change(columns_json1: &str, columns_json2: &str, indexes_json1: &str, indexes_json2: &str, options_json: &str) -> String {
+    let options: OnlineOptions = match serde_json::from_str(options_json) {
+        Ok(options) => options,
+        Err(e) => return format!("Error parsing online schema change options: {}", e),
+    };
+    let result = online_plan(columns_json1, columns_json2, indexes_json1, indexes_json2, &options);
+    match result {
+        Ok(plan) => match serde_json::to_string(&plan) {
+            Ok(result) => result,
+            Err(e) => format!("Error serializing result: {}", e),
+        },
+        Err(e) => e,
+    }
+}
+
+fn online_plan(
+    columns_json1: &str,
+    columns_json2: &str,
+    indexes_json1: &str,
+    indexes_json2: &str,
+    options: &OnlineOptions
+) -> Result<OnlinePlan, String> {
+    let indexes1 = parse_indexes(indexes_json1)?;
+    let comparison = compare_columns_and_indexes(
+        parse_columns(columns_json1)?,
+        parse_columns(columns_json2)?,
+        indexes1.clone(),
+        parse_indexes(indexes_json2)?,
+    );
+    Ok(plan_online_changes(&comparison, &indexes1, options))
+}
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+use super::SchemaDiff;
+use super::full::FullComparison;
+use super::index::IndexDiff;
+use super::snapshot::SnapshotComparison;
+
+// Version of the JSON returned by compare_schemas / compare_indexs / compare_full / compare_snapshots.
+// Bump it on any change that is not a purely additive field.
+// 1: tables_only_in_* as (`schema`.table, sql) tuples, no format_version field
+// 2: typed table entries, table_schema on column and index differences
+pub const FORMAT_VERSION: u32 = 2;
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+pub struct SchemaComparison {
+    pub format_version: u32,
+    pub diff: SchemaDiff,
+    /// Generated statements keyed by section, e.g. "Add column in Schema 1 (Schema 2 only)"
+    pub sql: HashMap<String, String>,
+    /// Differences left after applying the generated SQL to an in-memory model, empty when the SQL is complete
+    pub verification_errors: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+pub struct IndexComparison {
+    pub format_version: u32,
+    pub diff: IndexDiff,
+    /// Generated statements keyed by section, e.g. "Add index in Schema 1 (Schema 2 only)"
+    pub sql: HashMap<String, String>,
+    /// Differences left after applying the generated SQL to an in-memory model, empty when the SQL is complete
+    pub verification_errors: Vec<String>,
+}
+
+impl SchemaComparison {
+    pub fn new(diff: SchemaDiff, sql: HashMap<String, String>, verification_errors: Vec<String>) -> Self {
+        SchemaComparison {
+            format_version: FORMAT_VERSION,
+            diff,
+            sql,
+            verification_errors,
+        }
+    }
+}
+
+impl IndexComparison {
+    pub fn new(diff: IndexDiff, sql: HashMap<String, String>, verification_errors: Vec<String>) -> Self {
+        IndexComparison {
+            format_version: FORMAT_VERSION,
+            diff,
+            sql,
+            verification_errors,
+        }
+    }
+}
+
+// Any of the documents, only used to describe both in one JSON Schema
+#[derive(JsonSchema)]
+#[serde(untagged)]
+#[allow(dead_code)]
+enum ComparisonOutput {
+    Schema(SchemaComparison),
+    Index(IndexComparison),
+    Full(FullComparison),
+    Snapshot(SnapshotComparison),
+}
+
+// JSON Schema (draft 7) of the compare_schemas, compare_indexs, compare_full and compare_snapshots output
+pub fn output_schema() -> serde_json::Value {
+    let mut schema = serde_json::to_value(schemars::schema_for!(ComparisonOutput)).unwrap_or_default();
+    schema["title"] = serde_json::json!(format!("db_diff comparison output, format_version {}", FORMAT_VERSION));
+    schema
+}
+
+#[wasm_bindgen]
+pub fn output_json_schema() -> String {
+    match serde_json::to_string_pretty(&output_schema()) {
+        Ok(result) => result,
+        Err(e) => format!("Error serializing result: {}", e),
+    }
+}
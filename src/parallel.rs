@@ -0,0 +1,28 @@
+use std::collections::HashMap;
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+use rayon::prelude::*;
+
+// Keys of both maps in sorted order, the order tables are reported in
+pub(crate) fn table_keys<'a, A, B>(map1: &'a HashMap<String, A>, map2: &'a HashMap<String, B>) -> Vec<&'a String> {
+    let mut keys: Vec<&String> = map1.keys().chain(map2.keys().filter(|key| !map1.contains_key(*key))).collect();
+    keys.sort();
+    keys
+}
+
+// Run compare for every table and return the results in the order of table_keys.
+// With the "parallel" feature native builds spread the tables over the rayon thread pool,
+// wasm builds always run them one after the other
+pub(crate) fn map_tables<'a, R, F>(table_keys: &[&'a String], compare: F) -> Vec<R>
+where
+    R: Send,
+    F: Fn(&'a String) -> R + Sync + Send,
+{
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    {
+        table_keys.par_iter().map(|key| compare(key)).collect()
+    }
+    #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+    {
+        table_keys.iter().map(|key| compare(key)).collect()
+    }
+}
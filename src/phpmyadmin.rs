@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use serde_json::value::RawValue;
+use wasm_bindgen::prelude::*;
+use super::{ColumnInfo, MariaDBJson};
+use super::index::IndexInfo;
+
+// information_schema tables a phpMyAdmin export of schema metadata is taken from
+const SOURCE_TABLES: [&str; 5] = ["COLUMNS", "STATISTICS", "TABLES", "KEY_COLUMN_USAGE", "REFERENTIAL_CONSTRAINTS"];
+
+// Rows that can be read from a table block, and the information_schema table they come from
+pub trait ExportRow: DeserializeOwned {
+    const SOURCE_TABLE: &'static str;
+}
+
+impl ExportRow for ColumnInfo {
+    const SOURCE_TABLE: &'static str = "COLUMNS";
+}
+
+impl ExportRow for IndexInfo {
+    const SOURCE_TABLE: &'static str = "STATISTICS";
+}
+
+// A "table" block of the export, position is its index in the top-level array
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ExportBlock {
+    pub position: usize,
+    pub name: Option<String>,
+    pub database: Option<String>,
+    pub rows: usize,
+    // Why the block was not used, None for used blocks
+    pub skipped: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PhpMyAdminExport<T> {
+    // phpMyAdmin version from the header block
+    pub version: Option<String>,
+    // Rows of every used block, in export order
+    pub rows: Vec<T>,
+    pub blocks: Vec<ExportBlock>,
+}
+
+impl<T> PhpMyAdminExport<T> {
+    pub fn used_blocks(&self) -> impl Iterator<Item = &ExportBlock> {
+        self.blocks.iter().filter(|block| block.skipped.is_none())
+    }
+}
+
+// Read a phpMyAdmin JSON export: a header, database blocks and any number of table blocks.
+// Rows of every table block holding T are merged. Blocks exported from another
+// information_schema table, e.g. STATISTICS when reading columns, are skipped and reported
+pub fn parse_export<T: ExportRow>(json: &str) -> Result<PhpMyAdminExport<T>, String> {
+    // Rows stay borrowed slices of the input until they are read as T
+    let blocks: Vec<MariaDBJson<&RawValue>> = serde_json::from_str(json)
+        .map_err(|e| format!("Error parsing first JSON: {}", e))?;
+
+    let mut export = PhpMyAdminExport { version: None, rows: Vec::new(), blocks: Vec::new() };
+    let mut current_database: Option<String> = None;
+
+    for (position, block) in blocks.into_iter().enumerate() {
+        match block.r#type.as_str() {
+            "header" => {
+                if position != 0 {
+                    return Err(format!("Block {}: header is only allowed as the first block", position));
+                }
+                export.version = block.version;
+            }
+            "database" => {
+                let name = block.name.ok_or_else(|| format!("Block {}: database block without a name", position))?;
+                current_database = Some(name);
+            }
+            "table" => {
+                if let (Some(expected), Some(database)) = (&current_database, &block.database) {
+                    if expected != database {
+                        return Err(format!(
+                            "Block {}: table {} belongs to database {} but follows database {}",
+                            position,
+                            block.name.as_deref().unwrap_or_default(),
+                            database,
+                            expected
+                        ));
+                    }
+                }
+                let data = block.data.unwrap_or_default();
+                let mut entry = ExportBlock {
+                    position,
+                    name: block.name,
+                    database: block.database.or_else(|| current_database.clone()),
+                    rows: data.len(),
+                    skipped: None,
+                };
+
+                let other_source = entry.name.as_deref().and_then(|name| {
+                    SOURCE_TABLES
+                        .iter()
+                        .find(|source| source.eq_ignore_ascii_case(name) && !source.eq_ignore_ascii_case(T::SOURCE_TABLE))
+                });
+                if let Some(source) = other_source {
+                    entry.skipped = Some(format!("holds information_schema.{} rows", source));
+                } else {
+                    match data.iter().map(|row| serde_json::from_str::<T>(row.get())).collect::<Result<Vec<T>, _>>() {
+                        Ok(rows) => export.rows.extend(rows),
+                        // A block of the expected source table has to be readable
+                        Err(e) if entry.name.as_deref().is_some_and(|name| name.eq_ignore_ascii_case(T::SOURCE_TABLE)) => {
+                            return Err(format!("Block {}: {}", position, e));
+                        }
+                        Err(e) => entry.skipped = Some(format!("rows are not information_schema.{} rows: {}", T::SOURCE_TABLE, e)),
+                    }
+                }
+                export.blocks.push(entry);
+            }
+            other => return Err(format!("Block {}: unknown block type {}", position, other)),
+        }
+    }
+
+    if export.used_blocks().next().is_none() {
+        return Err("Cannot find data".to_string());
+    }
+    Ok(export)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ExportInspection {
+    pub version: Option<String>,
+    pub columns: Vec<ExportBlock>,
+    pub indexes: Vec<ExportBlock>,
+    pub column_rows: usize,
+    pub index_rows: usize,
+    // Set when the export cannot be read as the corresponding kind
+    pub column_error: Option<String>,
+    pub index_error: Option<String>,
+}
+
+// Which blocks of a phpMyAdmin export are read as columns and which as indexes
+#[wasm_bindgen]
+pub fn inspect_export(json: &str) -> String {
+    let columns = parse_export::<ColumnInfo>(json);
+    let indexes = parse_export::<IndexInfo>(json);
+
+    let version = match (&columns, &indexes) {
+        (Ok(export), _) => export.version.clone(),
+        (_, Ok(export)) => export.version.clone(),
+        _ => None,
+    };
+    let (columns, column_rows, column_error) = match columns {
+        Ok(export) => (export.blocks, export.rows.len(), None),
+        Err(e) => (vec![], 0, Some(e)),
+    };
+    let (indexes, index_rows, index_error) = match indexes {
+        Ok(export) => (export.blocks, export.rows.len(), None),
+        Err(e) => (vec![], 0, Some(e)),
+    };
+
+    let result = ExportInspection { version, columns, indexes, column_rows, index_rows, column_error, index_error };
+    match serde_json::to_string(&result) {
+        Ok(result) => result,
+        Err(e) => format!("Error serializing result: {}", e),
+    }
+}
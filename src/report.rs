@@ -0,0 +1,628 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+use super::{ColumnDifference, ColumnInfo, SchemaDiff, TableDefinition, build_schema_map, compare_schema_maps, create_column_info, format_column_definition, generate_sql_diff};
+use super::index::{IndexDiff, IndexInfo, build_index_map, compare_index_maps, create_index_info, generate_sql_index_diff};
+use super::model::{forward_script, reverse_script};
+use super::baseline::BaselineResult;
+use super::snapshot::SnapshotMetadata;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    pub fn parse(format: &str) -> Result<ReportFormat, String> {
+        match format.trim().to_lowercase().as_str() {
+            "" | "md" | "markdown" => Ok(ReportFormat::Markdown),
+            "html" => Ok(ReportFormat::Html),
+            other => Err(format!("Unknown report format: {}", other)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReportSummary {
+    pub tables_only_in_first: usize,
+    pub tables_only_in_second: usize,
+    pub columns_only_in_first: usize,
+    pub columns_only_in_second: usize,
+    pub columns_with_different_definitions: usize,
+    pub indexes_only_in_first: usize,
+    pub indexes_only_in_second: usize,
+    pub indexes_with_different_definitions: usize,
+    pub indexes_renamed: usize,
+}
+
+impl ReportSummary {
+    pub fn total(&self) -> usize {
+        self.tables_only_in_first
+            + self.tables_only_in_second
+            + self.columns_only_in_first
+            + self.columns_only_in_second
+            + self.columns_with_different_definitions
+            + self.indexes_only_in_first
+            + self.indexes_only_in_second
+            + self.indexes_with_different_definitions
+            + self.indexes_renamed
+    }
+}
+
+// A titled table of the report, rendered the same way in every format
+struct Section {
+    title: &'static str,
+    headers: &'static [&'static str],
+    rows: Vec<Vec<String>>,
+}
+
+pub struct DiffReport<'a> {
+    title: String,
+    schema_diff: Option<&'a SchemaDiff>,
+    index_diff: Option<&'a IndexDiff>,
+    sources: Option<(&'a SnapshotMetadata, &'a SnapshotMetadata)>,
+    baseline: Option<&'a BaselineResult>,
+}
+
+impl<'a> DiffReport<'a> {
+    pub fn new() -> Self {
+        DiffReport {
+            title: "Schema diff report".to_string(),
+            schema_diff: None,
+            index_diff: None,
+            sources: None,
+            baseline: None,
+        }
+    }
+
+    pub fn title<S: Into<String>>(mut self, title: S) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    pub fn with_schema_diff(mut self, diff: &'a SchemaDiff) -> Self {
+        self.schema_diff = Some(diff);
+        self
+    }
+
+    pub fn with_index_diff(mut self, diff: &'a IndexDiff) -> Self {
+        self.index_diff = Some(diff);
+        self
+    }
+
+    // Metadata of the snapshots both sides were loaded from, shown before the summary
+    pub fn with_sources(mut self, first: &'a SnapshotMetadata, second: &'a SnapshotMetadata) -> Self {
+        self.sources = Some((first, second));
+        self
+    }
+
+    // Differences a baseline suppressed and its stale entries, listed after the differences. The
+    // diffs given to the report are expected to have the baseline applied already
+    pub fn with_baseline(mut self, result: &'a BaselineResult) -> Self {
+        self.baseline = Some(result);
+        self
+    }
+
+    pub fn summary(&self) -> ReportSummary {
+        let mut summary = ReportSummary::default();
+        if let Some(diff) = self.schema_diff {
+            summary.tables_only_in_first = diff.tables_only_in_first.len();
+            summary.tables_only_in_second = diff.tables_only_in_second.len();
+            summary.columns_only_in_first = diff.columns_only_in_first.len();
+            summary.columns_only_in_second = diff.columns_only_in_second.len();
+            summary.columns_with_different_definitions = diff.columns_with_different_definitions.len();
+        }
+        if let Some(diff) = self.index_diff {
+            // Index diffs hold one entry per indexed column, count whole indexes instead
+            summary.indexes_only_in_first = group_indexes(&diff.indexes_only_in_first).len();
+            summary.indexes_only_in_second = group_indexes(&diff.indexes_only_in_second).len();
+            let mut modified: Vec<(&str, &str, &str)> = diff
+                .indexes_with_different_definitions
+                .iter()
+                .map(|difference| (difference.table_schema.as_str(), difference.table_name.as_str(), difference.index_name.as_str()))
+                .collect();
+            modified.sort();
+            modified.dedup();
+            summary.indexes_with_different_definitions = modified.len();
+            summary.indexes_renamed = diff.indexes_renamed.len();
+        }
+        summary
+    }
+
+    // Generated SQL of both diffs merged, keyed by the section names of the generators
+    pub fn sql_statements(&self) -> HashMap<String, String> {
+        let mut sql_statements = HashMap::new();
+        if let Some(diff) = self.schema_diff {
+            sql_statements.extend(generate_sql_diff(diff));
+        }
+        if let Some(diff) = self.index_diff {
+            sql_statements.extend(generate_sql_index_diff(diff));
+        }
+        sql_statements
+    }
+
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Markdown => self.to_markdown(),
+            ReportFormat::Html => self.to_html(),
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let summary = self.summary();
+        let mut out = format!("# {}\n\n", self.title);
+
+        let sources = self.source_rows();
+        if !sources.is_empty() {
+            out.push_str("| Source | Schema 1 | Schema 2 |\n| --- | --- | --- |\n");
+            for row in &sources {
+                out.push_str(&format!("| {} | {} | {} |\n", row[0], markdown_cell(&row[1]), markdown_cell(&row[2])));
+            }
+            out.push('\n');
+        }
+
+        if summary.total() == 0 {
+            out.push_str("No differences found.\n");
+            for section in self.baseline_sections() {
+                out.push_str(&markdown_section(&section));
+            }
+            return out;
+        }
+
+        out.push_str(&format!("**{} difference(s)** between Schema 1 and Schema 2.\n\n", summary.total()));
+        out.push_str("| Change | Count |\n| --- | ---: |\n");
+        for (label, count) in summary_rows(&summary) {
+            out.push_str(&format!("| {} | {} |\n", label, count));
+        }
+
+        for section in self.sections() {
+            out.push_str(&markdown_section(&section));
+        }
+
+        let (forward, reverse) = self.scripts();
+        if !forward.is_empty() || !reverse.is_empty() {
+            out.push_str("\n## SQL\n");
+            for (label, script) in [("Schema 1 → Schema 2", forward), ("Schema 2 → Schema 1", reverse)] {
+                if script.is_empty() {
+                    continue;
+                }
+                out.push_str(&format!(
+                    "\n<details>\n<summary>{}</summary>\n\n```sql\n{}\n```\n\n</details>\n",
+                    label, script
+                ));
+            }
+        }
+
+        for section in self.baseline_sections() {
+            out.push_str(&markdown_section(&section));
+        }
+        out
+    }
+
+    // Self-contained page with inline CSS, meant to be attached to a ticket
+    pub fn to_html(&self) -> String {
+        let summary = self.summary();
+        let title = escape_html(&self.title);
+        let mut body = format!("<h1>{}</h1>\n", title);
+
+        let sources = self.source_rows();
+        if !sources.is_empty() {
+            body.push_str("<table class=\"sources\">\n<tr><th>Source</th><th>Schema 1</th><th>Schema 2</th></tr>\n");
+            for row in &sources {
+                let cells: Vec<String> = row.iter().map(|cell| format!("<td>{}</td>", escape_html(cell))).collect();
+                body.push_str(&format!("<tr>{}</tr>\n", cells.concat()));
+            }
+            body.push_str("</table>\n");
+        }
+
+        if summary.total() == 0 {
+            body.push_str("<p>No differences found.</p>\n");
+        } else {
+            body.push_str(&format!(
+                "<p><strong>{} difference(s)</strong> between Schema 1 and Schema 2.</p>\n",
+                summary.total()
+            ));
+            body.push_str("<table class=\"summary\">\n<tr><th>Change</th><th>Count</th></tr>\n");
+            for (label, count) in summary_rows(&summary) {
+                body.push_str(&format!("<tr><td>{}</td><td class=\"count\">{}</td></tr>\n", label, count));
+            }
+            body.push_str("</table>\n");
+
+            for section in self.sections() {
+                body.push_str(&html_section(&section));
+            }
+
+            let (forward, reverse) = self.scripts();
+            if !forward.is_empty() || !reverse.is_empty() {
+                body.push_str("<h2>SQL</h2>\n");
+                for (label, script) in [("Schema 1 → Schema 2", forward), ("Schema 2 → Schema 1", reverse)] {
+                    if script.is_empty() {
+                        continue;
+                    }
+                    body.push_str(&format!(
+                        "<details>\n<summary>{}</summary>\n<pre><code>{}</code></pre>\n</details>\n",
+                        label,
+                        escape_html(&script)
+                    ));
+                }
+            }
+        }
+        for section in self.baseline_sections() {
+            body.push_str(&html_section(&section));
+        }
+
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"UTF-8\">\n<title>{}</title>\n<style>\n{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+            title, REPORT_CSS, body
+        )
+    }
+
+    // One row per metadata field set on either side
+    fn source_rows(&self) -> Vec<Vec<String>> {
+        let Some((first, second)) = self.sources else {
+            return vec![];
+        };
+        [
+            ("Flavor", &first.flavor, &second.flavor),
+            ("Server version", &first.server_version, &second.server_version),
+            ("Captured at", &first.captured_at, &second.captured_at),
+            ("Host", &first.host, &second.host),
+            ("Content hash", &first.content_hash, &second.content_hash),
+        ]
+        .into_iter()
+        .filter(|(_, value1, value2)| value1.is_some() || value2.is_some())
+        .map(|(label, value1, value2)| vec![
+            label.to_string(),
+            value1.clone().unwrap_or_default(),
+            value2.clone().unwrap_or_default(),
+        ])
+        .collect()
+    }
+
+    fn scripts(&self) -> (String, String) {
+        // The generators emit a section in hash map order, sort it so the report is stable
+        let sql_statements: HashMap<String, String> = self
+            .sql_statements()
+            .into_iter()
+            .map(|(section, sql)| {
+                let mut lines: Vec<&str> = sql.lines().collect();
+                // "Modify index" drops and re-creates, keep the drops first
+                lines.sort_by_key(|line| (!line.starts_with("DROP "), *line));
+                (section, lines.join("\n"))
+            })
+            .collect();
+        (forward_script(&sql_statements), reverse_script(&sql_statements))
+    }
+
+    fn sections(&self) -> Vec<Section> {
+        let mut sections = Vec::new();
+
+        if let Some(diff) = self.schema_diff {
+            sections.push(Section {
+                title: "Tables only in Schema 1",
+                headers: &["Table"],
+                rows: table_rows(&diff.tables_only_in_first),
+            });
+            sections.push(Section {
+                title: "Tables only in Schema 2",
+                headers: &["Table"],
+                rows: table_rows(&diff.tables_only_in_second),
+            });
+            sections.push(Section {
+                title: "Columns only in Schema 1",
+                headers: &["Table", "Column", "Definition"],
+                rows: column_rows(&diff.columns_only_in_first),
+            });
+            sections.push(Section {
+                title: "Columns only in Schema 2",
+                headers: &["Table", "Column", "Definition"],
+                rows: column_rows(&diff.columns_only_in_second),
+            });
+            sections.push(Section {
+                title: "Columns with different definitions",
+                headers: &["Table", "Column", "Schema 1", "Schema 2"],
+                rows: column_difference_rows(&diff.columns_with_different_definitions),
+            });
+        }
+
+        if let Some(diff) = self.index_diff {
+            sections.push(Section {
+                title: "Indexes only in Schema 1",
+                headers: &["Table", "Index", "Definition"],
+                rows: index_rows(&diff.indexes_only_in_first),
+            });
+            sections.push(Section {
+                title: "Indexes only in Schema 2",
+                headers: &["Table", "Index", "Definition"],
+                rows: index_rows(&diff.indexes_only_in_second),
+            });
+
+            let mut rows: Vec<Vec<String>> = diff
+                .indexes_with_different_definitions
+                .iter()
+                .map(|difference| vec![
+                    table_label(&difference.table_schema, &difference.table_name),
+                    difference.index_name.clone(),
+                    index_column_definition(&difference.first),
+                    index_column_definition(&difference.second),
+                ])
+                .collect();
+            rows.sort();
+            sections.push(Section {
+                title: "Indexes with different definitions",
+                headers: &["Table", "Index", "Schema 1", "Schema 2"],
+                rows,
+            });
+
+            let mut rows: Vec<Vec<String>> = diff
+                .indexes_renamed
+                .iter()
+                .map(|rename| vec![
+                    table_label(&rename.table_schema, &rename.table_name),
+                    rename.old_index_name.clone(),
+                    rename.new_index_name.clone(),
+                ])
+                .collect();
+            rows.sort();
+            sections.push(Section {
+                title: "Renamed indexes",
+                headers: &["Table", "Name in Schema 1", "Name in Schema 2"],
+                rows,
+            });
+        }
+
+        sections.retain(|section| !section.rows.is_empty());
+        sections
+    }
+
+    fn baseline_sections(&self) -> Vec<Section> {
+        let Some(result) = self.baseline else {
+            return vec![];
+        };
+        let mut sections = vec![
+            Section {
+                title: "Suppressed by baseline",
+                headers: &["Object", "Change", "Reason"],
+                rows: result
+                    .suppressed
+                    .iter()
+                    .map(|suppressed| vec![suppressed.path.clone(), suppressed.kind.as_str().to_string(), suppressed.reason.clone().unwrap_or_default()])
+                    .collect(),
+            },
+            Section {
+                title: "Stale baseline entries",
+                headers: &["Path", "Change", "Reason"],
+                rows: result
+                    .stale
+                    .iter()
+                    .map(|entry| vec![entry.path.clone(), entry.kind.as_str().to_string(), entry.reason.clone().unwrap_or_default()])
+                    .collect(),
+            },
+        ];
+        sections.retain(|section| !section.rows.is_empty());
+        sections
+    }
+}
+
+impl Default for DiffReport<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Empty inputs skip that part of the report, so either export pair may be omitted
+#[wasm_bindgen]
+pub fn generate_report(columns_json1: &str, columns_json2: &str, indexes_json1: &str, indexes_json2: &str, format: &str) -> String {
+    let format = match ReportFormat::parse(format) {
+        Ok(format) => format,
+        Err(e) => return e,
+    };
+
+    let (schema_diff, index_diff) = match parse_diffs(columns_json1, columns_json2, indexes_json1, indexes_json2) {
+        Ok(diffs) => diffs,
+        Err(e) => return e,
+    };
+
+    let mut report = DiffReport::new();
+    if let Some(diff) = &schema_diff {
+        report = report.with_schema_diff(diff);
+    }
+    if let Some(diff) = &index_diff {
+        report = report.with_index_diff(diff);
+    }
+    report.render(format)
+}
+
+// Compare the column and index export pairs, a pair of empty inputs gives None
+pub(crate) fn parse_diffs(
+    columns_json1: &str,
+    columns_json2: &str,
+    indexes_json1: &str,
+    indexes_json2: &str
+) -> Result<(Option<SchemaDiff>, Option<IndexDiff>), String> {
+    let schema_diff = if columns_json1.trim().is_empty() && columns_json2.trim().is_empty() {
+        None
+    } else {
+        let columns1 = create_column_info(columns_json1)?;
+        let columns2 = create_column_info(columns_json2)?;
+        Some(compare_schema_maps(&build_schema_map(columns1), &build_schema_map(columns2)))
+    };
+
+    let index_diff = if indexes_json1.trim().is_empty() && indexes_json2.trim().is_empty() {
+        None
+    } else {
+        let indexes1 = create_index_info(indexes_json1)?;
+        let indexes2 = create_index_info(indexes_json2)?;
+        Some(compare_index_maps(&build_index_map(indexes1), &build_index_map(indexes2)))
+    };
+
+    Ok((schema_diff, index_diff))
+}
+
+const REPORT_CSS: &str = "body { font-family: Arial, sans-serif; margin: 2em; color: #222; }
+h1 { border-bottom: 2px solid #ddd; padding-bottom: 0.3em; }
+h2 { margin-top: 1.5em; }
+table { border-collapse: collapse; margin: 0.5em 0; }
+th, td { border: 1px solid #ddd; padding: 4px 10px; text-align: left; vertical-align: top; }
+th { background: #f4f4f4; }
+td.count { text-align: right; }
+code, pre { font-family: Consolas, monospace; font-size: 0.95em; }
+pre { background: #f8f8f8; border: 1px solid #ddd; padding: 10px; overflow-x: auto; }
+summary { cursor: pointer; font-weight: bold; margin: 0.5em 0; }
+";
+
+fn summary_rows(summary: &ReportSummary) -> Vec<(&'static str, usize)> {
+    vec![
+        ("Tables only in Schema 1", summary.tables_only_in_first),
+        ("Tables only in Schema 2", summary.tables_only_in_second),
+        ("Columns only in Schema 1", summary.columns_only_in_first),
+        ("Columns only in Schema 2", summary.columns_only_in_second),
+        ("Columns with different definitions", summary.columns_with_different_definitions),
+        ("Indexes only in Schema 1", summary.indexes_only_in_first),
+        ("Indexes only in Schema 2", summary.indexes_only_in_second),
+        ("Indexes with different definitions", summary.indexes_with_different_definitions),
+        ("Renamed indexes", summary.indexes_renamed),
+    ]
+    .into_iter()
+    .filter(|(_, count)| *count > 0)
+    .collect()
+}
+
+pub(crate) fn table_label(table_schema: &str, table_name: &str) -> String {
+    format!("{}.{}", table_schema, table_name)
+}
+
+fn table_rows(tables: &[TableDefinition]) -> Vec<Vec<String>> {
+    let mut rows: Vec<Vec<String>> = tables
+        .iter()
+        .map(|table| vec![table_label(&table.schema, &table.table)])
+        .collect();
+    rows.sort();
+    rows
+}
+
+fn column_rows(columns: &[ColumnInfo]) -> Vec<Vec<String>> {
+    let mut rows: Vec<Vec<String>> = columns
+        .iter()
+        .map(|column| vec![
+            table_label(&column.table_schema, &column.table_name),
+            column.column_name.clone(),
+            format_column_definition(column),
+        ])
+        .collect();
+    rows.sort();
+    rows
+}
+
+fn column_difference_rows(differences: &[ColumnDifference]) -> Vec<Vec<String>> {
+    let mut rows: Vec<Vec<String>> = differences
+        .iter()
+        .map(|difference| vec![
+            table_label(&difference.table_schema, &difference.table_name),
+            difference.column_name.clone(),
+            format_column_definition(&difference.first),
+            format_column_definition(&difference.second),
+        ])
+        .collect();
+    rows.sort();
+    rows
+}
+
+// One row per index with its columns in seq_in_index order
+fn index_rows(indexes: &[IndexInfo]) -> Vec<Vec<String>> {
+    let mut rows: Vec<Vec<String>> = group_indexes(indexes)
+        .into_iter()
+        .map(|((table_schema, table_name, index_name), mut columns)| {
+            columns.sort_by_key(|index| index.seq_in_index);
+            let unique = columns.iter().all(|index| index.non_unique == Some(0));
+            let names: Vec<String> = columns.iter().map(|index| index_column_name(index)).collect();
+            let definition = format!(
+                "{}{} ({})",
+                if unique { "UNIQUE " } else { "" },
+                columns[0].index_type,
+                names.join(", ")
+            );
+            vec![table_label(table_schema, table_name), index_name.to_string(), definition]
+        })
+        .collect();
+    rows.sort();
+    rows
+}
+
+pub(crate) fn group_indexes(indexes: &[IndexInfo]) -> HashMap<(&str, &str, &str), Vec<&IndexInfo>> {
+    let mut groups: HashMap<(&str, &str, &str), Vec<&IndexInfo>> = HashMap::new();
+    for index in indexes {
+        groups
+            .entry((index.table_schema.as_str(), index.table_name.as_str(), index.index_name.as_str()))
+            .or_default()
+            .push(index);
+    }
+    groups
+}
+
+fn index_column_name(index: &IndexInfo) -> String {
+    match index.sub_part {
+        Some(sub_part) => format!("{}({})", index.column_name, sub_part),
+        None => index.column_name.clone(),
+    }
+}
+
+fn index_column_definition(index: &IndexInfo) -> String {
+    format!(
+        "{}{} {} #{}",
+        if index.non_unique == Some(0) { "UNIQUE " } else { "" },
+        index.index_type,
+        index_column_name(index),
+        index.seq_in_index.map(|seq| seq.to_string()).unwrap_or_default()
+    )
+}
+
+fn markdown_section(section: &Section) -> String {
+    let mut out = format!("\n## {} ({})\n\n", section.title, section.rows.len());
+    out.push_str(&format!("| {} |\n", section.headers.join(" | ")));
+    out.push_str(&format!("|{}\n", " --- |".repeat(section.headers.len())));
+    for row in &section.rows {
+        let cells: Vec<String> = row.iter().map(|cell| markdown_cell(cell)).collect();
+        out.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+    out
+}
+
+fn html_section(section: &Section) -> String {
+    let mut out = format!("<h2>{} ({})</h2>\n<table>\n<tr>", escape_html(section.title), section.rows.len());
+    for header in section.headers {
+        out.push_str(&format!("<th>{}</th>", escape_html(header)));
+    }
+    out.push_str("</tr>\n");
+    for row in &section.rows {
+        out.push_str("<tr>");
+        for cell in row {
+            out.push_str(&format!("<td><code>{}</code></td>", escape_html(cell)));
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</table>\n");
+    out
+}
+
+fn markdown_cell(cell: &str) -> String {
+    if cell.is_empty() {
+        return String::new();
+    }
+    let cell = cell.replace('|', "\\|").replace('\n', " ");
+    // Use a longer fence when the value itself contains backticks
+    if cell.contains('`') {
+        format!("`` {} ``", cell)
+    } else {
+        format!("`{}`", cell)
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use super::lint::Severity;
+use super::model::{MigrationStatement, definition_name, forward_script, split_script};
+
+// Risk of running one generated statement against a live database
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StatementRisk {
+    pub rule_id: String,
+    pub severity: Severity,
+    pub table: String,
+    pub statement: String,
+    pub message: String,
+}
+
+// Rule id, severity and description of every kind of risk, used for SARIF rule metadata
+pub const RISK_RULES: [(&str, Severity, &str); 5] = [
+    ("drops-table", Severity::Error, "Statement drops a table and all of its data"),
+    ("drops-column", Severity::Error, "Statement drops a column and its data"),
+    ("rewrites-column", Severity::Warning, "Statement changes a column definition, which may rebuild the table or truncate data"),
+    ("drops-index", Severity::Warning, "Statement drops an index that queries may rely on"),
+    ("additive-change", Severity::Info, "Statement only adds or renames objects"),
+];
+
+pub fn assess_statement(statement: &MigrationStatement, sql: &str) -> StatementRisk {
+    let (rule_id, table, message) = match statement {
+        MigrationStatement::DropTable { table } => ("drops-table", table, format!("Drops table {}", table)),
+        MigrationStatement::DropColumn { table, column } => ("drops-column", table, format!("Drops column {} of {}", column, table)),
+        MigrationStatement::ModifyColumn { table, definition } => {
+            ("rewrites-column", table, format!("Changes column {} of {}", definition_name(definition), table))
+        },
+        MigrationStatement::ChangeColumn { table, column, .. } => {
+            ("rewrites-column", table, format!("Changes column {} of {}", column, table))
+        },
+        MigrationStatement::DropIndex { table, index } => ("drops-index", table, format!("Drops index {} of {}", index, table)),
+        MigrationStatement::CreateTable { table, .. }
+        | MigrationStatement::AddColumn { table, .. }
+        | MigrationStatement::CreateIndex { table, .. }
+        | MigrationStatement::RenameIndex { table, .. } => ("additive-change", table, format!("Adds to {}", table)),
+    };
+    let severity = RISK_RULES
+        .iter()
+        .find(|(id, _, _)| *id == rule_id)
+        .map(|(_, severity, _)| *severity)
+        .unwrap_or(Severity::Info);
+
+    StatementRisk {
+        rule_id: rule_id.to_string(),
+        severity,
+        table: table.clone(),
+        statement: sql.trim().to_string(),
+        message,
+    }
+}
+
+// Risk of each statement of the forward migration (schema 1 into schema 2), in executable order,
+// and the parse error of every statement that could not be assessed
+pub fn assess_sql(sql_statements: &HashMap<String, String>) -> (Vec<StatementRisk>, Vec<String>) {
+    let mut risks = Vec::new();
+    let mut errors = Vec::new();
+    for sql in split_script(&forward_script(sql_statements)) {
+        match MigrationStatement::parse(&sql) {
+            Ok(statement) => risks.push(assess_statement(&statement, &sql)),
+            Err(e) => errors.push(e),
+        }
+    }
+    (risks, errors)
+}
@@ -0,0 +1,287 @@
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use sha2::{Digest, Sha256};
+use wasm_bindgen::prelude::*;
+use super::{ColumnInfo, SchemaDiff, build_schema_map, compare_schema_maps};
+use super::ddl_diff::{parse_columns, parse_indexes};
+use super::full::{FullComparison, compare_columns_and_indexes};
+use super::index::{IndexDiff, IndexInfo, build_index_map, compare_index_maps};
+use super::output::FORMAT_VERSION;
+use super::report::{DiffReport, ReportFormat};
+
+// Version of the snapshot document itself, independent of the comparison FORMAT_VERSION
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+// Where and when a snapshot was taken. Everything is optional so hand-made snapshots load too
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+pub struct SnapshotMetadata {
+    /// Server flavor, e.g. "mysql" or "mariadb"
+    #[serde(default)]
+    pub flavor: Option<String>,
+    /// SELECT VERSION() of the captured server, e.g. "10.11.6-MariaDB"
+    #[serde(default)]
+    pub server_version: Option<String>,
+    /// Capture time as RFC 3339, e.g. "2024-05-01T12:00:00Z"
+    #[serde(default)]
+    pub captured_at: Option<String>,
+    /// Free-form label of the captured server, never a connection string
+    #[serde(default)]
+    pub host: Option<String>,
+    /// "sha256:" followed by the hex digest of the schema content, see Snapshot::content_hash
+    #[serde(default)]
+    pub content_hash: Option<String>,
+}
+
+// One row of information_schema.TABLES
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct TableOptions {
+    pub table_schema: String,
+    pub table_name: String,
+    #[serde(default)]
+    pub engine: Option<String>,
+    #[serde(default)]
+    pub row_format: Option<String>,
+    #[serde(default)]
+    pub table_collation: Option<String>,
+    #[serde(default)]
+    pub create_options: Option<String>,
+    #[serde(default)]
+    pub table_comment: Option<String>,
+}
+
+// One column of a foreign key, information_schema.KEY_COLUMN_USAGE joined with REFERENTIAL_CONSTRAINTS
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct ForeignKey {
+    pub table_schema: String,
+    pub table_name: String,
+    pub constraint_name: String,
+    pub column_name: String,
+    #[serde(default)]
+    pub ordinal_position: Option<u32>,
+    pub referenced_table_schema: String,
+    pub referenced_table_name: String,
+    pub referenced_column_name: String,
+    #[serde(default)]
+    pub update_rule: Option<String>,
+    #[serde(default)]
+    pub delete_rule: Option<String>,
+}
+
+// Columns, indexes and optional table options / foreign keys of one server in a single file
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct Snapshot {
+    pub snapshot_version: u32,
+    #[serde(default)]
+    pub metadata: SnapshotMetadata,
+    #[serde(default)]
+    pub columns: Vec<ColumnInfo>,
+    #[serde(default)]
+    pub indexes: Vec<IndexInfo>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub table_options: Vec<TableOptions>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub foreign_keys: Vec<ForeignKey>,
+}
+
+impl Snapshot {
+    pub fn new(columns: Vec<ColumnInfo>, indexes: Vec<IndexInfo>) -> Self {
+        Snapshot {
+            snapshot_version: SNAPSHOT_VERSION,
+            metadata: SnapshotMetadata::default(),
+            columns,
+            indexes,
+            table_options: Vec::new(),
+            foreign_keys: Vec::new(),
+        }
+    }
+
+    pub fn metadata(mut self, metadata: SnapshotMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    pub fn table_options(mut self, table_options: Vec<TableOptions>) -> Self {
+        self.table_options = table_options;
+        self
+    }
+
+    pub fn foreign_keys(mut self, foreign_keys: Vec<ForeignKey>) -> Self {
+        self.foreign_keys = foreign_keys;
+        self
+    }
+
+    // Rows in a fixed order, so the same schema always serializes to the same document. Columns keep
+    // their order within a table, it is the column order of the table
+    pub fn sorted(mut self) -> Self {
+        self.columns.sort_by(|a, b| (&a.table_schema, &a.table_name).cmp(&(&b.table_schema, &b.table_name)));
+        self.indexes.sort_by(|a, b| {
+            (&a.table_schema, &a.table_name, &a.index_name, a.seq_in_index).cmp(&(&b.table_schema, &b.table_name, &b.index_name, b.seq_in_index))
+        });
+        self.table_options.sort_by(|a, b| (&a.table_schema, &a.table_name).cmp(&(&b.table_schema, &b.table_name)));
+        self.foreign_keys.sort_by(|a, b| {
+            (&a.table_schema, &a.table_name, &a.constraint_name, a.ordinal_position)
+                .cmp(&(&b.table_schema, &b.table_name, &b.constraint_name, b.ordinal_position))
+        });
+        self
+    }
+
+    // Store the content hash in the metadata, call it last
+    pub fn seal(mut self) -> Self {
+        self.metadata.content_hash = Some(self.content_hash());
+        self
+    }
+
+    // Hash of columns, indexes, table options and foreign keys. Rows are hashed in sorted
+    // order so two exports of the same schema match whatever order the server returned
+    pub fn content_hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        hash_rows(&mut hasher, "columns", &self.columns);
+        hash_rows(&mut hasher, "indexes", &self.indexes);
+        hash_rows(&mut hasher, "table_options", &self.table_options);
+        hash_rows(&mut hasher, "foreign_keys", &self.foreign_keys);
+        let digest: String = hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect();
+        format!("sha256:{}", digest)
+    }
+}
+
+fn hash_rows<T: Serialize>(hasher: &mut Sha256, name: &str, rows: &[T]) {
+    let mut serialized: Vec<String> = rows.iter().filter_map(|row| serde_json::to_string(row).ok()).collect();
+    serialized.sort();
+    hasher.update(format!("{}:{}\n", name, serialized.len()));
+    for row in serialized {
+        hasher.update(row);
+        hasher.update("\n");
+    }
+}
+
+// Parse a snapshot and check its version and, when present, its content hash
+pub fn load_snapshot(json: &str) -> Result<Snapshot, String> {
+    let snapshot: Snapshot = serde_json::from_str(json).map_err(|e| format!("Error parsing snapshot: {}", e))?;
+    if snapshot.snapshot_version > SNAPSHOT_VERSION {
+        return Err(format!(
+            "Unsupported snapshot version {}, this build reads up to {}",
+            snapshot.snapshot_version, SNAPSHOT_VERSION
+        ));
+    }
+    if let Some(expected) = &snapshot.metadata.content_hash {
+        let actual = snapshot.content_hash();
+        if *expected != actual {
+            return Err(format!("Snapshot content hash mismatch: expected {}, got {}", expected, actual));
+        }
+    }
+    Ok(snapshot)
+}
+
+// A snapshot document is a JSON object with a snapshot_version, plain exports are arrays
+pub(crate) fn is_snapshot(json: &str) -> bool {
+    #[derive(Deserialize)]
+    struct VersionOnly {
+        snapshot_version: Option<serde::de::IgnoredAny>,
+    }
+    // Checked without building the document, exports can be large
+    json.trim_start().starts_with('{')
+        && serde_json::from_str::<VersionOnly>(json).is_ok_and(|document| document.snapshot_version.is_some())
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+pub struct SnapshotComparison {
+    pub format_version: u32,
+    pub first: SnapshotMetadata,
+    pub second: SnapshotMetadata,
+    pub comparison: FullComparison,
+}
+
+impl SnapshotComparison {
+    pub fn new(snapshot1: Snapshot, snapshot2: Snapshot) -> Self {
+        SnapshotComparison {
+            format_version: FORMAT_VERSION,
+            first: snapshot1.metadata,
+            second: snapshot2.metadata,
+            comparison: compare_columns_and_indexes(snapshot1.columns, snapshot2.columns, snapshot1.indexes, snapshot2.indexes),
+        }
+    }
+}
+
+// Bundle column and index exports into a sealed snapshot, metadata_json may be empty
+#[wasm_bindgen]
+pub fn create_snapshot(columns_json: &str, indexes_json: &str, metadata_json: &str) -> String {
+    let columns = match parse_columns(columns_json) {
+        Ok(columns) => columns,
+        Err(e) => return e,
+    };
+    let indexes = match parse_indexes(indexes_json) {
+        Ok(indexes) => indexes,
+        Err(e) => return e,
+    };
+    let metadata = if metadata_json.trim().is_empty() {
+        SnapshotMetadata::default()
+    } else {
+        match serde_json::from_str::<SnapshotMetadata>(metadata_json) {
+            Ok(metadata) => metadata,
+            Err(e) => return format!("Error parsing snapshot metadata: {}", e),
+        }
+    };
+
+    let snapshot = Snapshot::new(columns, indexes).metadata(metadata).seal();
+    match serde_json::to_string_pretty(&snapshot) {
+        Ok(result) => result,
+        Err(e) => format!("Error serializing result: {}", e),
+    }
+}
+
+#[wasm_bindgen]
+pub fn compare_snapshots(snapshot_json1: &str, snapshot_json2: &str) -> String {
+    let (snapshot1, snapshot2) = match load_pair(snapshot_json1, snapshot_json2) {
+        Ok(pair) => pair,
+        Err(e) => return e,
+    };
+
+    match serde_json::to_string(&SnapshotComparison::new(snapshot1, snapshot2)) {
+        Ok(result) => result,
+        Err(e) => format!("Error serializing result: {}", e),
+    }
+}
+
+// Same report as generate_report, with the metadata of both snapshots as a header
+#[wasm_bindgen]
+pub fn generate_snapshot_report(snapshot_json1: &str, snapshot_json2: &str, format: &str) -> String {
+    let format = match ReportFormat::parse(format) {
+        Ok(format) => format,
+        Err(e) => return e,
+    };
+    let (snapshot1, snapshot2) = match load_pair(snapshot_json1, snapshot_json2) {
+        Ok(pair) => pair,
+        Err(e) => return e,
+    };
+
+    snapshot_report(snapshot1, snapshot2, format)
+}
+
+// Report of two loaded snapshots, shared with the CLI
+pub fn snapshot_report(snapshot1: Snapshot, snapshot2: Snapshot, format: ReportFormat) -> String {
+    let (schema_diff, index_diff) = snapshot_diffs(&snapshot1, &snapshot2);
+    DiffReport::new()
+        .with_sources(&snapshot1.metadata, &snapshot2.metadata)
+        .with_schema_diff(&schema_diff)
+        .with_index_diff(&index_diff)
+        .render(format)
+}
+
+// Column and index diffs of two snapshots, as the report and the baseline work on them
+pub fn snapshot_diffs(snapshot1: &Snapshot, snapshot2: &Snapshot) -> (SchemaDiff, IndexDiff) {
+    let schema_diff = compare_schema_maps(
+        &build_schema_map(snapshot1.columns.clone()),
+        &build_schema_map(snapshot2.columns.clone()),
+    );
+    let index_diff = compare_index_maps(
+        &build_index_map(snapshot1.indexes.clone()),
+        &build_index_map(snapshot2.indexes.clone()),
+    );
+    (schema_diff, index_diff)
+}
+
+fn load_pair(snapshot_json1: &str, snapshot_json2: &str) -> Result<(Snapshot, Snapshot), String> {
+    let snapshot1 = load_snapshot(snapshot_json1).map_err(|e| format!("First snapshot: {}", e))?;
+    let snapshot2 = load_snapshot(snapshot_json2).map_err(|e| format!("Second snapshot: {}", e))?;
+    Ok((snapshot1, snapshot2))
+}
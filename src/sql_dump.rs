@@ -0,0 +1,503 @@
+use serde::{Deserialize, Serialize};
+use super::ColumnInfo;
+use super::index::IndexInfo;
+use super::model::split_top_level;
+
+// Schema used for tables of a dump that never names its database
+pub const DEFAULT_SCHEMA: &str = "default";
+
+// Words that end the type part of a column definition
+const COLUMN_ATTRIBUTES: [&str; 19] = [
+    "NOT", "NULL", "DEFAULT", "AUTO_INCREMENT", "COMMENT", "CHARACTER", "CHARSET", "COLLATE", "PRIMARY",
+    "UNIQUE", "KEY", "ON", "GENERATED", "AS", "INVISIBLE", "VISIBLE", "CHECK", "REFERENCES", "COLUMN_FORMAT",
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct SqlDump {
+    pub columns: Vec<ColumnInfo>,
+    pub indexes: Vec<IndexInfo>,
+}
+
+// Read the CREATE TABLE statements of a mysqldump / mariadb-dump file into information_schema rows.
+// Data statements are ignored, the schema comes from USE, the "-- Host: ... Database: x" header
+// or a qualified table name, falling back to default_schema
+pub fn parse_sql_dump(sql: &str, default_schema: &str) -> Result<SqlDump, String> {
+    let mut dump = SqlDump::default();
+    let mut schema = header_database(sql).unwrap_or_else(|| default_schema.to_string());
+    let mariadb = header_is_mariadb(sql);
+
+    for statement in split_statements(&strip_comments(sql)) {
+        let statement = statement.trim();
+        let upper = statement.to_ascii_uppercase();
+        if let Some(database) = upper.strip_prefix("USE ").map(|_| &statement[4..]) {
+            schema = unquote(database.trim());
+        } else if upper.starts_with("CREATE TABLE") {
+            parse_create_table(statement, &schema, mariadb, &mut dump)?;
+        }
+    }
+
+    if dump.columns.is_empty() {
+        return Err("Cannot find CREATE TABLE statements".to_string());
+    }
+    Ok(dump)
+}
+
+fn header_database(sql: &str) -> Option<String> {
+    sql.lines()
+        .take_while(|line| line.starts_with("--") || line.trim().is_empty())
+        .find_map(|line| line.split_once("Database: "))
+        .map(|(_, database)| database.trim().to_string())
+        .filter(|database| !database.is_empty())
+}
+
+// "-- MariaDB dump 10.19 ..." on the first line, mysqldump writes "-- MySQL dump"
+fn header_is_mariadb(sql: &str) -> bool {
+    sql.lines().next().is_some_and(|line| line.starts_with("--") && line.contains("MariaDB dump"))
+}
+
+fn parse_create_table(statement: &str, schema: &str, mariadb: bool, dump: &mut SqlDump) -> Result<(), String> {
+    let open = statement.find('(').ok_or_else(|| format!("CREATE TABLE without columns: {}", statement))?;
+    let close = matching_parenthesis(statement, open).ok_or_else(|| format!("Unbalanced parentheses in: {}", statement))?;
+
+    let name = statement["CREATE TABLE".len()..open].trim();
+    let name = match name.to_ascii_uppercase().strip_prefix("IF NOT EXISTS") {
+        Some(_) => name["IF NOT EXISTS".len()..].trim(),
+        None => name,
+    };
+    let (table_schema, table_name) = match split_qualified(name) {
+        (Some(database), table) => (database, table),
+        (None, table) => (schema.to_string(), table),
+    };
+
+    let mut columns: Vec<ColumnInfo> = Vec::new();
+    let mut keys: Vec<(String, bool, String, String)> = Vec::new();
+    for definition in split_top_level(&statement[open + 1..close]) {
+        let definition = definition.trim();
+        // CONSTRAINT `name` UNIQUE KEY (...) names the index after the constraint
+        let (constraint, definition) = match definition.get(..11).filter(|prefix| prefix.eq_ignore_ascii_case("CONSTRAINT ")) {
+            Some(_) => match definition[11..].trim_start().split_once(char::is_whitespace) {
+                Some((name, rest)) => (unquote(name), rest.trim_start()),
+                None => continue,
+            },
+            None => (String::new(), definition),
+        };
+        let upper = definition.to_ascii_uppercase();
+
+        if upper.starts_with("PRIMARY KEY") {
+            keys.push(("PRIMARY".to_string(), true, "BTREE".to_string(), definition["PRIMARY KEY".len()..].to_string()));
+        } else if let Some(kind) = ["UNIQUE", "FULLTEXT", "SPATIAL", "KEY", "INDEX"].iter().find(|kind| starts_with_word(&upper, kind)) {
+            let mut rest = definition[kind.len()..].trim_start();
+            if *kind != "KEY" && *kind != "INDEX" {
+                for word in ["KEY", "INDEX"] {
+                    if starts_with_word(&rest.to_ascii_uppercase(), word) {
+                        rest = rest[word.len()..].trim_start();
+                    }
+                }
+            }
+            let index_type = match *kind {
+                "FULLTEXT" | "SPATIAL" => kind.to_string(),
+                _ => "BTREE".to_string(),
+            };
+            let (name, rest) = match rest.find('(') {
+                Some(position) if rest[..position].trim().is_empty() => (constraint, &rest[position..]),
+                Some(position) => (unquote(rest[..position].trim()), &rest[position..]),
+                None => return Err(format!("Key without columns in `{}`: {}", table_name, definition)),
+            };
+            keys.push((name, *kind == "UNIQUE", index_type, rest.to_string()));
+        } else if ["FOREIGN", "CHECK", "PERIOD"].iter().any(|word| starts_with_word(&upper, word)) {
+            continue;
+        } else if !definition.is_empty() {
+            let (column, inline_key) = parse_column(definition, &table_schema, &table_name, mariadb)?;
+            if let Some((name, unique)) = inline_key {
+                keys.push((name, unique, "BTREE".to_string(), format!("(`{}`)", column.column_name)));
+            }
+            columns.push(column);
+        }
+    }
+
+    for (name, unique, index_type, rest) in keys {
+        let open = rest.find('(').ok_or_else(|| format!("Key without columns in `{}`: {}", table_name, rest))?;
+        let close = matching_parenthesis(&rest, open).ok_or_else(|| format!("Unbalanced parentheses in: {}", rest))?;
+        let index_type = match rest[close + 1..].to_ascii_uppercase().split_once("USING ") {
+            Some((_, using)) => using.split_whitespace().next().unwrap_or("BTREE").to_string(),
+            None => index_type,
+        };
+        let parts: Vec<(String, Option<u64>)> = split_top_level(&rest[open + 1..close]).iter().map(|part| index_part(part)).collect();
+        // An unnamed key is named after its first column, like the server does
+        let name = if name.is_empty() { parts.first().map(|(column, _)| column.clone()).unwrap_or_default() } else { name };
+
+        for (seq, (column_name, sub_part)) in parts.into_iter().enumerate() {
+            let nullable = columns
+                .iter()
+                .find(|column| column.column_name.eq_ignore_ascii_case(&column_name))
+                .map(|column| if column.is_nullable == "YES" && name != "PRIMARY" { "YES" } else { "" })
+                .unwrap_or_default();
+            dump.indexes.push(IndexInfo {
+                table_schema: table_schema.clone(),
+                table_name: table_name.clone(),
+                index_name: name.clone(),
+                column_name,
+                seq_in_index: Some(seq as u32 + 1),
+                collation: None,
+                cardinality: None,
+                sub_part,
+                packed: None,
+                nullable: nullable.to_string(),
+                index_type: index_type.clone(),
+                non_unique: Some(if unique { 0 } else { 1 }),
+            });
+        }
+    }
+
+    // Primary key columns are NOT NULL even when the definition does not say so
+    for column in columns.iter_mut() {
+        let in_primary = dump.indexes.iter().any(|index| {
+            index.index_name == "PRIMARY" && index.table_name == table_name && index.table_schema == table_schema && index.column_name == column.column_name
+        });
+        if in_primary {
+            column.is_nullable = "NO".to_string();
+        }
+    }
+    dump.columns.extend(columns);
+    Ok(())
+}
+
+// A column definition, and the key declared inline with PRIMARY KEY or UNIQUE.
+// Values are stored the way information_schema.COLUMNS holds them: empty extra and comment
+// instead of missing ones, and string defaults unquoted on MySQL but quoted on MariaDB
+fn parse_column(definition: &str, table_schema: &str, table_name: &str, mariadb: bool) -> Result<(ColumnInfo, Option<(String, bool)>), String> {
+    let tokens = tokenize(definition);
+    let column_name = unquote(tokens.first().ok_or_else(|| format!("Empty column definition in `{}`", table_name))?);
+    let type_length = tokens[1..]
+        .iter()
+        .position(|token| COLUMN_ATTRIBUTES.contains(&token.to_ascii_uppercase().as_str()))
+        .unwrap_or(tokens.len() - 1);
+    if type_length == 0 {
+        return Err(format!("Column `{}`.`{}` has no type", table_name, column_name));
+    }
+    let column_type = lowercase_type(&tokens[1..=type_length].join(" "));
+    let data_type = column_type.split(['(', ' ']).next().unwrap_or_default().to_string();
+
+    let mut column = ColumnInfo::builder(
+        table_schema.to_string(),
+        table_name.to_string(),
+        column_name.clone(),
+        data_type.clone(),
+        column_type.clone(),
+        "YES".to_string(),
+    )
+    .set_extra(String::new())
+    .set_column_comment(String::new());
+    if matches!(data_type.as_str(), "char" | "varchar" | "binary" | "varbinary") {
+        if let Some(length) = column_type.split_once('(').and_then(|(_, rest)| rest.split(')').next()?.trim().parse::<u64>().ok()) {
+            column = column.set_character_maximum_length(length);
+        }
+    }
+
+    let mut inline_key = None;
+    let mut extra: Vec<String> = Vec::new();
+    let rest: Vec<&String> = tokens[type_length + 1..].iter().collect();
+    let mut position = 0;
+    while position < rest.len() {
+        let word = rest[position].to_ascii_uppercase();
+        let next = rest.get(position + 1).map(|token| token.as_str());
+        match word.as_str() {
+            "NOT" if next.is_some_and(|next| next.eq_ignore_ascii_case("NULL")) => {
+                column.is_nullable = "NO".to_string();
+                position += 1;
+            }
+            "NULL" => column.is_nullable = "YES".to_string(),
+            "DEFAULT" => {
+                if let Some(value) = next {
+                    if !value.eq_ignore_ascii_case("NULL") {
+                        let value = if mariadb { value.to_string() } else { unquote_string(value) };
+                        column = column.set_default(value);
+                    }
+                    position += 1;
+                }
+            }
+            "AUTO_INCREMENT" => extra.push("auto_increment".to_string()),
+            "ON" if next.is_some_and(|next| next.eq_ignore_ascii_case("UPDATE")) => {
+                if let Some(value) = rest.get(position + 2) {
+                    extra.push(format!("on update {}", value.to_lowercase()));
+                }
+                position += 2;
+            }
+            "COMMENT" => {
+                if let Some(value) = next {
+                    column = column.set_column_comment(unquote_string(value));
+                    position += 1;
+                }
+            }
+            "CHARACTER" | "CHARSET" => {
+                // CHARACTER SET x or CHARSET x
+                let skip = if word == "CHARACTER" { 2 } else { 1 };
+                if let Some(value) = rest.get(position + skip) {
+                    column = column.set_character_set_name(value.to_string());
+                }
+                position += skip;
+            }
+            "COLLATE" => {
+                if let Some(value) = next {
+                    column = column.set_collation_name(value.to_string());
+                    position += 1;
+                }
+            }
+            "PRIMARY" => inline_key = Some(("PRIMARY".to_string(), true)),
+            "UNIQUE" if inline_key.is_none() => inline_key = Some((column_name.clone(), true)),
+            _ => {}
+        }
+        position += 1;
+    }
+    if inline_key.as_ref().is_some_and(|(name, _)| name == "PRIMARY") {
+        column.is_nullable = "NO".to_string();
+    }
+    if !extra.is_empty() {
+        column = column.set_extra(extra.join(" "));
+    }
+    Ok((column, inline_key))
+}
+
+// "`note`(10) DESC" -> ("note", Some(10))
+fn index_part(part: &str) -> (String, Option<u64>) {
+    let part = part.trim();
+    let part = part.split_whitespace().next().unwrap_or_default();
+    match part.split_once('(') {
+        Some((name, length)) => (unquote(name), length.trim_end_matches(')').parse().ok()),
+        None => (unquote(part), None),
+    }
+}
+
+// Split a definition on whitespace, keeping quoted strings and parenthesized groups together
+fn tokenize(definition: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    let mut chars = definition.chars().peekable();
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(q) => {
+                current.push(c);
+                if c == '\\' {
+                    if let Some(escaped) = chars.next() {
+                        current.push(escaped);
+                    }
+                } else if c == q {
+                    // A doubled quote is an escaped quote
+                    if chars.peek() == Some(&q) {
+                        current.push(chars.next().unwrap());
+                    } else {
+                        quote = None;
+                    }
+                }
+            }
+            None => match c {
+                '\'' | '"' | '`' => {
+                    quote = Some(c);
+                    current.push(c);
+                }
+                '(' => {
+                    depth += 1;
+                    current.push(c);
+                }
+                ')' => {
+                    depth = depth.saturating_sub(1);
+                    current.push(c);
+                }
+                c if c.is_whitespace() && depth == 0 => {
+                    if !current.is_empty() {
+                        tokens.push(std::mem::take(&mut current));
+                    }
+                }
+                _ => current.push(c),
+            },
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+// Drop "-- ", "#" and "/* */" comments, including the /*!40101 ... */ version comments of mysqldump
+fn strip_comments(sql: &str) -> String {
+    let mut result = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    let mut quote: Option<char> = None;
+    let mut line_start = true;
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            result.push(c);
+            if c == '\\' {
+                if let Some(escaped) = chars.next() {
+                    result.push(escaped);
+                }
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '\'' | '"' | '`' => {
+                quote = Some(c);
+                result.push(c);
+            }
+            '-' if chars.peek() == Some(&'-') && line_start => {
+                skip_line(&mut chars, &mut result);
+                // The next line starts fresh, it may be a comment too
+                continue;
+            }
+            '#' if line_start => {
+                skip_line(&mut chars, &mut result);
+                continue;
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+                result.push(' ');
+            }
+            _ => result.push(c),
+        }
+        line_start = c == '\n' || (line_start && c.is_whitespace());
+    }
+    result
+}
+
+fn skip_line(chars: &mut impl Iterator<Item = char>, result: &mut String) {
+    for c in chars {
+        if c == '\n' {
+            result.push('\n');
+            break;
+        }
+    }
+}
+
+// Split a script on semicolons that are not inside quotes
+fn split_statements(sql: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut chars = sql.chars();
+    while let Some(c) = chars.next() {
+        current.push(c);
+        match quote {
+            Some(_) if c == '\\' => {
+                if let Some(escaped) = chars.next() {
+                    current.push(escaped);
+                }
+            }
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '\'' | '"' | '`' => quote = Some(c),
+                ';' => {
+                    current.pop();
+                    statements.push(std::mem::take(&mut current));
+                }
+                _ => {}
+            },
+        }
+    }
+    statements.push(current);
+    statements
+}
+
+fn matching_parenthesis(text: &str, open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    let mut chars = text[open..].char_indices();
+    while let Some((position, c)) = chars.next() {
+        match quote {
+            Some(_) if c == '\\' => {
+                chars.next();
+            }
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '\'' | '"' | '`' => quote = Some(c),
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(open + position);
+                    }
+                }
+                _ => {}
+            },
+        }
+    }
+    None
+}
+
+// "`shop`.`orders`" -> (Some("shop"), "orders")
+fn split_qualified(name: &str) -> (Option<String>, String) {
+    let parts: Vec<String> = split_outside_backticks(name, '.');
+    match parts.as_slice() {
+        [schema, table] => (Some(unquote(schema)), unquote(table)),
+        _ => (None, unquote(name)),
+    }
+}
+
+fn split_outside_backticks(text: &str, separator: char) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut quoted = false;
+    for c in text.chars() {
+        if c == '`' {
+            quoted = !quoted;
+        }
+        if c == separator && !quoted {
+            parts.push(String::new());
+        } else {
+            parts.last_mut().unwrap().push(c);
+        }
+    }
+    parts
+}
+
+fn starts_with_word(upper: &str, word: &str) -> bool {
+    upper.starts_with(word) && upper[word.len()..].chars().next().is_none_or(|c| c.is_whitespace() || c == '(' || c == '`')
+}
+
+// information_schema reports the type keyword in lowercase, the values of an enum or set keep their case
+fn lowercase_type(column_type: &str) -> String {
+    let mut result = String::with_capacity(column_type.len());
+    let mut in_quote = false;
+    let mut escaped = false;
+    for c in column_type.chars() {
+        if in_quote {
+            result.push(c);
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '\'' {
+                // A doubled quote closes and reopens the literal, which keeps the state right
+                in_quote = false;
+            }
+        } else {
+            if c == '\'' {
+                in_quote = true;
+            }
+            result.extend(c.to_lowercase());
+        }
+    }
+    result
+}
+
+fn unquote(identifier: &str) -> String {
+    identifier.trim().trim_matches('`').replace("``", "`")
+}
+
+fn unquote_string(value: &str) -> String {
+    match value.strip_prefix('\'').and_then(|value| value.strip_suffix('\'')) {
+        Some(inner) => inner.replace("''", "'").replace("\\'", "'"),
+        None => value.to_string(),
+    }
+}
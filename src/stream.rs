@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+use std::fmt;
+use indexmap::IndexMap;
+use serde::Deserializer;
+use serde::de::{SeqAccess, Visitor};
+use super::{ColumnInfo, phpmyadmin, snapshot};
+
+// Builds the `schema`.table -> column map one row at a time. Exports list the columns of a
+// table together, so the key of the previous row is kept and a table key is only formatted
+// once per run of rows instead of once per column
+#[derive(Default)]
+pub struct SchemaMapBuilder {
+    map: HashMap<String, IndexMap<String, ColumnInfo>>,
+    // schema, table and map key of the previous row
+    current: Option<(String, String, String)>,
+}
+
+impl SchemaMapBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, column: ColumnInfo) {
+        let same_table = matches!(
+            &self.current,
+            Some((schema, table, _)) if *schema == column.table_schema && *table == column.table_name
+        );
+        if !same_table {
+            let key = format!("`{}`.{}", column.table_schema, column.table_name);
+            self.current = Some((column.table_schema.clone(), column.table_name.clone(), key));
+        }
+        let key = &self.current.as_ref().expect("current table").2;
+        let columns = match self.map.get_mut(key) {
+            Some(columns) => columns,
+            None => self.map.entry(key.clone()).or_default(),
+        };
+        columns.insert(column.column_name.clone(), column);
+    }
+
+    pub fn finish(self) -> HashMap<String, IndexMap<String, ColumnInfo>> {
+        self.map
+    }
+}
+
+// Hands every row of a plain column export (a JSON array of information_schema.COLUMNS rows)
+// to on_row while parsing, without collecting them first
+pub fn for_each_column<F: FnMut(ColumnInfo)>(json: &str, on_row: F) -> Result<(), String> {
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    deserializer
+        .deserialize_seq(ColumnRows { on_row })
+        .and_then(|_| deserializer.end())
+        .map_err(|e| format!("Error parsing JSON: {}", e))
+}
+
+struct ColumnRows<F> {
+    on_row: F,
+}
+
+impl<'de, F: FnMut(ColumnInfo)> Visitor<'de> for ColumnRows<F> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an array of information_schema.COLUMNS rows")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(mut self, mut seq: A) -> Result<(), A::Error> {
+        while let Some(column) = seq.next_element::<ColumnInfo>()? {
+            (self.on_row)(column);
+        }
+        Ok(())
+    }
+}
+
+// Same input as create_column_info, read straight into the map build_schema_map returns
+pub fn schema_map_from_json(json: &str) -> Result<HashMap<String, IndexMap<String, ColumnInfo>>, String> {
+    if snapshot::is_snapshot(json) {
+        return snapshot::load_snapshot(json).map(|snapshot| super::build_schema_map(snapshot.columns));
+    }
+    let mut builder = SchemaMapBuilder::new();
+    match for_each_column(json, |column| builder.insert(column)) {
+        Ok(()) => Ok(builder.finish()),
+        // phpMyAdmin / MariaDB export with header, database and table blocks. Its first
+        // element is not a column row, so little work is thrown away before getting here
+        Err(_) => phpmyadmin::parse_export::<ColumnInfo>(json).map(|export| super::build_schema_map(export.rows)),
+    }
+}
@@ -0,0 +1,126 @@
+mod common;
+
+#[cfg(test)]
+mod baseline_tests {
+    use db_diff::{ColumnInfo, build_schema_map, compare_schema_maps};
+    use db_diff::baseline::{Baseline, BaselineEntry, ChangeKind, apply_baseline, generate_report_with_baseline};
+    use db_diff::ci::junit_report_with_baseline;
+    use db_diff::index::{IndexInfo, build_index_map, compare_index_maps};
+    use super::common::{column, index};
+
+    fn columns1() -> Vec<ColumnInfo> {
+        vec![
+            column("orders", "id", "int(11)"),
+            column("orders", "note", "varchar(20)"),
+            column("audit", "id", "int(11)"),
+        ]
+    }
+
+    fn columns2() -> Vec<ColumnInfo> {
+        vec![
+            column("orders", "id", "int(11)"),
+            column("orders", "note", "varchar(50)"),
+            column("orders", "audit_user", "varchar(32)"),
+        ]
+    }
+
+    fn indexes1() -> Vec<IndexInfo> {
+        vec![index("audit", "idx_id", "id", 1)]
+    }
+
+    fn indexes2() -> Vec<IndexInfo> {
+        vec![index("orders", "idx_audit", "audit_user", 1), index("orders", "idx_audit", "id", 2)]
+    }
+
+    fn json<T: serde::Serialize>(rows: &[T]) -> String {
+        serde_json::to_string(rows).unwrap()
+    }
+
+    const BASELINE: &str = r#"{"accepted": [
+        {"path": "shop.audit", "kind": "table_only_in_first", "reason": "dropped in prod first"},
+        {"path": "`shop`.orders.audit_*", "kind": "column_only_in_second"},
+        {"path": "shop.orders.idx_audit", "kind": "index_only_in_second"},
+        {"path": "shop.orders.status", "kind": "column_definition_differs", "reason": "fixed last sprint"}
+    ]}"#;
+
+    #[test]
+    fn test_apply() {
+        let baseline = Baseline::parse(BASELINE).unwrap();
+        let mut schema_diff = compare_schema_maps(&build_schema_map(columns1()), &build_schema_map(columns2()));
+        let mut index_diff = compare_index_maps(&build_index_map(indexes1()), &build_index_map(indexes2()));
+        let result = baseline.apply(Some(&mut schema_diff), Some(&mut index_diff));
+
+        // Only the changed note column is new drift
+        assert!(schema_diff.tables_only_in_first.is_empty());
+        assert!(schema_diff.columns_only_in_second.is_empty());
+        assert_eq!(schema_diff.columns_with_different_definitions.len(), 1);
+        // The index of the accepted table goes with it, both columns of idx_audit with their entry
+        assert!(index_diff.indexes_only_in_first.is_empty());
+        assert!(index_diff.indexes_only_in_second.is_empty());
+
+        let suppressed: Vec<_> = result.suppressed.iter().map(|suppressed| (suppressed.path.as_str(), suppressed.kind)).collect();
+        assert_eq!(
+            suppressed,
+            vec![
+                ("shop.audit", ChangeKind::TableOnlyInFirst),
+                ("shop.orders.audit_user", ChangeKind::ColumnOnlyInSecond),
+                ("shop.orders.idx_audit", ChangeKind::IndexOnlyInSecond),
+            ]
+        );
+        assert_eq!(result.suppressed[0].reason.as_deref(), Some("dropped in prod first"));
+        assert_eq!(
+            result.stale,
+            vec![BaselineEntry {
+                path: "shop.orders.status".to_string(),
+                kind: ChangeKind::ColumnDefinitionDiffers,
+                reason: Some("fixed last sprint".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_kind_and_path_must_both_match() {
+        let baseline = Baseline::parse(r#"{"accepted": [
+            {"path": "shop.orders.note", "kind": "column_only_in_second"},
+            {"path": "shop.orders.not*", "kind": "column_definition_differs"},
+            {"path": "shop.orders.idx_audit", "kind": "index_only_in_first"}
+        ]}"#)
+        .unwrap();
+        let mut schema_diff = compare_schema_maps(&build_schema_map(columns1()), &build_schema_map(columns2()));
+        let result = baseline.apply(Some(&mut schema_diff), None);
+        assert!(schema_diff.columns_with_different_definitions.is_empty());
+        assert_eq!(schema_diff.columns_only_in_second.len(), 1);
+        // Without an index diff the index entry cannot be judged stale
+        let stale: Vec<&str> = result.stale.iter().map(|entry| entry.path.as_str()).collect();
+        assert_eq!(stale, vec!["shop.orders.note"]);
+    }
+
+    #[test]
+    fn test_reports() {
+        let (columns1, columns2, indexes1, indexes2) = (json(&columns1()), json(&columns2()), json(&indexes1()), json(&indexes2()));
+
+        let result: serde_json::Value = serde_json::from_str(&apply_baseline(&columns1, &columns2, &indexes1, &indexes2, BASELINE)).unwrap();
+        assert_eq!(result["schema_diff"]["columns_with_different_definitions"].as_array().map(Vec::len), Some(1));
+        assert_eq!(result["baseline"]["suppressed"].as_array().map(Vec::len), Some(3));
+        assert_eq!(result["baseline"]["stale"][0]["kind"], "column_definition_differs");
+
+        let markdown = generate_report_with_baseline(&columns1, &columns2, &indexes1, &indexes2, "markdown", BASELINE);
+        assert!(markdown.contains("**1 difference(s)**"));
+        assert!(markdown.contains("## Suppressed by baseline (3)"));
+        assert!(markdown.contains("| `shop.audit` | `table_only_in_first` | `dropped in prod first` |"));
+        assert!(markdown.contains("## Stale baseline entries (1)"));
+
+        // Suppressed and stale sections are shown when no drift is left
+        let accept_all = r#"{"accepted": [{"path": "shop.*", "kind": "column_definition_differs"}, {"path": "*", "kind": "column_only_in_second"}, {"path": "shop.audit", "kind": "table_only_in_first"}]}"#;
+        let html = generate_report_with_baseline(&columns1, &columns2, "", "", "html", accept_all);
+        assert!(html.contains("<p>No differences found.</p>"));
+        assert!(html.contains("<h2>Suppressed by baseline (3)</h2>"));
+
+        let xml = junit_report_with_baseline(&columns1, &columns2, &indexes1, &indexes2, BASELINE);
+        assert!(xml.contains("<testsuites name=\"schema drift\" tests=\"7\" failures=\"1\" skipped=\"4\">"));
+        assert!(xml.contains("<testsuite name=\"baseline\" tests=\"4\" failures=\"0\" skipped=\"4\">"));
+        assert!(xml.contains("<testcase classname=\"baseline\" name=\"shop.audit\">\n      <skipped type=\"table_only_in_first\" message=\"Accepted by baseline: dropped in prod first\"/>"));
+
+        assert!(apply_baseline(&columns1, &columns2, "", "", "{\"accepted\": [{\"path\": \"x\"}]}").starts_with("Error parsing baseline"));
+    }
+}
@@ -0,0 +1,138 @@
+mod common;
+
+#[cfg(test)]
+mod ci_output_tests {
+    use std::collections::HashMap;
+    use std::fs;
+    use db_diff::{ColumnInfo, build_schema_map, compare_schema_maps, generate_sql_diff};
+    use db_diff::ci::{SarifLog, junit_report, render_junit, sarif_report};
+    use db_diff::index::{build_index_map, compare_index_maps};
+    use db_diff::lint::{LintContext, Linter, Severity};
+    use db_diff::risk::assess_sql;
+    use super::common::{column, index};
+
+    fn columns1() -> Vec<ColumnInfo> {
+        vec![
+            column("orders", "id", "int(11)"),
+            column("orders", "note", "varchar(20)"),
+            column("orders", "legacy_flag", "tinyint(1)"),
+            column("audit", "id", "int(11)"),
+        ]
+    }
+
+    fn columns2() -> Vec<ColumnInfo> {
+        vec![
+            column("orders", "id", "int(11)"),
+            column("orders", "note", "varchar(50)"),
+        ]
+    }
+
+    #[test]
+    fn test_junit_report() {
+        let schema_diff = compare_schema_maps(&build_schema_map(columns1()), &build_schema_map(columns2()));
+        let index_diff = compare_index_maps(
+            &build_index_map(vec![index("orders", "idx_note", "note", 1)]),
+            &build_index_map(vec![index("orders", "idx_note", "note", 1), index("orders", "idx_id_note", "id", 1), index("orders", "idx_id_note", "note", 2)]),
+        );
+
+        let xml = render_junit(Some(&schema_diff), Some(&index_diff));
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites name=\"schema drift\" tests=\"4\" failures=\"4\">"));
+        assert!(xml.contains("<testsuite name=\"tables\" tests=\"1\" failures=\"1\">"));
+        assert!(xml.contains("<testcase classname=\"shop.audit\" name=\"shop.audit\">\n      <failure type=\"table_only_in_first\""));
+        assert!(xml.contains("<testcase classname=\"shop.orders\" name=\"legacy_flag\">"));
+        assert!(xml.contains("message=\"Column definition differs: note varchar(20) NOT NULL in Schema 1, note varchar(50) NOT NULL in Schema 2\""));
+        // A two column index is a single test case
+        assert_eq!(xml.matches("name=\"idx_id_note\"").count(), 1);
+    }
+
+    #[test]
+    fn test_junit_report_without_drift() {
+        let xml = junit_report(
+            &serde_json::to_string(&columns2()).unwrap(),
+            &serde_json::to_string(&columns2()).unwrap(),
+            "",
+            "",
+        );
+        assert!(xml.contains("<testsuites name=\"schema drift\" tests=\"2\" failures=\"0\">"));
+        assert!(xml.contains("<testcase classname=\"columns\" name=\"no drift\"/>"));
+        assert!(!xml.contains("indexes"));
+    }
+
+    #[test]
+    fn test_statement_risks() {
+        let diff = compare_schema_maps(&build_schema_map(columns1()), &build_schema_map(columns2()));
+        let (risks, errors) = assess_sql(&generate_sql_diff(&diff));
+        assert!(errors.is_empty());
+
+        let found: Vec<(&str, Severity)> = risks.iter().map(|risk| (risk.rule_id.as_str(), risk.severity)).collect();
+        assert_eq!(found, vec![
+            ("drops-table", Severity::Error),
+            ("drops-column", Severity::Error),
+            ("rewrites-column", Severity::Warning),
+        ]);
+        assert_eq!(risks[0].table, "`shop`.audit");
+        assert_eq!(risks[2].message, "Changes column note of `shop`.orders");
+    }
+
+    #[test]
+    fn test_sarif_log() {
+        let schema_map = build_schema_map(columns2());
+        let index_map = build_index_map(vec![]);
+        let linter = Linter::new();
+        let findings = linter.run(&LintContext { schema_map: &schema_map, index_map: &index_map });
+        let diff = compare_schema_maps(&build_schema_map(columns1()), &build_schema_map(columns2()));
+        let (risks, _) = assess_sql(&generate_sql_diff(&diff));
+
+        let log = SarifLog::new().with_lint_findings(&linter, &findings).with_risks(&risks).to_value();
+        assert_eq!(log["version"], "2.1.0");
+        let run = &log["runs"][0];
+        assert_eq!(run["tool"]["driver"]["name"], "db_diff");
+
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        let drops_table = rules.iter().find(|rule| rule["id"] == "drops-table").unwrap();
+        assert_eq!(drops_table["defaultConfiguration"]["level"], "error");
+        let comment = rules.iter().find(|rule| rule["id"] == "missing-comment").unwrap();
+        assert!(!comment["shortDescription"]["text"].as_str().unwrap().is_empty());
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), findings.len() + risks.len());
+        let missing_comment = results.iter().find(|result| result["ruleId"] == "missing-comment").unwrap();
+        assert_eq!(missing_comment["level"], "note");
+        assert_eq!(missing_comment["locations"][0]["logicalLocations"][0]["kind"], "member");
+    }
+
+    #[test]
+    fn test_sarif_report_from_sample_files() {
+        let json1 = fs::read_to_string("sample_schema1.json").expect("Failed to read sample_schema1.json");
+        let json2 = fs::read_to_string("sample_schema2.json").expect("Failed to read sample_schema2.json");
+
+        let log: serde_json::Value = serde_json::from_str(&sarif_report(&json1, &json2, "", "", "")).unwrap();
+        assert_eq!(log["$schema"], "https://json.schemastore.org/sarif-2.1.0.json");
+        assert!(log["runs"][0]["results"].is_array());
+
+        assert!(sarif_report(&json1, &json2, "", "", "{").starts_with("Error parsing lint config:"));
+    }
+
+    #[test]
+    fn test_sarif_report_reads_whole_statements() {
+        // A comment holding a newline spreads one generated statement over two lines
+        let json1 = serde_json::to_string(&vec![column("orders", "note", "varchar(20)")]).unwrap();
+        let json2 = serde_json::to_string(&vec![column("orders", "note", "varchar(20)").set_column_comment("line1\nline2".to_string())]).unwrap();
+
+        let log: serde_json::Value = serde_json::from_str(&sarif_report(&json1, &json2, "", "", "")).unwrap();
+        let run = &log["runs"][0];
+        let rewrite = run["results"].as_array().unwrap().iter().find(|result| result["ruleId"] == "rewrites-column").unwrap();
+        assert!(rewrite["message"]["text"].as_str().unwrap().contains("line1\nline2"));
+        assert_eq!(run["invocations"][0]["toolExecutionNotifications"].as_array().unwrap().len(), 0);
+
+        // Statements that cannot be assessed become notifications instead of replacing the log
+        let sql = HashMap::from([("Drop table in Schema 1 (Schema 1 only)".to_string(), "DROP TABLE `shop`.audit;\nTRUNCATE `shop`.orders;".to_string())]);
+        let (risks, errors) = assess_sql(&sql);
+        assert_eq!(risks.len(), 1);
+        assert_eq!(errors, vec!["Unsupported statement: TRUNCATE `shop`.orders;".to_string()]);
+        let log = SarifLog::new().with_risks(&risks).with_notifications(&errors).to_value();
+        let notifications = log["runs"][0]["invocations"][0]["toolExecutionNotifications"].as_array().unwrap();
+        assert_eq!(notifications[0]["level"], "warning");
+        assert_eq!(notifications[0]["message"]["text"], errors[0]);
+    }
+}
@@ -0,0 +1,263 @@
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod cli_tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use db_diff::cli::{DRIFT_STATUS, ServerKind, connection_kind, run, run_with_status};
+    use db_diff::{ColumnInfo, create_column_info};
+    use db_diff::index::create_index_info;
+    use db_diff::snapshot::{Snapshot, SnapshotMetadata, load_snapshot};
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    fn temp_file(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("db_diff_cli_{}_{}", std::process::id(), name));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_compare_files() {
+        let output = run(&args("compare sample_schema1.json sample_schema2.json")).unwrap();
+        let result: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert!(result["comparison"]["tables"].as_array().is_some_and(|tables| !tables.is_empty()));
+        assert!(result["comparison"]["forward_script"].as_str().is_some_and(|script| script.contains("ALTER TABLE")));
+
+        let report = run(&args("compare sample_schema1.json sample_schema2.json --format markdown")).unwrap();
+        assert!(report.starts_with('#'));
+        assert!(run(&args("compare sample_schema1.json sample_schema2.json --format html")).unwrap().contains("<html"));
+
+        // A column export against an index export: only the columns differ once indexes are ignored
+        let output = run(&args("compare sample_schema1.json sample_index1.json --columns-only")).unwrap();
+        let result: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert!(result["comparison"]["tables"].as_array().unwrap().iter().all(|table| table["indexes_only_in_second"].as_array().is_none_or(|indexes| indexes.is_empty())));
+    }
+
+    #[test]
+    fn test_snapshot_files_keep_their_metadata() {
+        let columns = create_column_info(&fs::read_to_string("sample_schema1.json").unwrap()).unwrap();
+        let metadata = SnapshotMetadata { host: Some("db1:3306/shop".to_string()), ..SnapshotMetadata::default() };
+        let snapshot = Snapshot::new(columns, vec![]).metadata(metadata).seal();
+        let path = temp_file("snapshot.json", &serde_json::to_string(&snapshot).unwrap());
+
+        let output = run(&[
+            "compare".to_string(),
+            path.to_string_lossy().into_owned(),
+            "sample_schema1.json".to_string(),
+        ])
+        .unwrap();
+        fs::remove_file(&path).unwrap();
+        let result: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(result["first"]["host"], "db1:3306/shop");
+        assert_eq!(result["comparison"]["tables"].as_array().map(Vec::len), Some(0));
+    }
+
+    #[test]
+    fn test_snapshot_is_deterministic() {
+        let json = fs::read_to_string("sample_schema1.json").unwrap();
+        let mut columns: Vec<ColumnInfo> = create_column_info(&json).unwrap();
+        // Tables in another order, the columns of each table still in their own order
+        columns.sort_by(|a, b| b.table_name.cmp(&a.table_name));
+        let shuffled = temp_file("shuffled.json", &serde_json::to_string(&columns).unwrap());
+
+        let first = run(&args("snapshot sample_schema1.json")).unwrap();
+        let second = run(&["snapshot".to_string(), shuffled.to_string_lossy().into_owned()]).unwrap();
+        fs::remove_file(&shuffled).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(load_snapshot(&first).unwrap().columns.len(), columns.len());
+    }
+
+    #[test]
+    fn test_snapshot_files_are_read_by_the_parsers() {
+        let columns_path = std::env::temp_dir().join(format!("db_diff_cli_{}_columns.json", std::process::id()));
+        let indexes_path = std::env::temp_dir().join(format!("db_diff_cli_{}_indexes.json", std::process::id()));
+        let output_path = std::env::temp_dir().join(format!("db_diff_cli_{}_snapshot.json", std::process::id()));
+        let dump = temp_file(
+            "dump.sql",
+            "CREATE TABLE `orders` (
+  `id` int(11) NOT NULL AUTO_INCREMENT,
+  `reference` varchar(32) NOT NULL,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `uk_reference` (`reference`)
+) ENGINE=InnoDB;",
+        );
+        let output = run(&[
+            "snapshot".to_string(),
+            dump.to_string_lossy().into_owned(),
+            "--output".to_string(),
+            output_path.to_string_lossy().into_owned(),
+            "--columns".to_string(),
+            columns_path.to_string_lossy().into_owned(),
+            "--indexes".to_string(),
+            indexes_path.to_string_lossy().into_owned(),
+        ])
+        .unwrap();
+        assert!(output.contains("Wrote 2 columns to"));
+        assert!(output.contains("Wrote 2 index columns to"));
+
+        let columns = create_column_info(&fs::read_to_string(&columns_path).unwrap()).unwrap();
+        let indexes = create_index_info(&fs::read_to_string(&indexes_path).unwrap()).unwrap();
+        let snapshot_json = fs::read_to_string(&output_path).unwrap();
+        assert!(snapshot_json.ends_with("}\n"));
+        let snapshot = load_snapshot(&snapshot_json).unwrap();
+        assert_eq!((snapshot.columns, snapshot.indexes), (columns, indexes));
+
+        // The dump and its snapshot compare equal
+        let result = run(&["compare".to_string(), dump.to_string_lossy().into_owned(), output_path.to_string_lossy().into_owned()]).unwrap();
+        let result: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(result["comparison"]["tables"].as_array().map(Vec::len), Some(0));
+        for path in [columns_path, indexes_path, output_path, dump] {
+            fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_snapshot_drops_cardinality() {
+        let has_cardinality = |output: &str| load_snapshot(output).unwrap().indexes.iter().any(|index| index.cardinality.is_some());
+        assert!(!has_cardinality(&run(&args("snapshot sample_index1.json")).unwrap()));
+        assert!(has_cardinality(&run(&args("snapshot sample_index1.json --keep-cardinality")).unwrap()));
+    }
+
+    #[test]
+    fn test_snapshot_drops_capture_metadata() {
+        let columns = create_column_info(&fs::read_to_string("sample_schema1.json").unwrap()).unwrap();
+        let metadata = SnapshotMetadata {
+            server_version: Some("10.11.6-MariaDB".to_string()),
+            captured_at: Some("2024-05-01T12:00:00Z".to_string()),
+            host: Some("db1:3306/shop".to_string()),
+            ..SnapshotMetadata::default()
+        };
+        let path = temp_file("captured.json", &serde_json::to_string(&Snapshot::new(columns, vec![]).metadata(metadata).seal()).unwrap());
+        let path = path.to_string_lossy().into_owned();
+        let metadata = |options: &str| load_snapshot(&run(&args(&format!("snapshot {} {}", path, options))).unwrap()).unwrap().metadata;
+
+        let default = metadata("");
+        assert_eq!((default.captured_at, default.server_version, default.host.as_deref()), (None, None, Some("db1:3306/shop")));
+        let kept = metadata("--keep-capture-time --keep-server-version");
+        assert_eq!(kept.captured_at.as_deref(), Some("2024-05-01T12:00:00Z"));
+        assert_eq!(kept.server_version.as_deref(), Some("10.11.6-MariaDB"));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_baseline() {
+        let (_, status) = run_with_status(&args("compare sample_schema1.json sample_schema2.json --fail-on-drift")).unwrap();
+        assert_eq!(status, DRIFT_STATUS);
+        assert_eq!(run_with_status(&args("compare sample_schema1.json sample_schema1.json --fail-on-drift")).unwrap().1, 0);
+
+        // Accept every difference between the two samples, plus one that does not exist
+        let kinds = ["table_only_in_first", "table_only_in_second", "column_only_in_first", "column_only_in_second", "column_definition_differs"];
+        let mut accepted: Vec<serde_json::Value> = kinds.iter().map(|kind| serde_json::json!({ "path": "*", "kind": kind })).collect();
+        accepted.push(serde_json::json!({ "path": "shop.gone", "kind": "index_renamed" }));
+        let baseline = temp_file("baseline.json", &serde_json::json!({ "accepted": accepted }).to_string());
+        let baseline = baseline.to_string_lossy().into_owned();
+
+        let line = format!("compare sample_schema1.json sample_schema2.json --columns-only --fail-on-drift --baseline {}", baseline);
+        let (output, status) = run_with_status(&args(&line)).unwrap();
+        assert_eq!(status, 0);
+        let result: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert!(result["baseline"]["suppressed"].as_array().is_some_and(|suppressed| !suppressed.is_empty()));
+        // Indexes are not compared, so the index entry is not stale
+        assert!(result["baseline"]["stale"].as_array().unwrap().iter().all(|entry| entry["kind"] != "index_renamed"));
+
+        let report = run(&args(&format!("compare sample_schema1.json sample_schema2.json --format markdown --baseline {}", baseline))).unwrap();
+        assert!(report.contains("## Suppressed by baseline"));
+        assert!(report.contains("| `shop.gone` | `index_renamed` |"));
+        let xml = run(&args(&format!("compare sample_schema1.json sample_schema2.json --format junit --columns-only --baseline {}", baseline))).unwrap();
+        assert!(xml.contains("failures=\"0\""));
+        assert!(xml.contains("<testsuite name=\"baseline\""));
+
+        fs::remove_file(&baseline).unwrap();
+        assert!(run(&args("compare a b --baseline")).unwrap_err().starts_with("--baseline needs a file name"));
+    }
+
+    #[test]
+    fn test_migrate_writes_files() {
+        let directory = std::env::temp_dir().join(format!("db_diff_cli_{}_migrations", std::process::id()));
+        let output = run(&[
+            "migrate".to_string(),
+            "sample_schema1.json".to_string(),
+            "sample_schema2.json".to_string(),
+            "--tool".to_string(),
+            "flyway".to_string(),
+            "--version".to_string(),
+            "4".to_string(),
+            "--description".to_string(),
+            "sample changes".to_string(),
+            "--output-dir".to_string(),
+            directory.to_string_lossy().into_owned(),
+        ])
+        .unwrap();
+        assert_eq!(output.lines().count(), 2);
+        let forward = fs::read_to_string(directory.join("V4__sample_changes.sql")).unwrap();
+        let reverse = fs::read_to_string(directory.join("U4__sample_changes.sql")).unwrap();
+        assert!(forward.contains("ALTER TABLE"));
+        assert!(!reverse.is_empty());
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert!(run(&args("migrate sample_schema1.json sample_schema2.json")).unwrap_err().starts_with("migrate needs --tool"));
+        assert!(run(&args("migrate sample_schema1.json sample_schema2.json --tool dbmate")).unwrap_err().starts_with("Unknown migration tool: dbmate"));
+    }
+
+    #[test]
+    fn test_online_script() {
+        let script = run(&args("online sample_schema1.json sample_schema2.json --tool pt-osc")).unwrap();
+        assert!(script.starts_with("#!/bin/sh\nset -e\n"));
+        assert!(script.contains("pt-online-schema-change --alter '"));
+        let plan: serde_json::Value = serde_json::from_str(&run(&args("online sample_schema1.json sample_schema2.json --tool gh-ost --format json")).unwrap()).unwrap();
+        assert!(plan["tables"].as_array().is_some_and(|tables| tables.iter().any(|table| table["method"] == "online")));
+        assert!(run(&args("online sample_schema1.json sample_schema2.json")).unwrap_err().starts_with("online needs --tool"));
+        assert!(run(&args("online a b --tool gh-ost --min-rows many")).unwrap_err().starts_with("--min-rows needs a number, got many"));
+    }
+
+    #[test]
+    fn test_migrate_and_online_refuse_postgres() {
+        let columns = create_column_info(&fs::read_to_string("sample_schema1.json").unwrap()).unwrap();
+        let metadata = SnapshotMetadata { flavor: Some("postgresql".to_string()), ..SnapshotMetadata::default() };
+        let path = temp_file("postgres_snapshot.json", &serde_json::to_string(&Snapshot::new(columns, vec![]).metadata(metadata).seal()).unwrap());
+        let path = path.to_string_lossy().into_owned();
+
+        let error = run(&args(&format!("migrate sample_schema1.json {} --tool flyway", path))).unwrap_err();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(error, format!("Second source: migrate writes MySQL statements, {} is a PostgreSQL schema", path));
+        assert_eq!(
+            run(&args("online postgres://postgres@localhost/shop sample_schema2.json --tool gh-ost")).unwrap_err(),
+            "First source: online writes MySQL statements, postgres://postgres@localhost/shop is a PostgreSQL schema"
+        );
+    }
+
+    #[test]
+    fn test_connection_urls() {
+        assert_eq!(connection_kind("mysql://root@localhost/shop"), Some(ServerKind::Mysql));
+        assert_eq!(connection_kind("MariaDB://root@localhost"), Some(ServerKind::Mysql));
+        assert_eq!(connection_kind("postgres://postgres@localhost/shop"), Some(ServerKind::Postgres));
+        assert_eq!(connection_kind("PostgreSQL://postgres@localhost"), Some(ServerKind::Postgres));
+        assert_eq!(connection_kind("mysql.json"), None);
+        assert_eq!(connection_kind("exports/postgres.sql.gz"), None);
+        assert_eq!(connection_kind("https://example.com/export.json"), None);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(run(&args("compare sample_schema1.json")).unwrap_err().starts_with("compare needs two sources, got 1"));
+        assert!(run(&args("compare a b --verbose")).unwrap_err().starts_with("Unknown option: --verbose"));
+        assert!(run(&args("compare a b --format pdf")).unwrap_err().starts_with("Unknown report format: pdf"));
+        assert!(run(&args("diff a b")).unwrap_err().starts_with("Unknown command: diff"));
+        assert!(run(&args("compare missing.json sample_schema1.json")).unwrap_err().starts_with("First source: Error reading missing.json"));
+        assert!(run(&args("help")).unwrap().starts_with("Usage:\n  db-diff compare"));
+        assert!(run(&args("snapshot")).unwrap_err().starts_with("snapshot needs one source, got 0"));
+        assert!(run(&args("snapshot sample_schema1.json --output")).unwrap_err().starts_with("--output needs a file name"));
+
+        #[cfg(not(feature = "mysql"))]
+        assert_eq!(
+            run(&args("compare mysql://root@localhost/shop sample_schema1.json")).unwrap_err(),
+            "First source: Connection URLs need a build with --features mysql"
+        );
+        #[cfg(not(feature = "postgres"))]
+        assert_eq!(
+            run(&args("compare sample_schema1.json postgres://postgres@localhost/shop")).unwrap_err(),
+            "Second source: PostgreSQL connection URLs need a build with --features postgres"
+        );
+    }
+}
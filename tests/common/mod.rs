@@ -0,0 +1,35 @@
+// Rows shared by the integration tests, in schema `shop`. Each test file uses a few of them
+#![allow(dead_code)]
+
+use db_diff::ColumnInfo;
+use db_diff::index::IndexInfo;
+
+// A NOT NULL column, data_type is the column type without its length, e.g. varchar for varchar(20)
+pub fn column(table: &str, name: &str, column_type: &str) -> ColumnInfo {
+    ColumnInfo::builder(
+        "shop".to_string(),
+        table.to_string(),
+        name.to_string(),
+        column_type.split('(').next().unwrap_or_default().to_string(),
+        column_type.to_string(),
+        "NO".to_string(),
+    )
+}
+
+// One column of a non-unique BTREE index, change the rest with the IndexInfo setters
+pub fn index(table: &str, name: &str, column_name: &str, seq: u32) -> IndexInfo {
+    IndexInfo::builder()
+        .table_schema("shop")
+        .table_name(table)
+        .index_name(name)
+        .column_name(column_name)
+        .seq_in_index(seq)
+        .nullable("YES")
+        .index_type("BTREE")
+        .non_unique_opt(1)
+}
+
+pub fn nullable(mut column: ColumnInfo) -> ColumnInfo {
+    column.is_nullable = "YES".to_string();
+    column
+}
@@ -0,0 +1,222 @@
+#[cfg(test)]
+mod compression_tests {
+    use std::fs;
+    use std::io::{Cursor, Write};
+    use flate2::Compression as GzLevel;
+    use flate2::GzBuilder;
+    use flate2::write::GzEncoder;
+    use zip::write::SimpleFileOptions;
+    use db_diff::{compare_schemas, create_column_info};
+    use db_diff::index::create_index_info;
+    use db_diff::input::{Compression, decode_upload, decompress, decompress_limited, detect_compression, read_input};
+    use db_diff::sql_dump::parse_sql_dump;
+
+    const DUMP: &str = "-- MariaDB dump 10.19  Distrib 10.11.6-MariaDB, for Linux (x86_64)
+--
+-- Host: localhost    Database: shop
+-- ------------------------------------------------------
+/*!40101 SET @OLD_CHARACTER_SET_CLIENT=@@CHARACTER_SET_CLIENT */;
+
+DROP TABLE IF EXISTS `orders`;
+CREATE TABLE `orders` (
+  `id` int(11) NOT NULL AUTO_INCREMENT,
+  `customer_id` int(11) DEFAULT NULL,
+  `note` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci DEFAULT 'n/a; none' COMMENT 'It''s free text',
+  `updated_at` timestamp NOT NULL DEFAULT current_timestamp() ON UPDATE current_timestamp(),
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `uq_note` (`note`(10)),
+  KEY `idx_customer` (`customer_id`,`updated_at`) USING BTREE,
+  FULLTEXT KEY `ft_note` (`note`),
+  CONSTRAINT `fk_customer` FOREIGN KEY (`customer_id`) REFERENCES `customers` (`id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+LOCK TABLES `orders` WRITE;
+INSERT INTO `orders` VALUES (1,NULL,'CREATE TABLE `x` (;',NOW());
+UNLOCK TABLES;
+";
+
+    fn gzip(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), GzLevel::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn zip(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, bytes) in entries {
+            writer.start_file(*name, SimpleFileOptions::default()).unwrap();
+            writer.write_all(bytes).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_gzip_is_detected_by_magic_bytes() {
+        let json = fs::read_to_string("sample_schema1.json").expect("Failed to read sample_schema1.json");
+        let compressed = gzip(json.as_bytes());
+        assert_eq!(detect_compression(&compressed), Compression::Gzip);
+        assert_eq!(detect_compression(json.as_bytes()), Compression::None);
+
+        // The name does not matter, and a single JSON file is passed through unchanged
+        let decoded = decode_upload("export.json", &compressed, None).unwrap();
+        assert_eq!(decoded.compression, Compression::Gzip);
+        assert_eq!(decoded.files, vec!["export.json".to_string()]);
+        assert_eq!(decoded.text, json);
+        assert_eq!(decode_upload("schema1.json.gz", &compressed, None).unwrap().files, vec!["schema1.json".to_string()]);
+
+        // gzip -N keeps the original name in the header
+        let mut named = GzBuilder::new().filename("columns.json").write(Vec::new(), GzLevel::default());
+        named.write_all(json.as_bytes()).unwrap();
+        let (_, files) = decompress("upload", &named.finish().unwrap()).unwrap();
+        assert_eq!(files[0].name, "columns.json");
+
+        assert!(decode_upload("broken.gz", &[0x1F, 0x8B, 0x08, 0x00], None).unwrap_err().starts_with("Error reading gzip file broken.gz"));
+
+        // Unpacking stops at the size cap
+        let limit = json.len() as u64;
+        assert_eq!(decompress_limited("schema1.json.gz", &compressed, limit).unwrap().1[0].bytes, json.as_bytes());
+        assert_eq!(
+            decompress_limited("schema1.json.gz", &compressed, limit - 1).unwrap_err(),
+            format!("Error reading gzip file schema1.json.gz: file unpacks to more than {} bytes", limit - 1)
+        );
+        let archive = zip(&[("columns.json", json.as_bytes())]);
+        assert!(decompress_limited("export.zip", &archive, limit - 1).unwrap_err().starts_with("Error reading columns.json from export.zip: file unpacks"));
+    }
+
+    #[test]
+    fn test_zip_entries_are_merged() {
+        let columns = fs::read_to_string("sample_schema1.json").expect("Failed to read sample_schema1.json");
+        let indexes = fs::read_to_string("sample_index1.json").expect("Failed to read sample_index1.json");
+        let archive = zip(&[
+            ("export/columns.json", columns.as_bytes()),
+            ("export/indexes.json", indexes.as_bytes()),
+            ("export/README.txt", b"not read"),
+            ("__MACOSX/export/._columns.json", b"\x00\x05\x16\x07"),
+        ]);
+        assert_eq!(detect_compression(&archive), Compression::Zip);
+
+        let decoded = decode_upload("export.zip", &archive, None).unwrap();
+        assert_eq!(decoded.compression, Compression::Zip);
+        assert_eq!(decoded.files, vec!["export/columns.json".to_string(), "export/indexes.json".to_string()]);
+        // The merged document is a snapshot, which every comparison entry point reads
+        assert_eq!(create_column_info(&decoded.text).unwrap(), create_column_info(&columns).unwrap());
+        assert_eq!(create_index_info(&decoded.text).unwrap(), create_index_info(&indexes).unwrap());
+
+        let snapshot = read_input("export.zip", &archive, None).unwrap();
+        assert_eq!(snapshot.columns.len(), create_column_info(&columns).unwrap().len());
+
+        let empty = zip(&[("notes.txt", b"nothing here")]);
+        assert_eq!(decode_upload("notes.zip", &empty, None).unwrap_err(), "Zip file notes.zip contains no .json or .sql files");
+        // Merged files have to be readable, a single JSON file is passed through as before
+        let broken = zip(&[("columns.json", columns.as_bytes()), ("indexes.json", b"{\"not\": \"an export\"}")]);
+        assert!(decode_upload("broken.zip", &broken, None).unwrap_err().starts_with("indexes.json: "));
+
+        // A phpMyAdmin export with both tables has to be readable as both
+        let export = |index_row: &str| {
+            format!(
+                r#"[{{"type":"header","version":"5.2.1"}},{{"type":"database","name":"information_schema"}},
+                {{"type":"table","name":"COLUMNS","database":"information_schema","data":[{{"table_schema":"shop","table_name":"orders",
+                  "column_name":"id","data_type":"int","column_type":"int(11)","is_nullable":"NO","column_default":null,"character_maximum_length":null}}]}},
+                {{"type":"table","name":"STATISTICS","database":"information_schema","data":[{}]}}]"#,
+                index_row
+            )
+        };
+        let index_row = r#"{"table_schema":"shop","table_name":"orders","index_name":"PRIMARY","column_name":"id","nullable":"","index_type":"BTREE"}"#;
+        let snapshot = read_input("both.zip", &zip(&[("schema.json", export(index_row).as_bytes())]), None).unwrap();
+        assert_eq!((snapshot.columns.len(), snapshot.indexes.len()), (1, 1));
+        let broken = zip(&[("schema.json", export(r#"{"table_schema":"shop","table_name":"orders"}"#).as_bytes())]);
+        assert!(read_input("broken.zip", &broken, None).unwrap_err().starts_with("schema.json: Block 3: missing field `index_name`"));
+    }
+
+    #[test]
+    fn test_sql_dump_is_read() {
+        let dump = parse_sql_dump(DUMP, "fallback").unwrap();
+        let columns: Vec<(&str, &str, &str)> = dump
+            .columns
+            .iter()
+            .map(|column| (column.table_schema.as_str(), column.column_name.as_str(), column.is_nullable.as_str()))
+            .collect();
+        assert_eq!(columns, vec![("shop", "id", "NO"), ("shop", "customer_id", "YES"), ("shop", "note", "YES"), ("shop", "updated_at", "NO")]);
+
+        let note = &dump.columns[2];
+        assert_eq!((note.data_type.as_str(), note.column_type.as_str()), ("varchar", "varchar(255)"));
+        assert_eq!(note.character_maximum_length, Some(255));
+        assert_eq!(note.column_default.as_deref(), Some("'n/a; none'"));
+        assert_eq!(note.column_comment.as_deref(), Some("It's free text"));
+        assert_eq!(note.collation_name.as_deref(), Some("utf8mb4_unicode_ci"));
+        assert_eq!(dump.columns[0].extra.as_deref(), Some("auto_increment"));
+        assert_eq!(dump.columns[3].extra.as_deref(), Some("on update current_timestamp()"));
+        assert_eq!(dump.columns[1].column_default, None);
+
+        let indexes: Vec<_> = dump
+            .indexes
+            .iter()
+            .map(|index| (index.index_name.as_str(), index.column_name.as_str(), index.seq_in_index, index.sub_part, index.index_type.as_str()))
+            .collect();
+        assert_eq!(
+            indexes,
+            vec![
+                ("PRIMARY", "id", Some(1), None, "BTREE"),
+                ("uq_note", "note", Some(1), Some(10), "BTREE"),
+                ("idx_customer", "customer_id", Some(1), None, "BTREE"),
+                ("idx_customer", "updated_at", Some(2), None, "BTREE"),
+                ("ft_note", "note", Some(1), None, "FULLTEXT"),
+            ]
+        );
+        assert_eq!(dump.indexes[1].non_unique, Some(0));
+        assert_eq!(dump.indexes[2].nullable, "YES");
+
+        // Only the type keyword is lowercased, enum and set values keep their case
+        let dump = parse_sql_dump("CREATE TABLE t (status ENUM('New','Paid') NOT NULL, tags SET('A','b'))", "fallback").unwrap();
+        let types: Vec<(&str, &str)> = dump.columns.iter().map(|column| (column.data_type.as_str(), column.column_type.as_str())).collect();
+        assert_eq!(types, vec![("enum", "enum('New','Paid')"), ("set", "set('A','b')")]);
+
+        // Without a database name the fallback schema is used
+        let dump = parse_sql_dump("CREATE TABLE t (id int PRIMARY KEY, code char(3) UNIQUE)", "fallback").unwrap();
+        assert_eq!(dump.columns[0].table_schema, "fallback");
+        assert_eq!(dump.indexes.iter().map(|index| index.index_name.as_str()).collect::<Vec<&str>>(), vec!["PRIMARY", "code"]);
+        assert_eq!(parse_sql_dump("INSERT INTO t VALUES (1);", "fallback").unwrap_err(), "Cannot find CREATE TABLE statements");
+    }
+
+    #[test]
+    fn test_compressed_dumps_compare() {
+        let changed = DUMP.replace("`note` varchar(255)", "`note` varchar(512)");
+        let first = decode_upload("shop.sql.gz", &gzip(DUMP.as_bytes()), None).unwrap();
+        let second = decode_upload("shop.zip", &zip(&[("shop.sql", changed.as_bytes())]), None).unwrap();
+        assert_eq!(second.files, vec!["shop.sql".to_string()]);
+
+        let result: serde_json::Value = serde_json::from_str(&compare_schemas(&first.text, &second.text)).unwrap();
+        let modified = result["diff"]["columns_with_different_definitions"].as_array().unwrap();
+        assert_eq!(modified.len(), 1);
+        assert_eq!(modified[0]["first"]["column_type"], "varchar(255)");
+    }
+
+    #[test]
+    fn test_mysql_dump_matches_its_export() {
+        let dump = "-- MySQL dump 10.13  Distrib 8.0.36, for Linux (x86_64)
+--
+-- Host: localhost    Database: shop
+CREATE TABLE `orders` (
+  `id` int(11) NOT NULL AUTO_INCREMENT,
+  `status` varchar(10) NOT NULL DEFAULT 'new',
+  `note` varchar(20) DEFAULT NULL COMMENT 'it\\'s (a',
+  PRIMARY KEY (`id`)
+) ENGINE=InnoDB;
+";
+        let export = r#"[
+            {"table_schema": "shop", "table_name": "orders", "column_name": "id", "data_type": "int", "column_type": "int(11)",
+             "is_nullable": "NO", "column_default": null, "extra": "auto_increment", "column_comment": "", "character_maximum_length": null},
+            {"table_schema": "shop", "table_name": "orders", "column_name": "status", "data_type": "varchar", "column_type": "varchar(10)",
+             "is_nullable": "NO", "column_default": "new", "extra": "", "column_comment": "", "character_maximum_length": 10},
+            {"table_schema": "shop", "table_name": "orders", "column_name": "note", "data_type": "varchar", "column_type": "varchar(20)",
+             "is_nullable": "YES", "column_default": null, "extra": "", "column_comment": "it's (a", "character_maximum_length": 20}
+        ]"#;
+
+        let decoded = decode_upload("shop.sql", dump.as_bytes(), None).unwrap();
+        assert_eq!(create_column_info(&decoded.text).unwrap(), create_column_info(export).unwrap());
+
+        let result: serde_json::Value = serde_json::from_str(&compare_schemas(&decoded.text, export)).unwrap();
+        assert_eq!(result["diff"]["columns_with_different_definitions"].as_array().unwrap().len(), 0);
+        assert_eq!(result["sql"].as_object().unwrap().len(), 0);
+    }
+}
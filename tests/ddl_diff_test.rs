@@ -0,0 +1,99 @@
+mod common;
+
+#[cfg(test)]
+mod ddl_diff_tests {
+    use std::collections::HashMap;
+    use std::fs;
+    use db_diff::build_schema_map;
+    use db_diff::ddl_diff::{DEFAULT_CONTEXT_LINES, table_ddl, unified_ddl_diff, unified_diff, unified_schema_diff};
+    use db_diff::index::build_index_map;
+    use super::common::{column, index};
+
+    fn lines(text: &[&str]) -> Vec<String> {
+        text.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_table_ddl() {
+        let schema_map = build_schema_map(vec![column("orders", "id", "int(11)"), column("orders", "note", "text")]);
+        let index_map = build_index_map(vec![
+            index("orders", "idx_note", "note", 1).sub_part_opt(10),
+            index("orders", "uk_id_note", "note", 2).non_unique_opt(0),
+            index("orders", "uk_id_note", "id", 1).non_unique_opt(0),
+            index("orders", "PRIMARY", "id", 1).non_unique_opt(0),
+        ]);
+
+        let ddl = table_ddl("`shop`.orders", schema_map.get("`shop`.orders"), index_map.get("`shop`.orders"));
+        assert_eq!(ddl, lines(&[
+            "CREATE TABLE `shop`.orders (",
+            "  id int(11) NOT NULL,",
+            "  note text NOT NULL,",
+            "  PRIMARY KEY (id) USING BTREE,",
+            "  KEY `idx_note` (note(10)) USING BTREE,",
+            "  UNIQUE KEY `uk_id_note` (id, note) USING BTREE",
+            ");",
+        ]));
+        assert!(table_ddl("`shop`.missing", None, None).is_empty());
+    }
+
+    #[test]
+    fn test_unified_diff_hunks() {
+        let old: Vec<String> = (1..=12).map(|n| format!("line {}", n)).collect();
+        let mut new = old.clone();
+        new[1] = "line two".to_string();
+        new.remove(10);
+
+        assert_eq!(unified_diff(&old, &old, "a/x", "b/x", 3), "");
+        assert_eq!(
+            unified_diff(&old, &new, "a/x", "b/x", 1),
+            "--- a/x\n+++ b/x\n@@ -1,3 +1,3 @@\n line 1\n-line 2\n+line two\n line 3\n@@ -10,3 +10,2 @@\n line 10\n-line 11\n line 12\n"
+        );
+        // With more context both changes end up in one hunk
+        assert_eq!(unified_diff(&old, &new, "a/x", "b/x", 4).matches("@@ -").count(), 1);
+        assert_eq!(
+            unified_diff(&[], &lines(&["a"]), "/dev/null", "b/x", 3),
+            "--- /dev/null\n+++ b/x\n@@ -0,0 +1 @@\n+a\n"
+        );
+    }
+
+    #[test]
+    fn test_only_changed_tables_are_included() {
+        let schema_map1 = build_schema_map(vec![
+            column("orders", "id", "int(11)"),
+            column("orders", "note", "varchar(20)"),
+            column("customers", "id", "int(11)"),
+            column("legacy", "id", "int(11)"),
+        ]);
+        let schema_map2 = build_schema_map(vec![
+            column("orders", "id", "int(11)"),
+            column("orders", "note", "varchar(50)"),
+            column("customers", "id", "int(11)"),
+        ]);
+
+        let diff = unified_schema_diff(&schema_map1, &schema_map2, &HashMap::new(), &HashMap::new(), DEFAULT_CONTEXT_LINES);
+        assert!(!diff.contains("customers"));
+        assert!(diff.contains("--- a/shop.legacy\n+++ /dev/null\n@@ -1,3 +0,0 @@\n-CREATE TABLE `shop`.legacy (\n"));
+        assert!(diff.contains("--- a/shop.orders\n+++ b/shop.orders\n"));
+        assert!(diff.contains("-  note varchar(20) NOT NULL\n+  note varchar(50) NOT NULL\n"));
+        // Tables are listed in key order
+        assert!(diff.find("shop.legacy").unwrap() < diff.find("shop.orders").unwrap());
+    }
+
+    #[test]
+    fn test_unified_ddl_diff_json() {
+        let indexes1 = serde_json::to_string(&vec![index("orders", "PRIMARY", "id", 1).non_unique_opt(0)]).unwrap();
+        let indexes2 = serde_json::to_string(&vec![index("orders", "PRIMARY", "id", 1).non_unique_opt(0), index("orders", "idx_note", "note", 1)]).unwrap();
+
+        assert_eq!(
+            unified_ddl_diff("", "", &indexes1, &indexes2, 0),
+            "--- a/shop.orders\n+++ b/shop.orders\n@@ -2 +2,2 @@\n-  PRIMARY KEY (id) USING BTREE\n+  PRIMARY KEY (id) USING BTREE,\n+  KEY `idx_note` (note) USING BTREE\n"
+        );
+
+        let json1 = fs::read_to_string("sample_schema1.json").expect("Failed to read sample_schema1.json");
+        let json2 = fs::read_to_string("sample_schema2.json").expect("Failed to read sample_schema2.json");
+        let diff = unified_ddl_diff(&json1, &json2, "", "", 3);
+        assert!(diff.starts_with("--- "));
+        assert_eq!(unified_ddl_diff(&json1, &json1, "", "", 3), "");
+        assert!(unified_ddl_diff("{", &json2, "", "", 3).starts_with("Error parsing first JSON"));
+    }
+}
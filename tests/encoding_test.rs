@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod encoding_tests {
+    use std::fs;
+    use db_diff::create_column_info;
+    use db_diff::encoding::{DetectedBy, decode_bytes};
+
+    fn utf16(text: &str, little_endian: bool, bom: bool) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let units = if bom { vec![0xFEFF] } else { vec![] };
+        for unit in units.into_iter().chain(text.encode_utf16()) {
+            let pair = if little_endian { unit.to_le_bytes() } else { unit.to_be_bytes() };
+            bytes.extend_from_slice(&pair);
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_byte_order_marks() {
+        let json = fs::read_to_string("sample_schema1.json").expect("Failed to read sample_schema1.json");
+
+        let mut utf8 = vec![0xEF, 0xBB, 0xBF];
+        utf8.extend_from_slice(json.as_bytes());
+        let decoded = decode_bytes(&utf8, None).unwrap();
+        assert_eq!((decoded.encoding.as_str(), decoded.detected_by), ("UTF-8", DetectedBy::Bom));
+        assert_eq!(decoded.text, json);
+
+        for (little_endian, name) in [(true, "UTF-16LE"), (false, "UTF-16BE")] {
+            let decoded = decode_bytes(&utf16(&json, little_endian, true), None).unwrap();
+            assert_eq!((decoded.encoding.as_str(), decoded.detected_by), (name, DetectedBy::Bom));
+            assert!(!decoded.had_replacements);
+            assert_eq!(create_column_info(&decoded.text).unwrap().len(), create_column_info(&json).unwrap().len());
+        }
+    }
+
+    #[test]
+    fn test_detection_without_bom() {
+        let decoded = decode_bytes("[{\"comment\": \"ราคา\"}]".as_bytes(), None).unwrap();
+        assert_eq!((decoded.encoding.as_str(), decoded.detected_by), ("UTF-8", DetectedBy::Utf8));
+
+        let decoded = decode_bytes(&utf16("[{\"a\": 1}]", true, false), None).unwrap();
+        assert_eq!((decoded.encoding.as_str(), decoded.detected_by), ("UTF-16LE", DetectedBy::Heuristic));
+        assert_eq!(decoded.text, "[{\"a\": 1}]");
+
+        // "Prix unitaire en €, café compris" in Latin-1 / windows-1252
+        let latin1: Vec<u8> = b"[{\"column_comment\": \"Prix unitaire en \x80, caf\xe9 compris, d\xe9j\xe0 pay\xe9\"}]".to_vec();
+        let decoded = decode_bytes(&latin1, None).unwrap();
+        assert_eq!((decoded.encoding.as_str(), decoded.detected_by), ("windows-1252", DetectedBy::Heuristic));
+        assert!(decoded.text.contains("en €, café compris, déjà payé"));
+
+        // Thai comment in TIS-620 / windows-874, what the old fallback assumed for every non UTF-8 file
+        let (thai, _, _) = encoding_rs::WINDOWS_874.encode("[{\"column_comment\": \"ราคาสินค้าต่อหน่วย รวมภาษีมูลค่าเพิ่มแล้ว\"}]");
+        let decoded = decode_bytes(&thai, None).unwrap();
+        assert_eq!(decoded.encoding, "windows-874");
+        assert!(decoded.text.contains("ราคาสินค้าต่อหน่วย"));
+        assert!(!decoded.had_replacements);
+    }
+
+    #[test]
+    fn test_override_and_replacements() {
+        let latin1 = b"[{\"column_comment\": \"caf\xe9\"}]";
+
+        let decoded = decode_bytes(latin1, Some("latin1")).unwrap();
+        assert_eq!((decoded.encoding.as_str(), decoded.detected_by), ("windows-1252", DetectedBy::Override));
+        assert!(decoded.text.contains("café"));
+
+        // Forcing the wrong encoding is reported instead of silently producing mojibake
+        let decoded = decode_bytes(latin1, Some("utf-8")).unwrap();
+        assert!(decoded.had_replacements);
+        assert!(decoded.text.contains('\u{FFFD}'));
+
+        // The BOM of the requested encoding is stripped, an empty override means auto-detect
+        let decoded = decode_bytes(&utf16("[]", true, true), Some("utf-16")).unwrap();
+        assert_eq!(decoded.text, "[]");
+        assert_eq!(decode_bytes(b"[]", Some(" ")).unwrap().detected_by, DetectedBy::Utf8);
+
+        assert_eq!(decode_bytes(b"[]", Some("klingon")).unwrap_err(), "Unknown encoding: klingon");
+    }
+}
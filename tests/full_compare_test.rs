@@ -0,0 +1,141 @@
+mod common;
+
+#[cfg(test)]
+mod full_compare_tests {
+    use std::fs;
+    use db_diff::full::{FullComparison, TableStatus, compare_columns_and_indexes, compare_full};
+    use db_diff::output::FORMAT_VERSION;
+    use super::common::{column, index};
+
+    fn lines(script: &str) -> Vec<&str> {
+        script.lines().collect()
+    }
+
+    #[test]
+    fn test_new_table_is_created_with_its_indexes() {
+        let columns2 = vec![
+            column("orders", "id", "int(11)"),
+            column("orders", "customer_id", "int(11)"),
+            column("orders", "note", "text"),
+        ];
+        let indexes2 = vec![
+            index("orders", "PRIMARY", "id", 1).nullable("NO").non_unique_opt(0),
+            index("orders", "uk_customer_note", "note", 2).nullable("NO").non_unique_opt(0).sub_part_opt(20),
+            index("orders", "uk_customer_note", "customer_id", 1).nullable("NO").non_unique_opt(0),
+        ];
+
+        let result = compare_columns_and_indexes(vec![], columns2, vec![], indexes2);
+        assert_eq!(lines(&result.forward_script), vec![
+            "CREATE TABLE `shop`.orders (id int(11) NOT NULL, customer_id int(11) NOT NULL, note text NOT NULL);",
+            "ALTER TABLE `shop`.orders ADD PRIMARY KEY (id);",
+            "CREATE UNIQUE INDEX `uk_customer_note` ON `shop`.orders (customer_id, note(20));",
+        ]);
+        assert_eq!(result.reverse_script, "DROP TABLE `shop`.orders;");
+        assert!(result.verification_errors.is_empty(), "{:?}", result.verification_errors);
+
+        assert_eq!(result.tables.len(), 1);
+        let table = &result.tables[0];
+        assert_eq!(table.status, TableStatus::OnlyInSecond);
+        assert_eq!(table.indexes_only_in_second.len(), 3);
+        assert!(table.ddl_diff.starts_with("--- /dev/null\n+++ b/shop.orders\n"));
+        assert_eq!(table.forward_sql.len(), 3);
+    }
+
+    #[test]
+    fn test_column_and_index_steps_are_ordered() {
+        let columns1 = vec![
+            column("orders", "id", "int(11)"),
+            column("orders", "note", "varchar(20)"),
+            column("customers", "id", "int(11)"),
+        ];
+        let columns2 = vec![
+            column("orders", "id", "int(11)"),
+            column("orders", "status", "varchar(10)"),
+            column("customers", "id", "int(11)"),
+        ];
+        let indexes1 = vec![
+            index("orders", "idx_id_note", "id", 1).nullable("NO"),
+            index("orders", "idx_id_note", "note", 2).nullable("NO"),
+        ];
+        let indexes2 = vec![
+            index("orders", "idx_id_note", "id", 1).nullable("NO"),
+            index("orders", "idx_status", "status", 1).nullable("NO"),
+        ];
+
+        let result = compare_columns_and_indexes(columns1, columns2, indexes1, indexes2);
+        // The index is dropped before the column it covers, and indexes are created after their columns
+        assert_eq!(lines(&result.forward_script), vec![
+            "DROP INDEX `idx_id_note` ON `shop`.orders;",
+            "ALTER TABLE `shop`.orders DROP COLUMN note;",
+            "ALTER TABLE `shop`.orders ADD COLUMN status varchar(10) NOT NULL;",
+            "CREATE INDEX `idx_id_note` ON `shop`.orders (id);",
+            "CREATE INDEX `idx_status` ON `shop`.orders (status);",
+        ]);
+        assert_eq!(lines(&result.reverse_script), vec![
+            "DROP INDEX `idx_status` ON `shop`.orders;",
+            "DROP INDEX `idx_id_note` ON `shop`.orders;",
+            "ALTER TABLE `shop`.orders DROP COLUMN status;",
+            "ALTER TABLE `shop`.orders ADD COLUMN note varchar(20) NOT NULL;",
+            "CREATE INDEX `idx_id_note` ON `shop`.orders (id, note);",
+        ]);
+        assert!(result.verification_errors.is_empty(), "{:?}", result.verification_errors);
+
+        // Unchanged tables are left out
+        assert_eq!(result.tables.len(), 1);
+        let table = &result.tables[0];
+        assert_eq!((table.schema.as_str(), table.table.as_str()), ("shop", "orders"));
+        assert_eq!(table.status, TableStatus::Changed);
+        assert_eq!(table.columns_only_in_first[0].column_name, "note");
+        assert_eq!(table.indexes_with_different_definitions[0].index_name, "idx_id_note");
+        assert!(table.ddl_diff.contains("-  KEY `idx_id_note` (id, note) USING BTREE\n"));
+    }
+
+    #[test]
+    fn test_indexes_of_a_table_without_columns_are_left_out() {
+        let columns1 = vec![column("orders", "id", "int(11)"), column("customers", "id", "int(11)")];
+        let columns2 = vec![column("customers", "id", "int(11)")];
+        let indexes = vec![index("orders", "PRIMARY", "id", 1).nullable("NO").non_unique_opt(0)];
+
+        let result = compare_columns_and_indexes(columns1, columns2, vec![], indexes);
+        assert_eq!(result.forward_script, "DROP TABLE `shop`.orders;");
+        assert_eq!(result.reverse_script, "CREATE TABLE `shop`.orders (id int(11) NOT NULL);");
+        assert_eq!(
+            result.verification_errors,
+            vec!["Indexes of `shop`.orders in the second export are left out, the table is not in its column export".to_string()]
+        );
+        assert_eq!(result.tables.len(), 1);
+        assert!(result.tables[0].indexes_only_in_second.is_empty());
+    }
+
+    #[test]
+    fn test_primary_key_changes() {
+        let columns = vec![column("orders", "id", "int(11)"), column("orders", "line", "int(11)")];
+        let indexes1 = vec![index("orders", "PRIMARY", "id", 1).nullable("NO").non_unique_opt(0)];
+        let indexes2 = vec![index("orders", "PRIMARY", "id", 1).nullable("NO").non_unique_opt(0), index("orders", "PRIMARY", "line", 2).nullable("NO").non_unique_opt(0)];
+
+        let result = compare_columns_and_indexes(columns.clone(), columns, indexes1, indexes2);
+        assert_eq!(lines(&result.forward_script), vec![
+            "ALTER TABLE `shop`.orders DROP PRIMARY KEY;",
+            "ALTER TABLE `shop`.orders ADD PRIMARY KEY (id, line);",
+        ]);
+        assert!(result.verification_errors.is_empty(), "{:?}", result.verification_errors);
+    }
+
+    #[test]
+    fn test_compare_full_json() {
+        let json1 = fs::read_to_string("sample_schema1.json").expect("Failed to read sample_schema1.json");
+        let json2 = fs::read_to_string("sample_schema2.json").expect("Failed to read sample_schema2.json");
+
+        let result: FullComparison = serde_json::from_str(&compare_full(&json1, &json2, "", "")).unwrap();
+        assert_eq!(result.format_version, FORMAT_VERSION);
+        assert!(!result.tables.is_empty());
+        assert!(result.verification_errors.is_empty(), "{:?}", result.verification_errors);
+        // Tables come out in key order
+        let keys: Vec<String> = result.tables.iter().map(|table| format!("{}.{}", table.schema, table.table)).collect();
+        let mut sorted = keys.clone();
+        sorted.sort();
+        assert_eq!(keys, sorted);
+
+        assert!(compare_full("{", &json2, "", "").starts_with("Error parsing first JSON"));
+    }
+}
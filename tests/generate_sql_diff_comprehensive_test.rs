@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use db_diff::{SchemaDiff, ColumnInfo, ColumnDifference, generate_sql_diff};
+    use db_diff::{SchemaDiff, ColumnInfo, ColumnDifference, TableDefinition, generate_sql_diff};
 
     #[test]
     fn test_generate_sql_diff_with_empty_diff() {
@@ -21,10 +21,10 @@ mod tests {
     fn test_generate_sql_diff_with_table_differences() {
         let diff = SchemaDiff {
             tables_only_in_first: vec![
-                ("`public`.old_table".to_string(), "old_table".to_string()),
+                TableDefinition { schema: "public".to_string(), table: "old_table".to_string(), definition: "old_table".to_string() },
             ],
             tables_only_in_second: vec![
-                ("`public`.new_table".to_string(), "new_table".to_string()),
+                TableDefinition { schema: "public".to_string(), table: "new_table".to_string(), definition: "new_table".to_string() },
             ],
             columns_only_in_first: vec![],
             columns_only_in_second: vec![],
@@ -107,6 +107,7 @@ mod tests {
             columns_only_in_second: vec![],
             columns_with_different_definitions: vec![
                 ColumnDifference {
+                    table_schema: "public".to_string(),
                     table_name: "users".to_string(),
                     column_name: "modified_column".to_string(),
                     first: ColumnInfo::builder(
@@ -148,10 +149,10 @@ mod tests {
     fn test_generate_sql_diff_with_multiple_differences() {
         let diff = SchemaDiff {
             tables_only_in_first: vec![
-                ("public".to_string(), "table1".to_string()),
+                TableDefinition { schema: "public".to_string(), table: "table1".to_string(), definition: "table1".to_string() },
             ],
             tables_only_in_second: vec![
-                ("public".to_string(), "table2".to_string()),
+                TableDefinition { schema: "public".to_string(), table: "table2".to_string(), definition: "table2".to_string() },
             ],
             columns_only_in_first: vec![
                 ColumnInfo::builder(
@@ -177,6 +178,7 @@ mod tests {
             ],
             columns_with_different_definitions: vec![
                 ColumnDifference {
+                    table_schema: "public".to_string(),
                     table_name: "orders".to_string(),
                     column_name: "status".to_string(),
                     first: ColumnInfo::builder(
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use db_diff::{SchemaDiff, ColumnInfo, ColumnDifference, generate_sql_diff};
+    use db_diff::{SchemaDiff, ColumnInfo, ColumnDifference, TableDefinition, generate_sql_diff};
 
     #[test]
     fn test_generate_sql_diff_key_collision_documentation() {
@@ -9,12 +9,12 @@ mod tests {
 
         let diff = SchemaDiff {
             tables_only_in_first: vec![
-                ("public".to_string(), "table1".to_string()),
-                ("public".to_string(), "table2".to_string()),
+                TableDefinition { schema: "public".to_string(), table: "table1".to_string(), definition: "table1".to_string() },
+                TableDefinition { schema: "public".to_string(), table: "table2".to_string(), definition: "table2".to_string() },
             ],
             tables_only_in_second: vec![
-                ("public".to_string(), "table3".to_string()),
-                ("public".to_string(), "table4".to_string()),
+                TableDefinition { schema: "public".to_string(), table: "table3".to_string(), definition: "table3".to_string() },
+                TableDefinition { schema: "public".to_string(), table: "table4".to_string(), definition: "table4".to_string() },
             ],
             columns_only_in_first: vec![
                 ColumnInfo::builder(
@@ -55,6 +55,7 @@ mod tests {
             ],
             columns_with_different_definitions: vec![
                 ColumnDifference {
+                    table_schema: "public".to_string(),
                     table_name: "products".to_string(),
                     column_name: "price".to_string(),
                     first: ColumnInfo::builder(
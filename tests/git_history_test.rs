@@ -0,0 +1,123 @@
+mod common;
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod git_history_tests {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::process::Command;
+    use db_diff::ColumnInfo;
+    use db_diff::cli::run;
+    use db_diff::index::IndexInfo;
+    use db_diff::snapshot::Snapshot;
+    use super::common::{column, index};
+
+    fn git(repository: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(["-C", &repository.to_string_lossy(), "-c", "user.name=Dana", "-c", "user.email=dana@example.com"])
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    fn commit(repository: &Path, file: &str, contents: &str, message: &str) -> String {
+        let path = repository.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, contents).unwrap();
+        git(repository, &["add", "-A"]);
+        git(repository, &["commit", "-q", "-m", message]);
+        git(repository, &["rev-parse", "HEAD"])
+    }
+
+    fn snapshot(columns: Vec<ColumnInfo>, indexes: Vec<IndexInfo>) -> String {
+        serde_json::to_string_pretty(&Snapshot::new(columns, indexes).seal()).unwrap()
+    }
+
+    // Four commits: the first snapshot, status made NOT NULL, an index and a table added, and
+    // an unrelated file
+    fn repository(name: &str) -> (PathBuf, Vec<String>) {
+        let repository = std::env::temp_dir().join(format!("db_diff_git_{}_{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&repository);
+        fs::create_dir_all(&repository).unwrap();
+        git(&repository, &["init", "-q"]);
+        let orders = |status: &str| vec![column("orders", "id", "varchar(16)"), ColumnInfo { is_nullable: status.to_string(), ..column("orders", "status", "varchar(16)") }];
+        let commits = vec![
+            commit(&repository, "schema/shop.json", &snapshot(orders("YES"), vec![]), "Initial schema"),
+            commit(&repository, "schema/shop.json", &snapshot(orders("NO"), vec![]), "Make status required"),
+            commit(
+                &repository,
+                "schema/shop.json",
+                &snapshot([orders("NO"), vec![column("customers", "id", "varchar(16)")]].concat(), vec![index("orders", "idx_status", "status", 1).nullable("")]),
+                "Index status, add customers",
+            ),
+            commit(&repository, "README.md", "docs", "Unrelated"),
+        ];
+        (repository, commits)
+    }
+
+    fn run_in(repository: &Path, line: &str) -> Result<String, String> {
+        let mut args: Vec<String> = line.split_whitespace().map(str::to_string).collect();
+        args.extend(["-C".to_string(), repository.to_string_lossy().into_owned()]);
+        run(&args)
+    }
+
+    #[test]
+    fn test_history_per_table() {
+        let (repository, commits) = repository("history");
+        let output = run_in(&repository, &format!("git {} HEAD schema/shop.json", commits[0])).unwrap();
+        let result: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+        let tables: Vec<&str> = result["comparison"]["comparison"]["tables"].as_array().unwrap().iter().map(|table| table["table"].as_str().unwrap()).collect();
+        assert_eq!(tables, vec!["customers", "orders"]);
+
+        let history = result["history"]["tables"].as_array().unwrap();
+        assert_eq!(history.len(), 2);
+        let orders = &history[1];
+        assert_eq!(orders["table"], "orders");
+        let changes = orders["changes"].as_array().unwrap();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0]["revision"]["commit"], commits[1].as_str());
+        assert_eq!(changes[0]["revision"]["subject"], "Make status required");
+        assert_eq!(changes[0]["revision"]["author"], "Dana");
+        assert_eq!(
+            changes[0]["changes"][0],
+            "changed column `status`: status varchar(16) NULL -> status varchar(16) NOT NULL"
+        );
+        assert!(changes[0]["forward_sql"][0].as_str().unwrap().contains("MODIFY"));
+        assert_eq!(changes[1]["changes"][0], "added index `idx_status` (status)");
+        assert_eq!(history[0]["changes"][0]["status"], "only_in_second");
+        assert_eq!(history[0]["changes"][0]["changes"][0], "created table with 1 column(s)");
+
+        // Only the commits in the range are listed
+        let output = run_in(&repository, &format!("git {} {} schema/shop.json --table shop.orders", commits[1], commits[2])).unwrap();
+        let result: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let history = result["history"]["tables"].as_array().unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0]["changes"].as_array().map(Vec::len), Some(1));
+
+        let markdown = run_in(&repository, &format!("git {} HEAD schema/shop.json --format markdown --table orders", commits[0])).unwrap();
+        assert!(markdown.starts_with("# Schema diff report"));
+        assert!(markdown.contains(&format!("- `{}`", &commits[1][..7])));
+        assert!(markdown.contains("  - changed column `status`: status varchar(16) NULL -> status varchar(16) NOT NULL\n"));
+        assert!(!markdown.contains("### `shop`.customers"));
+
+        fs::remove_dir_all(&repository).unwrap();
+    }
+
+    #[test]
+    fn test_errors() {
+        let (repository, commits) = repository("errors");
+        assert_eq!(
+            run_in(&repository, "git no-such-revision HEAD schema/shop.json").unwrap_err(),
+            "Unknown revision: no-such-revision"
+        );
+        assert_eq!(
+            run_in(&repository, &format!("git {} HEAD schema/other.json", commits[0])).unwrap_err(),
+            format!("schema/other.json does not exist at {}", commits[0])
+        );
+        assert!(run_in(&repository, "git HEAD schema/shop.json").unwrap_err().starts_with("git needs two revisions and a path, got 2 argument(s)"));
+        assert_eq!(run_in(&repository, "git HEAD~1 HEAD schema/shop.json --format html").unwrap_err(), "git writes json or markdown");
+        fs::remove_dir_all(&repository).unwrap();
+    }
+}
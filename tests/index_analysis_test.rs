@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod index_analysis_tests {
+    use std::fs;
+    use db_diff::index::{IndexInfo, build_index_map};
+    use db_diff::index_analysis::{Redundancy, analyze_index_map, analyze_indexes};
+
+    fn index(name: &str, column_name: &str, seq: u32, non_unique: u8) -> IndexInfo {
+        IndexInfo::builder()
+            .table_schema("public")
+            .table_name("orders")
+            .index_name(name)
+            .column_name(column_name)
+            .seq_in_index(seq)
+            .nullable("YES")
+            .index_type("BTREE")
+            .non_unique_opt(non_unique)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_left_prefix_and_duplicates() {
+        let indexes = vec![
+            index("PRIMARY", "id", 1, 0),
+            index("uk_id", "id", 1, 0),
+            index("idx_customer", "customer_id", 1, 1),
+            index("idx_customer_status", "customer_id", 1, 1),
+            index("idx_customer_status", "status", 2, 1),
+            index("idx_status", "status", 1, 1),
+            index("idx_status_copy", "status", 1, 1),
+            index("uk_number", "number", 1, 0),
+            index("uk_number_tenant", "number", 1, 0),
+            index("uk_number_tenant", "tenant_id", 2, 0),
+        ];
+
+        let findings = analyze_index_map(&build_index_map(indexes));
+        let found: Vec<(&str, Redundancy, &str)> = findings
+            .iter()
+            .map(|finding| (finding.index_name.as_str(), finding.redundancy, finding.covered_by.as_str()))
+            .collect();
+
+        assert_eq!(found, vec![
+            ("uk_id", Redundancy::DuplicatesPrimary, "PRIMARY"),
+            ("idx_customer", Redundancy::LeftPrefix, "idx_customer_status"),
+            ("idx_status_copy", Redundancy::Duplicate, "idx_status"),
+        ]);
+        assert_eq!(findings[1].sql, "DROP INDEX `idx_customer` ON `public`.orders;");
+    }
+
+    #[test]
+    fn test_prefix_length_is_part_of_the_index() {
+        let indexes = vec![
+            index("idx_name", "name", 1, 1),
+            index("idx_name_short", "name", 1, 1).sub_part_opt(10),
+        ];
+
+        assert!(analyze_index_map(&build_index_map(indexes)).is_empty());
+    }
+
+    #[test]
+    fn test_analyze_sample_index_file() {
+        let content = fs::read_to_string("sample_index1.json").expect("Failed to read sample_index1.json");
+
+        let result: serde_json::Value = serde_json::from_str(&analyze_indexes(&content)).unwrap();
+        let findings = result["findings"].as_array().unwrap();
+        assert!(!findings.is_empty());
+        for finding in findings {
+            assert_ne!(finding["index_name"], "PRIMARY");
+            assert!(result["sql"].as_str().unwrap().contains(finding["sql"].as_str().unwrap()));
+        }
+    }
+}
@@ -222,6 +222,7 @@ mod index_diff_tests {
             .unwrap();
 
         let difference = IndexDifference {
+            table_schema: "public".to_string(),
             table_name: "users".to_string(),
             index_name: "idx_users_email".to_string(),
             first: index1,
@@ -280,6 +281,7 @@ mod index_diff_tests {
                     .unwrap(),
             ],
             indexes_with_different_definitions: vec![],
+            indexes_renamed: vec![],
         };
 
         let sql_statements = generate_sql_index_diff(&diff);
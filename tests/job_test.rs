@@ -0,0 +1,204 @@
+#[cfg(test)]
+mod job_tests {
+    use std::fs;
+    use db_diff::{ColumnInfo, compare_schemas};
+    use db_diff::index::{IndexInfo, compare_indexs};
+    use db_diff::job::{ComparisonJob, IndexComparisonJob, Phase, Progress};
+
+    fn columns(tables: usize, column_type: &str) -> String {
+        let columns: Vec<ColumnInfo> = (0..tables * 4)
+            .map(|i| {
+                ColumnInfo::builder(
+                    "shop".to_string(),
+                    format!("table_{:03}", i / 4),
+                    format!("column_{}", i % 4),
+                    "int".to_string(),
+                    if i == 5 { column_type.to_string() } else { "int(11)".to_string() },
+                    "NO".to_string(),
+                )
+            })
+            .collect();
+        serde_json::to_string(&columns).unwrap()
+    }
+
+    fn parse(json: &str) -> serde_json::Value {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn run(mut job: ComparisonJob) -> Vec<Progress> {
+        let mut reports = Vec::new();
+        while !job.is_done() {
+            reports.push(job.step().unwrap());
+        }
+        reports
+    }
+
+    #[test]
+    fn test_steps_report_rows_and_tables() {
+        let json1 = columns(10, "int(11)");
+        let json2 = columns(12, "bigint(20)");
+
+        let reports = run(ComparisonJob::new(json1.clone(), json2.clone()).step_size(15, 5));
+        let summary: Vec<(Phase, u64, u64)> = reports.iter().map(|progress| (progress.phase, progress.done, progress.total)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Phase::ParseFirst, 15, 40),
+                (Phase::ParseFirst, 30, 40),
+                (Phase::ParseFirst, 40, 40),
+                (Phase::ParseSecond, 15, 48),
+                (Phase::ParseSecond, 30, 48),
+                (Phase::ParseSecond, 45, 48),
+                (Phase::ParseSecond, 48, 48),
+                (Phase::Compare, 5, 12),
+                (Phase::Compare, 10, 12),
+                (Phase::Compare, 12, 12),
+                (Phase::Sql, 1, 1),
+                (Phase::Verify, 1, 3),
+                (Phase::Verify, 2, 3),
+                (Phase::Verify, 3, 3),
+            ]
+        );
+
+        // The result is the one compare_schemas returns
+        let result = ComparisonJob::new(json1.clone(), json2.clone()).step_size(15, 5).finish().unwrap();
+        assert_eq!(serde_json::to_value(&result).unwrap(), parse(&compare_schemas(&json1, &json2)));
+    }
+
+    #[test]
+    fn test_index_steps() {
+        let indexes = |tables: usize, unique_table: usize| -> String {
+            let indexes: Vec<IndexInfo> = (0..tables * 2)
+                .map(|i| {
+                    IndexInfo::builder()
+                        .table_schema("shop")
+                        .table_name(format!("table_{:03}", i / 2))
+                        .index_name("idx_columns")
+                        .column_name(format!("column_{}", i % 2))
+                        .seq_in_index(i as u32 % 2 + 1)
+                        .nullable("")
+                        .index_type("BTREE")
+                        .non_unique_opt(if i / 2 == unique_table { 0 } else { 1 })
+                })
+                .collect();
+            serde_json::to_string(&indexes).unwrap()
+        };
+        let json1 = indexes(4, 9);
+        let json2 = indexes(5, 1);
+
+        let mut job = IndexComparisonJob::new(json1.clone(), json2.clone()).step_size(5, 2);
+        let mut reports = Vec::new();
+        while !job.is_done() {
+            reports.push(job.step().unwrap());
+        }
+        let summary: Vec<(Phase, u64, u64)> = reports.iter().map(|progress| (progress.phase, progress.done, progress.total)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Phase::ParseFirst, 5, 8),
+                (Phase::ParseFirst, 8, 8),
+                (Phase::ParseSecond, 5, 10),
+                (Phase::ParseSecond, 10, 10),
+                (Phase::Compare, 2, 5),
+                (Phase::Compare, 4, 5),
+                (Phase::Compare, 5, 5),
+                (Phase::Sql, 1, 1),
+                (Phase::Verify, 1, 3),
+                (Phase::Verify, 2, 3),
+                (Phase::Verify, 3, 3),
+            ]
+        );
+
+        // The result is the one compare_indexs returns, phpMyAdmin exports are read in one step
+        let result = IndexComparisonJob::new(json1.clone(), json2.clone()).step_size(5, 2).finish().unwrap();
+        assert_eq!(serde_json::to_value(&result).unwrap(), parse(&compare_indexs(&json1, &json2)));
+        let export1 = fs::read_to_string("sample_index1.json").expect("Failed to read sample_index1.json");
+        let export2 = fs::read_to_string("sample_index2.json").expect("Failed to read sample_index2.json");
+        let result = IndexComparisonJob::new(export1.clone(), export2.clone()).finish().unwrap();
+        assert_eq!(serde_json::to_value(&result).unwrap(), parse(&compare_indexs(&export1, &export2)));
+    }
+
+    #[test]
+    fn test_other_inputs_are_read_in_one_step() {
+        let json = fs::read_to_string("sample_schema1.json").expect("Failed to read sample_schema1.json");
+        let export = format!(r#"[{{"type":"header"}},{{"type":"table","name":"COLUMNS","data":{}}}]"#, json);
+
+        let reports = run(ComparisonJob::new(export.clone(), json.clone()).step_size(2, 1));
+        assert_eq!((reports[0].phase, reports[0].done, reports[0].total), (Phase::ParseFirst, 6, 6));
+        assert_eq!(reports[1].phase, Phase::ParseSecond);
+
+        let result = ComparisonJob::new(export.clone(), json.clone()).finish().unwrap();
+        assert_eq!(serde_json::to_value(&result).unwrap(), parse(&compare_schemas(&export, &json)));
+    }
+
+    #[test]
+    fn test_errors_name_the_failing_side() {
+        let json = columns(2, "int(11)");
+        let broken = json.replace("\"column_1\"", "1");
+
+        let mut job = ComparisonJob::new(json.clone(), broken.clone());
+        let error = loop {
+            match job.step() {
+                Ok(_) => continue,
+                Err(e) => break e,
+            }
+        };
+        assert_eq!(job.phase(), Phase::ParseSecond);
+        // Same message as the synchronous entry point
+        assert_eq!(error, compare_schemas(&json, &broken));
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    mod wasm {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+        use wasm_bindgen::JsValue;
+        use wasm_bindgen::closure::Closure;
+        use wasm_bindgen::JsCast;
+        use wasm_bindgen_futures::JsFuture;
+        use wasm_bindgen_test::wasm_bindgen_test;
+        use db_diff::job::{compare_indexs_async, compare_schemas_async};
+
+        fn get(value: &JsValue, key: &str) -> JsValue {
+            js_sys::Reflect::get(value, &JsValue::from_str(key)).unwrap()
+        }
+
+        #[wasm_bindgen_test]
+        async fn test_progress_is_reported() {
+            let json = super::columns(3, "int(11)");
+            let phases = Rc::new(RefCell::new(Vec::new()));
+            let seen = phases.clone();
+            let on_progress = Closure::<dyn FnMut(JsValue)>::new(move |progress: JsValue| {
+                seen.borrow_mut().push(get(&progress, "phase").as_string().unwrap());
+            });
+
+            let promise = compare_schemas_async(json.clone(), json, Some(on_progress.as_ref().unchecked_ref::<js_sys::Function>().clone()), None);
+            let result = JsFuture::from(promise).await.unwrap();
+            assert!(get(&result, "diff").is_object());
+            assert_eq!(*phases.borrow(), vec!["parse_first", "parse_second", "compare", "sql", "verify", "verify", "verify", "done"]);
+        }
+
+        #[wasm_bindgen_test]
+        async fn test_abort_rejects() {
+            let json = super::columns(3, "int(11)");
+            let signal = web_sys::AbortSignal::abort();
+            let error = JsFuture::from(compare_schemas_async(json.clone(), json, None, Some(signal))).await.unwrap_err();
+            assert_eq!(get(&error, "name"), "DbDiffError");
+            assert_eq!(get(&error, "kind"), "aborted");
+
+            let json = include_str!("../sample_index1.json").to_string();
+            let signal = web_sys::AbortSignal::abort();
+            let error = JsFuture::from(compare_indexs_async(json.clone(), json, None, Some(signal))).await.unwrap_err();
+            assert_eq!(get(&error, "kind"), "aborted");
+        }
+
+        #[wasm_bindgen_test]
+        async fn test_index_comparison_resolves() {
+            let json = include_str!("../sample_index1.json").to_string();
+            let error = JsFuture::from(compare_indexs_async(json.clone(), "[1]".to_string(), None, None)).await.unwrap_err();
+            assert_eq!(get(&error, "kind"), "second_input");
+            let result = JsFuture::from(compare_indexs_async(json.clone(), json, None, None)).await.unwrap();
+            assert!(get(&result, "diff").is_object());
+        }
+    }
+}
@@ -0,0 +1,93 @@
+// The JsValue exports only run on wasm32: wasm-pack test --node
+#[cfg(test)]
+mod js_value_tests {
+    use db_diff::js::TYPESCRIPT_DEFINITIONS;
+    use db_diff::output::output_json_schema;
+
+    // Every type and field of the JSON Schema has to be declared in the TypeScript definitions
+    #[test]
+    fn test_typescript_definitions_match_json_schema() {
+        let schema: serde_json::Value = serde_json::from_str(&output_json_schema()).unwrap();
+
+        for (name, definition) in schema["definitions"].as_object().unwrap() {
+            // String enums are declared as a union of their values
+            if let Some(values) = definition["enum"].as_array() {
+                let start = TYPESCRIPT_DEFINITIONS
+                    .find(&format!("export type {} = ", name))
+                    .unwrap_or_else(|| panic!("missing type {}", name));
+                let end = start + TYPESCRIPT_DEFINITIONS[start..].find(';').unwrap();
+                let declared = &TYPESCRIPT_DEFINITIONS[start..end];
+                for value in values {
+                    assert!(declared.contains(&format!("\"{}\"", value.as_str().unwrap())), "{} misses {}", name, value);
+                }
+                assert_eq!(declared.matches(" | ").count() + 1, values.len(), "{} declares extra values", name);
+                continue;
+            }
+
+            let start = TYPESCRIPT_DEFINITIONS
+                .find(&format!("export interface {} {{", name))
+                .unwrap_or_else(|| panic!("missing interface {}", name));
+            let end = start + TYPESCRIPT_DEFINITIONS[start..].find("\n}").unwrap();
+            let interface = &TYPESCRIPT_DEFINITIONS[start..end];
+
+            let properties = definition["properties"].as_object().unwrap();
+            for property in properties.keys() {
+                assert!(interface.contains(&format!("\n    {}: ", property)), "{}.{} is not declared", name, property);
+            }
+            assert_eq!(interface.matches(";\n").count() + 1, properties.len(), "{} declares extra fields", name);
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    mod wasm {
+        use wasm_bindgen::{JsCast, JsValue};
+        use wasm_bindgen_test::wasm_bindgen_test;
+        use db_diff::ColumnInfo;
+        use db_diff::js::{compare_indexs_value, compare_schemas_value};
+        use db_diff::output::FORMAT_VERSION;
+
+        fn get(value: &JsValue, key: &str) -> JsValue {
+            js_sys::Reflect::get(value, &JsValue::from_str(key)).unwrap()
+        }
+
+        #[wasm_bindgen_test]
+        fn test_compare_schemas_value_returns_object() {
+            let column = |table: &str| {
+                ColumnInfo::builder(
+                    "shop".to_string(),
+                    table.to_string(),
+                    "id".to_string(),
+                    "int".to_string(),
+                    "int(11)".to_string(),
+                    "NO".to_string(),
+                )
+            };
+            let json1 = serde_json::to_string(&vec![column("orders"), column("legacy")]).unwrap();
+            let json2 = serde_json::to_string(&vec![column("orders")]).unwrap();
+
+            let result = compare_schemas_value(&json1, &json2).unwrap();
+            assert_eq!(get(&result, "format_version").as_f64(), Some(FORMAT_VERSION as f64));
+
+            let diff = get(&result, "diff");
+            let tables = js_sys::Array::from(&get(&diff, "tables_only_in_first"));
+            assert_eq!(tables.length(), 1);
+            assert_eq!(get(&tables.get(0), "table").as_string().as_deref(), Some("legacy"));
+
+            // Maps become plain objects and None becomes null, as with JSON.parse
+            let sql = get(&result, "sql");
+            assert!(!sql.is_instance_of::<js_sys::Map>());
+            assert!(get(&sql, "Drop table in Schema 1 (Schema 1 only)").is_string());
+        }
+
+        #[wasm_bindgen_test]
+        fn test_invalid_input_throws_typed_error() {
+            let error = compare_schemas_value("[]", "{not json").unwrap_err();
+            assert!(error.is_instance_of::<js_sys::Error>());
+            assert_eq!(get(&error, "name").as_string().as_deref(), Some("DbDiffError"));
+            assert_eq!(get(&error, "kind").as_string().as_deref(), Some("second_input"));
+
+            let error = compare_indexs_value("{", "[]").unwrap_err();
+            assert_eq!(get(&error, "kind").as_string().as_deref(), Some("first_input"));
+        }
+    }
+}
@@ -0,0 +1,155 @@
+mod common;
+
+#[cfg(test)]
+mod lint_tests {
+    use std::collections::HashMap;
+    use db_diff::{ColumnInfo, build_schema_map, compare_schemas};
+    use db_diff::index::{IndexInfo, build_index_map};
+    use db_diff::lint::{LintConfig, LintContext, LintRule, LintViolation, Linter, Severity, lint_schema};
+    use super::common::{column, index};
+
+    fn sample_columns() -> Vec<ColumnInfo> {
+        vec![
+            column("orders", "id", "int(11)").set_column_comment("documented".to_string()),
+            column("orders", "customer_id", "int(11)").set_column_comment("documented".to_string()),
+            column("orders", "price", "float").set_column_comment("documented".to_string()),
+            column("orders", "code", "varchar").set_column_comment("documented".to_string())
+                .set_collation_name("utf8_general_ci".to_string())
+                .set_character_set_name("utf8".to_string()),
+            column("orders", "note", "varchar(20)").set_column_comment("documented".to_string())
+                .set_collation_name("utf8mb4_general_ci".to_string())
+                .set_character_set_name("utf8mb4".to_string())
+                .set_character_maximum_length(20),
+            column("logs", "message", "text").set_column_comment("documented".to_string()),
+        ]
+    }
+
+    fn sample_indexes() -> Vec<IndexInfo> {
+        vec![
+            index("orders", "PRIMARY", "id", 1).non_unique_opt(0).nullable("NO"),
+            index("orders", "uk_note", "note", 1).non_unique_opt(0),
+        ]
+    }
+
+    fn rule_ids(findings: &[db_diff::lint::LintFinding]) -> Vec<(&str, Option<&str>)> {
+        findings
+            .iter()
+            .map(|finding| (finding.rule_id.as_str(), finding.object.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn test_builtin_rules() {
+        let schema_map = build_schema_map(sample_columns());
+        let index_map = build_index_map(sample_indexes());
+        let context = LintContext { schema_map: &schema_map, index_map: &index_map };
+
+        let findings = Linter::new().run(&context);
+        let found = rule_ids(&findings);
+
+        assert!(found.contains(&("missing-primary-key", None)));
+        assert!(found.contains(&("varchar-without-length", Some("code"))));
+        assert!(found.contains(&("mixed-collations", None)));
+        assert!(found.contains(&("nullable-unique-index", Some("uk_note"))));
+        assert!(found.contains(&("float-for-money", Some("price"))));
+        assert!(found.contains(&("unindexed-id-column", Some("customer_id"))));
+        assert!(found.contains(&("utf8mb3-charset", Some("code"))));
+        assert!(!found.contains(&("missing-comment", Some("id"))));
+
+        let missing_pk = findings.iter().find(|finding| finding.rule_id == "missing-primary-key").unwrap();
+        assert_eq!(missing_pk.table_name, "logs");
+        assert_eq!(missing_pk.severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_index_rules_are_skipped_without_index_data() {
+        let schema_map = build_schema_map(sample_columns());
+        let index_map = HashMap::new();
+        let context = LintContext { schema_map: &schema_map, index_map: &index_map };
+
+        let findings = Linter::new().run(&context);
+        assert!(findings.iter().all(|finding| finding.rule_id != "missing-primary-key"));
+        assert!(findings.iter().all(|finding| finding.rule_id != "unindexed-id-column"));
+    }
+
+    #[test]
+    fn test_config_overrides_severity_and_disables_rules() {
+        let schema_map = build_schema_map(sample_columns());
+        let index_map = build_index_map(sample_indexes());
+        let context = LintContext { schema_map: &schema_map, index_map: &index_map };
+
+        let config: LintConfig = serde_json::from_str(
+            r#"{"severity": {"float-for-money": "error"}, "disabled": ["utf8mb3-charset"]}"#
+        ).unwrap();
+        let findings = Linter::new().with_config(config).run(&context);
+
+        let money = findings.iter().find(|finding| finding.rule_id == "float-for-money").unwrap();
+        assert_eq!(money.severity, Severity::Error);
+        assert!(findings.iter().all(|finding| finding.rule_id != "utf8mb3-charset"));
+    }
+
+    struct NoLogsTable;
+
+    impl LintRule for NoLogsTable {
+        fn id(&self) -> &'static str {
+            "no-logs-table"
+        }
+
+        fn description(&self) -> &'static str {
+            "Log tables belong in the log store"
+        }
+
+        fn default_severity(&self) -> Severity {
+            Severity::Info
+        }
+
+        fn check(&self, context: &LintContext) -> Vec<LintViolation> {
+            context
+                .columns()
+                .filter(|column| column.table_name == "logs")
+                .take(1)
+                .map(|column| LintViolation {
+                    table_schema: column.table_schema.clone(),
+                    table_name: column.table_name.clone(),
+                    object: None,
+                    message: "logs table found".to_string(),
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_custom_rule() {
+        let schema_map = build_schema_map(sample_columns());
+        let index_map = build_index_map(sample_indexes());
+        let context = LintContext { schema_map: &schema_map, index_map: &index_map };
+
+        let findings = Linter::empty().with_rule(Box::new(NoLogsTable)).run(&context);
+        assert_eq!(rule_ids(&findings), vec![("no-logs-table", None)]);
+    }
+
+    #[test]
+    fn test_lint_schema_json() {
+        let columns = serde_json::to_string(&sample_columns()).unwrap();
+        let indexes = serde_json::to_string(&sample_indexes()).unwrap();
+
+        let result: serde_json::Value = serde_json::from_str(&lint_schema(&columns, &indexes, "")).unwrap();
+        let findings = result["findings"].as_array().unwrap();
+        assert!(findings.iter().any(|finding| finding["rule_id"] == "float-for-money" && finding["severity"] == "warning"));
+
+        let error = lint_schema(&columns, &indexes, "{not json");
+        assert!(error.starts_with("Error parsing lint config:"));
+    }
+
+    #[test]
+    fn test_collation_is_not_a_column_difference() {
+        let first = vec![column("orders", "note", "varchar(10)").set_column_comment("documented".to_string()).set_collation_name("utf8mb4_general_ci".to_string())];
+        let second = vec![column("orders", "note", "varchar(10)").set_column_comment("documented".to_string()).set_collation_name("utf8mb4_bin".to_string())];
+        let json1 = serde_json::to_string(&first).unwrap();
+        let json2 = serde_json::to_string(&second).unwrap();
+
+        let result: serde_json::Value = serde_json::from_str(&compare_schemas(&json1, &json2)).unwrap();
+        assert_eq!(result["diff"]["columns_with_different_definitions"].as_array().unwrap().len(), 0);
+        assert_eq!(result["sql"].as_object().unwrap().len(), 0);
+    }
+}
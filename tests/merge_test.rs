@@ -0,0 +1,134 @@
+mod common;
+
+#[cfg(test)]
+mod merge_tests {
+    use db_diff::{build_schema_map, compare_schema_maps};
+    use db_diff::index::build_index_map;
+    use db_diff::merge::{MergeOrigin, merge_schema_maps, merge_index_maps, merge_schemas};
+    use super::common::{column, index, nullable};
+
+    #[test]
+    fn test_merge_classifies_column_changes() {
+        let base = vec![
+            column("users", "id", "int(11)"),
+            nullable(column("users", "email", "varchar(100)")),
+            nullable(column("users", "name", "varchar(50)")),
+        ];
+        let ours = vec![
+            column("users", "id", "int(11)"),
+            nullable(column("users", "email", "varchar(255)")),
+            nullable(column("users", "name", "varchar(50)")),
+            column("users", "created_at", "datetime"),
+            column("users", "status", "tinyint(1)"),
+        ];
+        let theirs = vec![
+            column("users", "id", "int(11)"),
+            column("users", "email", "varchar(320)"),
+            column("users", "status", "tinyint(1)"),
+        ];
+
+        let merge = merge_schema_maps(
+            &build_schema_map(base),
+            &build_schema_map(ours),
+            &build_schema_map(theirs),
+        );
+
+        let origin_of = |name: &str| {
+            merge.changes.iter()
+                .find(|change| change.column_name == name)
+                .map(|change| change.origin)
+        };
+
+        assert_eq!(origin_of("email"), Some(MergeOrigin::Conflicting));
+        assert_eq!(origin_of("created_at"), Some(MergeOrigin::OnlyOurs));
+        assert_eq!(origin_of("name"), Some(MergeOrigin::OnlyTheirs));
+        assert_eq!(origin_of("status"), Some(MergeOrigin::SameOnBoth));
+        assert_eq!(origin_of("id"), None);
+
+        assert_eq!(merge.conflicts.len(), 1);
+        let conflict = &merge.conflicts[0];
+        assert_eq!(conflict.table_key, "`shop`.users");
+        assert_eq!(conflict.base.as_ref().unwrap().column_type, "varchar(100)");
+        assert_eq!(conflict.ours.as_ref().unwrap().column_type, "varchar(255)");
+        assert_eq!(conflict.theirs.as_ref().unwrap().column_type, "varchar(320)");
+
+        // Conflicting columns keep the ancestor definition, everything else is applied
+        let users = &merge.merged["`shop`.users"];
+        let names: Vec<&String> = users.keys().collect();
+        assert_eq!(names, vec!["id", "email", "created_at", "status"]);
+        assert_eq!(users["email"].column_type, "varchar(100)");
+    }
+
+    #[test]
+    fn test_merge_without_conflicts_matches_both_branches() {
+        let base = vec![column("orders", "id", "int(11)")];
+        let ours = vec![
+            column("orders", "id", "int(11)"),
+            column("orders", "total", "decimal(10,2)"),
+        ];
+        let theirs = vec![
+            column("orders", "id", "int(11)"),
+            column("invoices", "id", "int(11)"),
+        ];
+
+        let merge = merge_schema_maps(
+            &build_schema_map(base),
+            &build_schema_map(ours),
+            &build_schema_map(theirs),
+        );
+        assert!(merge.conflicts.is_empty());
+
+        let expected = build_schema_map(vec![
+            column("orders", "id", "int(11)"),
+            column("orders", "total", "decimal(10,2)"),
+            column("invoices", "id", "int(11)"),
+        ]);
+        let diff = compare_schema_maps(&merge.merged, &expected);
+        assert!(diff.tables_only_in_first.is_empty());
+        assert!(diff.tables_only_in_second.is_empty());
+        assert!(diff.columns_only_in_first.is_empty());
+        assert!(diff.columns_only_in_second.is_empty());
+        assert!(diff.columns_with_different_definitions.is_empty());
+    }
+
+    #[test]
+    fn test_merge_index_maps() {
+        let base = vec![index("users", "idx_email", "email", 1)];
+        let ours = vec![
+            index("users", "idx_email", "email", 1),
+            index("users", "idx_email", "deleted_at", 2),
+            index("users", "idx_name", "name", 1),
+        ];
+        let theirs = vec![
+            index("users", "idx_email", "email", 1),
+            index("users", "idx_email", "tenant_id", 2),
+            index("users", "idx_name", "name", 1),
+        ];
+
+        let merge = merge_index_maps(
+            &build_index_map(base),
+            &build_index_map(ours),
+            &build_index_map(theirs),
+        );
+
+        assert_eq!(merge.conflicts.len(), 1);
+        assert_eq!(merge.conflicts[0].index_name, "idx_email");
+        let name_change = merge.changes.iter().find(|change| change.index_name == "idx_name").unwrap();
+        assert_eq!(name_change.origin, MergeOrigin::SameOnBoth);
+
+        let users = &merge.merged["`shop`.users"];
+        assert_eq!(users["idx_email"].len(), 1);
+        assert!(users.contains_key("idx_name"));
+    }
+
+    #[test]
+    fn test_merge_schemas_json() {
+        let base = r#"[{"table_schema":"shop","table_name":"users","column_name":"id","data_type":"int","column_type":"int(11)","is_nullable":"NO","column_default":null,"extra":null,"column_comment":null,"character_maximum_length":null}]"#;
+        let ours = r#"[{"table_schema":"shop","table_name":"users","column_name":"id","data_type":"bigint","column_type":"bigint(20)","is_nullable":"NO","column_default":null,"extra":null,"column_comment":null,"character_maximum_length":null}]"#;
+
+        let result: serde_json::Value = serde_json::from_str(&merge_schemas(base, ours, base)).unwrap();
+        assert_eq!(result["changes"][0]["origin"], "only_ours");
+        assert_eq!(result["conflicts"].as_array().unwrap().len(), 0);
+        assert_eq!(result["merged"][0]["column_type"], "bigint(20)");
+    }
+}
@@ -0,0 +1,86 @@
+mod common;
+
+#[cfg(test)]
+mod migration_tests {
+    use db_diff::ColumnInfo;
+    use db_diff::full::compare_columns_and_indexes;
+    use db_diff::migration::{MigrationOptions, MigrationTool, generate_migration, migration_files};
+    use super::common::{column, index};
+
+    fn columns1() -> Vec<ColumnInfo> {
+        vec![column("orders", "id", "int(11)"), column("orders", "note", "varchar(20)")]
+    }
+
+    fn columns2() -> Vec<ColumnInfo> {
+        vec![
+            column("orders", "id", "int(11)"),
+            column("orders", "note", "varchar(50)"),
+            column("customers", "id", "int(11)"),
+        ]
+    }
+
+    fn files(options: MigrationOptions) -> Vec<(String, String)> {
+        let comparison = compare_columns_and_indexes(columns1(), columns2(), vec![], vec![index("orders", "idx_note", "note", 1).nullable("")]);
+        migration_files(&comparison, &options).unwrap().into_iter().map(|file| (file.path, file.contents)).collect()
+    }
+
+    #[test]
+    fn test_sql_file_layouts() {
+        let flyway = files(MigrationOptions::new(MigrationTool::Flyway).version("2.1").description("Widen note, add customers"));
+        let names: Vec<&str> = flyway.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(names, vec!["V2.1__Widen_note_add_customers.sql", "U2.1__Widen_note_add_customers.sql"]);
+        assert_eq!(
+            flyway[0].1,
+            "CREATE TABLE `shop`.customers (id int(11) NOT NULL);
+ALTER TABLE `shop`.orders MODIFY COLUMN note varchar(50) NOT NULL;
+CREATE INDEX `idx_note` ON `shop`.orders (note);
+"
+        );
+        assert!(flyway[1].1.starts_with("DROP INDEX `idx_note` ON `shop`.orders;\nDROP TABLE `shop`.customers;\n"));
+
+        for tool in [MigrationTool::GolangMigrate, MigrationTool::Sqlx] {
+            let names: Vec<String> = files(MigrationOptions::new(tool).version("20240501120000")).into_iter().map(|(path, _)| path).collect();
+            assert_eq!(names, vec!["20240501120000_schema_diff.up.sql", "20240501120000_schema_diff.down.sql"]);
+        }
+    }
+
+    #[test]
+    fn test_liquibase_changelogs() {
+        let xml = files(MigrationOptions::new(MigrationTool::Liquibase).version("7").author("dana").description("note & customers"));
+        assert_eq!(xml.len(), 1);
+        assert_eq!(xml[0].0, "7_note_customers.xml");
+        let xml = &xml[0].1;
+        assert!(xml.contains("<changeSet id=\"7-shop.customers\" author=\"dana\">\n    <comment>note &amp; customers</comment>"));
+        assert!(xml.contains("<rollback>\n      <sql>\n        DROP TABLE `shop`.customers;\n      </sql>\n    </rollback>"));
+        assert_eq!(xml.matches("<changeSet ").count(), 2);
+        assert!(xml.ends_with("</databaseChangeLog>\n"));
+
+        let yaml = files(MigrationOptions::new(MigrationTool::LiquibaseYaml).version("7"));
+        assert_eq!(yaml[0].0, "7_schema_diff.yaml");
+        assert!(yaml[0].1.starts_with("databaseChangeLog:\n  - changeSet:\n      id: \"7-shop.customers\"\n      author: \"db-diff\"\n"));
+        assert!(yaml[0].1.contains(
+            "      changes:
+        - sql:
+            sql: |-
+              ALTER TABLE `shop`.orders MODIFY COLUMN note varchar(50) NOT NULL;
+              CREATE INDEX `idx_note` ON `shop`.orders (note);
+      rollback:
+"
+        ));
+    }
+
+    #[test]
+    fn test_generate_migration() {
+        let columns1 = serde_json::to_string(&columns1()).unwrap();
+        let columns2 = serde_json::to_string(&columns2()).unwrap();
+        let output = generate_migration(&columns1, &columns2, "", "", r#"{"tool": "golang-migrate", "version": "3", "description": "customers"}"#);
+        let result: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(result["files"][0]["path"], "3_customers.up.sql");
+        assert_eq!(result["verification_errors"].as_array().map(Vec::len), Some(0));
+
+        assert_eq!(generate_migration(&columns1, &columns1, "", "", r#"{"tool": "sqlx"}"#), "No differences, there is nothing to migrate");
+        assert_eq!(generate_migration(&columns1, &columns2, "", "", r#"{"tool": "sqlx", "version": "1.2"}"#), "Invalid migration version: 1.2");
+        assert!(generate_migration(&columns1, &columns2, "", "", r#"{"tool": "rails"}"#).starts_with("Error parsing migration options"));
+        assert_eq!(MigrationTool::parse("liquibase-xml"), Ok(MigrationTool::Liquibase));
+    }
+}
@@ -0,0 +1,129 @@
+mod common;
+
+#[cfg(test)]
+mod model_tests {
+    use std::fs;
+    use db_diff::{create_column_info, compare_schemas};
+    use db_diff::index::compare_indexs;
+    use db_diff::model::{MigrationStatement, SchemaModel, parse_migration_script, verify_roundtrip, verify_index_roundtrip};
+    use super::common::{column, index};
+
+    #[test]
+    fn test_parse_generated_statements() {
+        let script = "DROP TABLE `shop`.old_users;\n\
+            -- CREATE TABLE `shop`.prices (id int NOT NULL,amount decimal(10,2) NOT NULL DEFAULT 0.00,kind enum('a','b') NULL,);\n\
+            ALTER TABLE `shop`.users ADD COLUMN age int(11) NULL;\n\
+            ALTER TABLE `shop`.users CHANGE COLUMN name full_name varchar(100) NOT NULL;\n\
+            CREATE UNIQUE INDEX `uk_email` ON `shop`.users (email);\n\
+            DROP INDEX `idx_name` ON `shop`.users;";
+
+        let statements = parse_migration_script(script).expect("script should parse");
+        assert_eq!(statements.len(), 6);
+        assert_eq!(statements[0], MigrationStatement::DropTable { table: "`shop`.old_users".to_string() });
+        assert_eq!(statements[1], MigrationStatement::CreateTable {
+            table: "`shop`.prices".to_string(),
+            columns: vec![
+                "id int NOT NULL".to_string(),
+                "amount decimal(10,2) NOT NULL DEFAULT 0.00".to_string(),
+                "kind enum('a','b') NULL".to_string(),
+            ],
+        });
+        assert_eq!(statements[3], MigrationStatement::ChangeColumn {
+            table: "`shop`.users".to_string(),
+            column: "name".to_string(),
+            definition: "full_name varchar(100) NOT NULL".to_string(),
+        });
+        assert_eq!(statements[4], MigrationStatement::CreateIndex {
+            table: "`shop`.users".to_string(),
+            index: "uk_email".to_string(),
+            unique: true,
+            columns: vec!["email".to_string()],
+        });
+    }
+
+    #[test]
+    fn test_apply_rejects_invalid_statements() {
+        let mut model = SchemaModel::from_columns(&[column("users", "id", "int(11)")]);
+
+        assert!(model.apply(&MigrationStatement::DropColumn {
+            table: "`shop`.users".to_string(),
+            column: "missing".to_string(),
+        }).is_err());
+        assert!(model.apply(&MigrationStatement::AddColumn {
+            table: "`shop`.users".to_string(),
+            definition: "id int NOT NULL".to_string(),
+        }).is_err());
+        assert!(model.apply(&MigrationStatement::DropTable { table: "`shop`.orders".to_string() }).is_err());
+    }
+
+    #[test]
+    fn test_apply_change_column_renames_index_columns() {
+        let mut model = SchemaModel::from_columns(&[column("users", "id", "int(11)"), column("users", "name", "varchar(50)")])
+            .with_indexes(&[index("users", "idx_name", "name", 1)]);
+
+        model.apply(&MigrationStatement::ChangeColumn {
+            table: "`shop`.users".to_string(),
+            column: "name".to_string(),
+            definition: "full_name varchar(50) NOT NULL".to_string(),
+        }).unwrap();
+
+        let users = &model.tables["`shop`.users"];
+        let names: Vec<&String> = users.columns.keys().collect();
+        assert_eq!(names, vec!["id", "full_name"]);
+        assert_eq!(users.indexes["idx_name"].columns, vec!["full_name".to_string()]);
+    }
+
+    #[test]
+    fn test_verify_roundtrip() {
+        let columns1 = vec![
+            column("users", "id", "int(11)"),
+            column("users", "name", "varchar(50)"),
+            column("old_users", "id", "int(11)"),
+        ];
+        let columns2 = vec![
+            column("users", "id", "bigint(20)"),
+            column("users", "email", "varchar(255)"),
+            column("prices", "amount", "decimal(10,2)"),
+        ];
+
+        assert_eq!(verify_roundtrip(columns1, columns2), Ok(()));
+    }
+
+    #[test]
+    fn test_verify_index_roundtrip() {
+        let indexes1 = vec![index("users", "PRIMARY", "id", 1).non_unique_opt(0), index("users", "idx_name", "name", 1)];
+        let indexes2 = vec![index("users", "PRIMARY", "id", 1).non_unique_opt(0), index("users", "idx_name", "name", 1).non_unique_opt(0)];
+
+        assert_eq!(verify_index_roundtrip(indexes1, indexes2), Ok(()));
+    }
+
+    #[test]
+    fn test_verify_roundtrip_with_non_ascii_names() {
+        // Unicode uppercasing changes the byte length of `ı`, keyword search must not
+        let columns1 = vec![column("kullanıcı", "id", "int(11)"), column("kullanıcı", "ıd", "int(11)")];
+        let columns2 = vec![column("kullanıcı", "id", "int(11)"), column("kullanıcı", "adı", "varchar(50)")];
+        assert_eq!(verify_roundtrip(columns1, columns2), Ok(()));
+
+        let indexes1 = vec![index("kullanıcı", "ıı", "ıd", 1)];
+        let indexes2 = vec![index("kullanıcı", "ıx_adı", "adı", 1).non_unique_opt(0)];
+        assert_eq!(verify_index_roundtrip(indexes1.clone(), indexes2.clone()), Ok(()));
+
+        let json1 = serde_json::to_string(&indexes1).unwrap();
+        let json2 = serde_json::to_string(&indexes2).unwrap();
+        let result: serde_json::Value = serde_json::from_str(&compare_indexs(&json1, &json2)).unwrap();
+        assert_eq!(result["verification_errors"].as_array().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_verify_roundtrip_on_sample_files() {
+        let schema1 = fs::read_to_string("sample_schema7.json").expect("Failed to read sample_schema7.json");
+        let schema2 = fs::read_to_string("sample_schema8.json").expect("Failed to read sample_schema8.json");
+
+        let columns1 = create_column_info(&schema1).unwrap();
+        let columns2 = create_column_info(&schema2).unwrap();
+        assert_eq!(verify_roundtrip(columns1, columns2), Ok(()));
+
+        let result: serde_json::Value = serde_json::from_str(&compare_schemas(&schema1, &schema2)).unwrap();
+        assert_eq!(result["verification_errors"].as_array().unwrap().len(), 0);
+    }
+}
@@ -0,0 +1,112 @@
+mod common;
+
+#[cfg(test)]
+mod naming_tests {
+    use db_diff::build_schema_map;
+    use db_diff::index::{build_index_map, compare_index_maps, generate_sql_index_diff};
+    use db_diff::lint::{LintContext, LintRule, lint_schema};
+    use db_diff::model::verify_index_roundtrip;
+    use db_diff::naming::{NamingConfig, NamingConvention, check_naming};
+    use super::common::{column, index};
+
+    #[test]
+    fn test_default_naming_convention() {
+        let schema_map = build_schema_map(vec![
+            column("orders", "id", "int(11)"),
+            column("orders", "customerId", "int(11)"),
+            column("OrderItems", "id", "int(11)"),
+        ]);
+        let index_map = build_index_map(vec![
+            index("orders", "PRIMARY", "id", 1).non_unique_opt(0),
+            index("orders", "idx_orders_customerId_id", "customerId", 1),
+            index("orders", "idx_orders_customerId_id", "id", 2),
+            index("orders", "customer", "customerId", 1),
+            index("orders", "email_unique", "id", 1).non_unique_opt(0),
+        ]);
+        let context = LintContext { schema_map: &schema_map, index_map: &index_map };
+
+        let rule = NamingConvention::new(&NamingConfig::default()).unwrap();
+        let objects: Vec<(String, Option<String>)> = rule
+            .check(&context)
+            .into_iter()
+            .map(|violation| (violation.table_name, violation.object))
+            .collect();
+
+        assert_eq!(objects, vec![
+            ("OrderItems".to_string(), None),
+            ("orders".to_string(), Some("customerId".to_string())),
+            ("orders".to_string(), Some("customer".to_string())),
+            ("orders".to_string(), Some("email_unique".to_string())),
+        ]);
+    }
+
+    #[test]
+    fn test_custom_patterns_and_invalid_regex() {
+        let config: NamingConfig = serde_json::from_str(r#"{"table": "^tbl_", "column": null, "index": null, "unique_index": "^ux_{table}_"}"#).unwrap();
+        assert_eq!(config.column, None);
+
+        let columns = serde_json::to_string(&vec![column("tbl_orders", "ID", "int(11)"), column("orders", "id", "int(11)")]).unwrap();
+        let indexes = serde_json::to_string(&vec![index("tbl_orders", "ux_tbl_orders_id", "ID", 1).non_unique_opt(0)]).unwrap();
+        let naming = serde_json::to_string(&config).unwrap();
+
+        let result: serde_json::Value = serde_json::from_str(&check_naming(&columns, &indexes, &naming)).unwrap();
+        let findings = result["findings"].as_array().unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0]["table_name"], "orders");
+        assert_eq!(findings[0]["rule_id"], "naming-convention");
+
+        let error = check_naming(&columns, &indexes, r#"{"table": "(unclosed"}"#);
+        assert!(error.starts_with("Error parsing naming pattern"));
+    }
+
+    #[test]
+    fn test_naming_through_lint_config() {
+        let columns = serde_json::to_string(&vec![column("Orders", "id", "int(11)")]).unwrap();
+
+        let without_naming: serde_json::Value = serde_json::from_str(&lint_schema(&columns, "", "")).unwrap();
+        assert!(without_naming["findings"].as_array().unwrap().iter().all(|finding| finding["rule_id"] != "naming-convention"));
+
+        let with_naming: serde_json::Value = serde_json::from_str(&lint_schema(&columns, "", r#"{"naming": {}}"#)).unwrap();
+        assert!(with_naming["findings"].as_array().unwrap().iter().any(|finding| finding["rule_id"] == "naming-convention"));
+    }
+
+    #[test]
+    fn test_index_rename_detection() {
+        let indexes1 = vec![
+            index("orders", "PRIMARY", "id", 1).non_unique_opt(0),
+            index("orders", "customer_idx", "customer_id", 1),
+            index("orders", "customer_idx", "created_at", 2),
+            index("orders", "status", "status", 1),
+        ];
+        let indexes2 = vec![
+            index("orders", "PRIMARY", "id", 1).non_unique_opt(0),
+            index("orders", "idx_orders_customer_id_created_at", "customer_id", 1),
+            index("orders", "idx_orders_customer_id_created_at", "created_at", 2),
+            index("orders", "idx_orders_status", "status", 1).non_unique_opt(0),
+        ];
+
+        let diff = compare_index_maps(&build_index_map(indexes1.clone()), &build_index_map(indexes2.clone()));
+
+        assert_eq!(diff.indexes_renamed.len(), 1);
+        assert_eq!(diff.indexes_renamed[0].old_index_name, "customer_idx");
+        assert_eq!(diff.indexes_renamed[0].new_index_name, "idx_orders_customer_id_created_at");
+        // A change of uniqueness is not a rename
+        assert_eq!(diff.indexes_only_in_first.len(), 1);
+        assert_eq!(diff.indexes_only_in_first[0].index_name, "status");
+        assert_eq!(diff.indexes_only_in_second.len(), 1);
+
+        let sql_statements = generate_sql_index_diff(&diff);
+        assert_eq!(
+            sql_statements.get("Rename index in Schema 1").unwrap(),
+            "ALTER TABLE `shop`.orders RENAME INDEX `customer_idx` TO `idx_orders_customer_id_created_at`;"
+        );
+        assert_eq!(
+            sql_statements.get("Rename index in Schema 2").unwrap(),
+            "ALTER TABLE `shop`.orders RENAME INDEX `idx_orders_customer_id_created_at` TO `customer_idx`;"
+        );
+
+        let single_column1 = vec![index("orders", "status", "status", 1)];
+        let single_column2 = vec![index("orders", "idx_orders_status", "status", 1)];
+        assert_eq!(verify_index_roundtrip(single_column1, single_column2), Ok(()));
+    }
+}
@@ -0,0 +1,110 @@
+mod common;
+
+#[cfg(test)]
+mod online_tests {
+    use db_diff::ColumnInfo;
+    use db_diff::full::compare_columns_and_indexes;
+    use db_diff::index::IndexInfo;
+    use db_diff::online::{ChangeMethod, OnlineOptions, OnlineTool, online_schema_change, plan_online_changes};
+    use super::common::{column, index};
+
+    fn columns1() -> Vec<ColumnInfo> {
+        vec![
+            column("orders", "id", "int(11)"),
+            column("orders", "note", "varchar(20)"),
+            column("orders", "legacy", "tinyint(1)"),
+            column("countries", "code", "char(2)"),
+            column("countries", "name", "varchar(50)"),
+        ]
+    }
+
+    fn columns2() -> Vec<ColumnInfo> {
+        vec![
+            column("orders", "id", "int(11)"),
+            column("orders", "note", "varchar(50)"),
+            column("orders", "status", "varchar(16)"),
+            column("countries", "code", "char(2)"),
+            column("countries", "name", "varchar(80)"),
+            column("audit", "id", "bigint(20)"),
+        ]
+    }
+
+    fn indexes1() -> Vec<IndexInfo> {
+        vec![
+            index("orders", "PRIMARY", "id", 1).nullable("").non_unique_opt(0).cardinality_opt(2_500_000),
+            index("orders", "idx_legacy", "legacy", 1).nullable("").cardinality_opt(2),
+            index("countries", "PRIMARY", "code", 1).nullable("").non_unique_opt(0).cardinality_opt(250),
+        ]
+    }
+
+    fn indexes2() -> Vec<IndexInfo> {
+        vec![
+            index("orders", "PRIMARY", "id", 1).nullable("").non_unique_opt(0).cardinality_opt(2_500_000),
+            index("orders", "idx_status", "status", 1).nullable("").cardinality_opt(5),
+            index("countries", "PRIMARY", "code", 1).nullable("").non_unique_opt(0).cardinality_opt(250),
+        ]
+    }
+
+    #[test]
+    fn test_one_alter_per_table() {
+        let comparison = compare_columns_and_indexes(columns1(), columns2(), indexes1(), indexes2());
+        let plan = plan_online_changes(&comparison, &indexes1(), &OnlineOptions::new(OnlineTool::GhOst).min_rows(100_000));
+        let tables: Vec<_> = plan.tables.iter().map(|table| (table.table.as_str(), table.method, table.reason.as_str())).collect();
+        assert_eq!(
+            tables,
+            vec![
+                ("audit", ChangeMethod::Plain, "new table"),
+                ("countries", ChangeMethod::Plain, "estimated 250 rows, below 100000"),
+                ("orders", ChangeMethod::Online, "estimated 2500000 rows"),
+            ]
+        );
+
+        let orders = &plan.tables[2];
+        assert_eq!(
+            orders.alter,
+            "DROP INDEX `idx_legacy`, DROP COLUMN legacy, ADD COLUMN status varchar(16) NOT NULL, MODIFY COLUMN note varchar(50) NOT NULL, ADD INDEX `idx_status` (status)"
+        );
+        assert_eq!(
+            orders.command,
+            "gh-ost --database=shop --table=orders --alter='DROP INDEX `idx_legacy`, DROP COLUMN legacy, ADD COLUMN status varchar(16) NOT NULL, MODIFY COLUMN note varchar(50) NOT NULL, ADD INDEX `idx_status` (status)' --execute"
+        );
+        assert_eq!(plan.tables[1].command, "ALTER TABLE `shop`.countries MODIFY COLUMN name varchar(80) NOT NULL;");
+        assert_eq!(plan.tables[0].command, "CREATE TABLE `shop`.audit (id bigint(20) NOT NULL);");
+
+        let script = plan.to_script();
+        assert!(script.starts_with("#!/bin/sh\nset -e\n"));
+        assert!(script.contains("\n# `shop`.countries: estimated 250 rows, below 100000\nmysql -e 'ALTER TABLE `shop`.countries MODIFY COLUMN name varchar(80) NOT NULL;'\n"));
+    }
+
+    #[test]
+    fn test_pt_online_schema_change() {
+        let comparison = compare_columns_and_indexes(columns1(), columns2(), vec![], vec![]);
+        let options = OnlineOptions::new(OnlineTool::PtOnlineSchemaChange).min_rows(1000).tool_options("--max-load Threads_running=25");
+        let plan = plan_online_changes(&comparison, &[], &options);
+        // Without cardinalities every changed table is copied online
+        let countries = plan.tables.iter().find(|table| table.table == "countries").unwrap();
+        assert_eq!(countries.reason, "row count unknown");
+        assert_eq!(
+            countries.command,
+            "pt-online-schema-change --alter 'MODIFY COLUMN name varchar(80) NOT NULL' D=shop,t=countries --max-load Threads_running=25 --execute"
+        );
+    }
+
+    #[test]
+    fn test_online_schema_change() {
+        let json = |value: &[ColumnInfo]| serde_json::to_string(value).unwrap();
+        let enum_note = vec![column("orders", "id", "int(11)"), column("orders", "note", "enum('new','paid')")];
+        let output = online_schema_change(&json(&columns1()[..2]), &json(&enum_note), "", "", r#"{"tool": "pt-osc"}"#);
+        let result: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let orders = result["tables"].as_array().unwrap().iter().find(|table| table["table"] == "orders").unwrap();
+        assert_eq!(orders["method"], "online");
+        // Single quotes of the definition survive the shell quoting
+        assert_eq!(
+            orders["command"],
+            "pt-online-schema-change --alter 'MODIFY COLUMN note enum('\\''new'\\'','\\''paid'\\'') NOT NULL' D=shop,t=orders --execute"
+        );
+
+        assert!(online_schema_change("", "", "", "", r#"{"tool": "lhm"}"#).starts_with("Error parsing online schema change options"));
+        assert_eq!(OnlineTool::parse("pt-online-schema-change"), Ok(OnlineTool::PtOnlineSchemaChange));
+    }
+}
@@ -0,0 +1,70 @@
+mod common;
+
+#[cfg(test)]
+mod output_format_tests {
+    use std::fs;
+    use db_diff::compare_schemas;
+    use db_diff::index::{IndexInfo, compare_indexs};
+    use db_diff::output::{FORMAT_VERSION, SchemaComparison, output_json_schema};
+    use super::common::column;
+
+    #[test]
+    fn test_compare_schemas_output_contract() {
+        let json1 = serde_json::to_string(&vec![column("orders", "id", "int(11)"), column("legacy", "id", "int(11)")]).unwrap();
+        let json2 = serde_json::to_string(&vec![column("orders", "id", "int(11)")]).unwrap();
+
+        let result: serde_json::Value = serde_json::from_str(&compare_schemas(&json1, &json2)).unwrap();
+        assert_eq!(result["format_version"], FORMAT_VERSION);
+        assert_eq!(
+            result["diff"]["tables_only_in_first"][0],
+            serde_json::json!({"schema": "shop", "table": "legacy", "definition": "id int(11) NOT NULL,"})
+        );
+
+        // The output deserializes back into the documented types
+        let comparison: SchemaComparison = serde_json::from_value(result).unwrap();
+        assert_eq!(comparison.diff.tables_only_in_first[0].key(), "`shop`.legacy");
+    }
+
+    #[test]
+    fn test_compare_indexs_output_contract() {
+        let index = |name: &str, non_unique: u8| {
+            IndexInfo::builder()
+                .table_schema("shop")
+                .table_name("orders")
+                .index_name(name)
+                .column_name("id")
+                .seq_in_index(1)
+                .nullable("NO")
+                .index_type("BTREE")
+                .non_unique_opt(non_unique)
+                .build()
+                .unwrap()
+        };
+        let json1 = serde_json::to_string(&vec![index("idx_id", 1)]).unwrap();
+        let json2 = serde_json::to_string(&vec![index("idx_id", 0)]).unwrap();
+
+        let result: serde_json::Value = serde_json::from_str(&compare_indexs(&json1, &json2)).unwrap();
+        assert_eq!(result["format_version"], FORMAT_VERSION);
+        let difference = &result["diff"]["indexes_with_different_definitions"][0];
+        assert_eq!(difference["table_schema"], "shop");
+        assert_eq!(difference["table_name"], "orders");
+    }
+
+    #[test]
+    fn test_json_schema_is_up_to_date() {
+        let schema = output_json_schema();
+        let parsed: serde_json::Value = serde_json::from_str(&schema).unwrap();
+        let definitions = parsed["definitions"].as_object().unwrap();
+        assert!(definitions.contains_key("SchemaComparison"));
+        assert!(definitions.contains_key("IndexComparison"));
+        assert_eq!(definitions["TableDefinition"]["required"], serde_json::json!(["definition", "schema", "table"]));
+
+        // The checked-in document is the published contract, regenerate it with
+        // UPDATE_OUTPUT_SCHEMA=1 cargo test --test output_format_test
+        if std::env::var("UPDATE_OUTPUT_SCHEMA").is_ok() {
+            fs::write("output_schema.json", format!("{}\n", schema)).unwrap();
+        }
+        let checked_in = fs::read_to_string("output_schema.json").expect("Failed to read output_schema.json");
+        assert_eq!(checked_in.trim_end(), schema, "output_schema.json is out of date");
+    }
+}
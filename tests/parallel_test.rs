@@ -0,0 +1,57 @@
+mod common;
+
+#[cfg(test)]
+mod parallel_tests {
+    use db_diff::{build_schema_map, compare_schema_maps};
+    use db_diff::index::{build_index_map, compare_index_maps};
+    use super::common::{column, index};
+
+    // Run with and without `--features parallel`, the output must not depend on the build
+
+    #[test]
+    fn test_schema_diff_is_in_table_order() {
+        let mut columns1 = Vec::new();
+        let mut columns2 = Vec::new();
+        for table in (0..300).rev() {
+            let name = format!("table_{:03}", table);
+            columns1.push(column(&name, "id", "int(11)"));
+            columns2.push(column(&name, "id", if table % 3 == 0 { "bigint(20)" } else { "int(11)" }));
+            if table % 50 == 0 {
+                columns1.push(column(&format!("old_{:03}", table), "id", "int(11)"));
+                columns2.push(column(&name, "note", "int(11)"));
+            }
+        }
+
+        let diff = compare_schema_maps(&build_schema_map(columns1.clone()), &build_schema_map(columns2.clone()));
+        let modified: Vec<&str> = diff.columns_with_different_definitions.iter().map(|difference| difference.table_name.as_str()).collect();
+        assert_eq!(modified.len(), 100);
+        assert!(modified.windows(2).all(|pair| pair[0] < pair[1]));
+        let dropped: Vec<&str> = diff.tables_only_in_first.iter().map(|table| table.table.as_str()).collect();
+        assert_eq!(dropped, vec!["old_000", "old_050", "old_100", "old_150", "old_200", "old_250"]);
+        assert_eq!(diff.columns_only_in_second.first().map(|column| column.table_name.as_str()), Some("table_000"));
+
+        // Same input, same serialized output
+        let again = compare_schema_maps(&build_schema_map(columns1), &build_schema_map(columns2));
+        assert_eq!(serde_json::to_string(&diff).unwrap(), serde_json::to_string(&again).unwrap());
+    }
+
+    #[test]
+    fn test_index_diff_is_in_table_order() {
+        let mut indexes1 = Vec::new();
+        let mut indexes2 = Vec::new();
+        for table in (0..200).rev() {
+            let name = format!("table_{:03}", table);
+            indexes1.push(index(&name, "idx_a", "a", 1));
+            indexes2.push(index(&name, if table % 2 == 0 { "idx_a_renamed" } else { "idx_a" }, "a", 1));
+            indexes2.push(index(&name, "idx_b", "b", 1));
+        }
+
+        let diff = compare_index_maps(&build_index_map(indexes1), &build_index_map(indexes2));
+        let renamed: Vec<&str> = diff.indexes_renamed.iter().map(|rename| rename.table_name.as_str()).collect();
+        assert_eq!(renamed.len(), 100);
+        assert!(renamed.windows(2).all(|pair| pair[0] < pair[1]));
+        let added: Vec<&str> = diff.indexes_only_in_second.iter().map(|index| index.table_name.as_str()).collect();
+        assert_eq!(added.len(), 200);
+        assert!(added.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
@@ -0,0 +1,102 @@
+#[cfg(test)]
+mod phpmyadmin_tests {
+    use std::fs;
+    use db_diff::{ColumnInfo, create_column_info};
+    use db_diff::index::{IndexInfo, create_index_info};
+    use db_diff::phpmyadmin::{ExportInspection, inspect_export, parse_export};
+
+    fn column_row(table: &str, name: &str) -> String {
+        format!(
+            r#"{{"table_schema":"shop","table_name":"{}","column_name":"{}","data_type":"int","column_type":"int(11)","is_nullable":"NO","column_default":null,"extra":"","column_comment":"","character_maximum_length":null}}"#,
+            table, name
+        )
+    }
+
+    fn index_row(name: &str, column: &str) -> String {
+        format!(
+            r#"{{"table_schema":"shop","table_name":"orders","index_name":"{}","column_name":"{}","seq_in_index":"1","nullable":"","index_type":"BTREE","non_unique":"0"}}"#,
+            name, column
+        )
+    }
+
+    fn multi_table_export() -> String {
+        format!(
+            r#"[
+{{"type":"header","version":"5.2.1","comment":"Export to JSON plugin for PHPMyAdmin"}},
+{{"type":"database","name":"information_schema"}},
+{{"type":"table","name":"COLUMNS","database":"information_schema","data":[{},{}]}},
+{{"type":"table","name":"STATISTICS","database":"information_schema","data":[{}]}},
+{{"type":"table","name":"COLUMNS","database":"information_schema","data":[{}]}}
+]"#,
+            column_row("orders", "id"),
+            column_row("orders", "total"),
+            index_row("PRIMARY", "id"),
+            column_row("customers", "id"),
+        )
+    }
+
+    #[test]
+    fn test_every_table_block_is_merged() {
+        let json = multi_table_export();
+
+        let columns = create_column_info(&json).unwrap();
+        let names: Vec<(&str, &str)> = columns.iter().map(|column| (column.table_name.as_str(), column.column_name.as_str())).collect();
+        assert_eq!(names, vec![("orders", "id"), ("orders", "total"), ("customers", "id")]);
+
+        let indexes = create_index_info(&json).unwrap();
+        assert_eq!(indexes.len(), 1);
+        assert_eq!(indexes[0].index_name, "PRIMARY");
+
+        let export = parse_export::<ColumnInfo>(&json).unwrap();
+        assert_eq!(export.version.as_deref(), Some("5.2.1"));
+        let used: Vec<usize> = export.used_blocks().map(|block| block.position).collect();
+        assert_eq!(used, vec![2, 4]);
+        assert_eq!(export.blocks[1].skipped.as_deref(), Some("holds information_schema.STATISTICS rows"));
+    }
+
+    #[test]
+    fn test_export_without_header_uses_first_block() {
+        let json = format!(r#"[{{"type":"table","name":"COLUMNS","database":"information_schema","data":[{}]}}]"#, column_row("orders", "id"));
+        assert_eq!(create_column_info(&json).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_blocks_are_validated() {
+        let mismatch = format!(
+            r#"[{{"type":"database","name":"information_schema"}},{{"type":"table","name":"COLUMNS","database":"shop","data":[{}]}}]"#,
+            column_row("orders", "id")
+        );
+        assert_eq!(
+            create_column_info(&mismatch).unwrap_err(),
+            "Block 1: table COLUMNS belongs to database shop but follows database information_schema"
+        );
+
+        let unknown = r#"[{"type":"view","name":"v"}]"#;
+        assert_eq!(create_column_info(unknown).unwrap_err(), "Block 0: unknown block type view");
+
+        // A COLUMNS block has to contain column rows
+        let broken = format!(r#"[{{"type":"table","name":"COLUMNS","data":[{}]}}]"#, index_row("PRIMARY", "id"));
+        assert!(create_column_info(&broken).unwrap_err().starts_with("Block 0: missing field"));
+
+        // Rows of an unrelated table are skipped, and an export without usable blocks is an error
+        let unrelated = r#"[{"type":"header"},{"type":"table","name":"orders","data":[{"id":"1"}]}]"#;
+        assert_eq!(create_column_info(unrelated).unwrap_err(), "Cannot find data");
+        // A block exported from a query has no information_schema name but is used when its rows fit
+        let query_export = parse_export::<IndexInfo>(&multi_table_export().replace("STATISTICS", "index_query")).unwrap();
+        assert_eq!(query_export.used_blocks().map(|block| block.position).collect::<Vec<usize>>(), vec![3]);
+    }
+
+    #[test]
+    fn test_inspect_export() {
+        let inspection: ExportInspection = serde_json::from_str(&inspect_export(&multi_table_export())).unwrap();
+        assert_eq!(inspection.column_rows, 3);
+        assert_eq!(inspection.index_rows, 1);
+        assert_eq!(inspection.indexes.iter().filter(|block| block.skipped.is_none()).count(), 1);
+        assert!(inspection.column_error.is_none());
+
+        let json = fs::read_to_string("sample_index1.json").expect("Failed to read sample_index1.json");
+        let inspection: ExportInspection = serde_json::from_str(&inspect_export(&json)).unwrap();
+        assert_eq!(inspection.index_rows, create_index_info(&json).unwrap().len());
+        assert_eq!(inspection.column_error.as_deref(), Some("Cannot find data"));
+    }
+}
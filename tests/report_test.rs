@@ -0,0 +1,104 @@
+mod common;
+
+#[cfg(test)]
+mod report_tests {
+    use std::fs;
+    use db_diff::{build_schema_map, compare_schema_maps};
+    use db_diff::index::{build_index_map, compare_index_maps};
+    use db_diff::report::{DiffReport, ReportFormat, generate_report};
+    use super::common::{column, index};
+
+    fn sample_diffs() -> (db_diff::SchemaDiff, db_diff::index::IndexDiff) {
+        let columns1 = vec![
+            column("orders", "id", "int(11)"),
+            column("orders", "note", "varchar(20)"),
+            column("legacy", "id", "int(11)"),
+        ];
+        let columns2 = vec![
+            column("orders", "id", "int(11)"),
+            column("orders", "note", "varchar(50)"),
+            column("orders", "status", "varchar(10)"),
+        ];
+        let indexes1 = vec![index("orders", "PRIMARY", "id", 1).non_unique_opt(0)];
+        let indexes2 = vec![
+            index("orders", "PRIMARY", "id", 1).non_unique_opt(0),
+            index("orders", "idx_orders_status_note", "status", 1),
+            index("orders", "idx_orders_status_note", "note", 2),
+        ];
+
+        (
+            compare_schema_maps(&build_schema_map(columns1), &build_schema_map(columns2)),
+            compare_index_maps(&build_index_map(indexes1), &build_index_map(indexes2)),
+        )
+    }
+
+    #[test]
+    fn test_markdown_report() {
+        let (schema_diff, index_diff) = sample_diffs();
+        let report = DiffReport::new().with_schema_diff(&schema_diff).with_index_diff(&index_diff);
+
+        let summary = report.summary();
+        assert_eq!(summary.tables_only_in_first, 1);
+        assert_eq!(summary.columns_only_in_second, 1);
+        assert_eq!(summary.columns_with_different_definitions, 1);
+        // Two rows of the same index count once
+        assert_eq!(summary.indexes_only_in_second, 1);
+        assert_eq!(summary.total(), 4);
+
+        let markdown = report.to_markdown();
+        assert!(markdown.starts_with("# Schema diff report\n\n**4 difference(s)**"));
+        assert!(markdown.contains("| Columns with different definitions | 1 |"));
+        assert!(markdown.contains("## Tables only in Schema 1 (1)\n\n| Table |\n| --- |\n| `shop.legacy` |"));
+        assert!(markdown.contains("| `shop.orders` | `note` | `note varchar(20) NOT NULL` | `note varchar(50) NOT NULL` |"));
+        assert!(markdown.contains("| `shop.orders` | `idx_orders_status_note` | `BTREE (status, note)` |"));
+        assert!(markdown.contains("<summary>Schema 1 → Schema 2</summary>\n\n```sql\n"));
+        assert!(!markdown.contains("Renamed indexes"));
+    }
+
+    #[test]
+    fn test_html_report_is_self_contained_and_escaped() {
+        let (schema_diff, index_diff) = sample_diffs();
+        let html = DiffReport::new()
+            .title("Release <1.2>")
+            .with_schema_diff(&schema_diff)
+            .with_index_diff(&index_diff)
+            .render(ReportFormat::Html);
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<style>"));
+        assert!(!html.contains("<link"));
+        assert!(!html.contains("<script"));
+        assert!(html.contains("<h1>Release &lt;1.2&gt;</h1>"));
+        assert!(html.contains("<td><code>note varchar(50) NOT NULL</code></td>"));
+        assert!(html.contains("<pre><code>"));
+        assert!(html.trim_end().ends_with("</html>"));
+    }
+
+    #[test]
+    fn test_empty_report() {
+        let (schema_diff, _) = sample_diffs();
+        let same = compare_schema_maps(
+            &build_schema_map(vec![column("orders", "id", "int(11)")]),
+            &build_schema_map(vec![column("orders", "id", "int(11)")]),
+        );
+        assert_eq!(DiffReport::new().with_schema_diff(&same).to_markdown(), "# Schema diff report\n\nNo differences found.\n");
+        assert!(!DiffReport::new().with_schema_diff(&schema_diff).to_markdown().contains("Indexes"));
+    }
+
+    #[test]
+    fn test_generate_report_from_sample_files() {
+        let json1 = fs::read_to_string("sample_schema1.json").expect("Failed to read sample_schema1.json");
+        let json2 = fs::read_to_string("sample_schema2.json").expect("Failed to read sample_schema2.json");
+
+        let markdown = generate_report(&json1, &json2, "", "", "markdown");
+        assert!(markdown.starts_with("# Schema diff report"));
+        // Rendering is deterministic even though the diff comes from hash maps
+        assert_eq!(markdown, generate_report(&json1, &json2, "", "", "md"));
+
+        let html = generate_report(&json1, &json2, "", "", "html");
+        assert!(html.starts_with("<!DOCTYPE html>"));
+
+        assert_eq!(generate_report(&json1, &json2, "", "", "pdf"), "Unknown report format: pdf");
+        assert!(generate_report("{", &json2, "", "", "html").starts_with("Error parsing first JSON"));
+    }
+}
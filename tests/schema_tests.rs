@@ -1,6 +1,5 @@
 // use wasm_bindgen_test::*;
 use db_diff::{ColumnInfo, build_schema_map, compare_schema_maps};
-use serde_json;
 
 #[test]
 fn test_build_schema_map() {
@@ -183,10 +182,12 @@ fn test_compare_schema_maps_different_tables() {
     assert_eq!(diff.tables_only_in_first.len(), 1);
     assert_eq!(diff.tables_only_in_second.len(), 1);
     
-    assert_eq!(diff.tables_only_in_first[0].0, "`public`.users");
-    assert_eq!(diff.tables_only_in_second[0].0, "`public`.products");
-    assert_eq!(diff.tables_only_in_first[0].1, "id int(11) NOT NULL,");
-    assert_eq!(diff.tables_only_in_second[0].1, "id int(11) NOT NULL,");
+    assert_eq!(diff.tables_only_in_first[0].schema, "public");
+    assert_eq!(diff.tables_only_in_first[0].table, "users");
+    assert_eq!(diff.tables_only_in_first[0].key(), "`public`.users");
+    assert_eq!(diff.tables_only_in_second[0].key(), "`public`.products");
+    assert_eq!(diff.tables_only_in_first[0].definition, "id int(11) NOT NULL,");
+    assert_eq!(diff.tables_only_in_second[0].definition, "id int(11) NOT NULL,");
 
     // Should have no column differences
     assert_eq!(diff.columns_only_in_first.len(), 0);
@@ -287,12 +288,12 @@ fn test_character_maximum_length_parsing() {
 
 #[test]
 fn test_generate_sql_diff() {
-    use db_diff::{SchemaDiff, ColumnDifference, generate_sql_diff};
+    use db_diff::{SchemaDiff, ColumnDifference, TableDefinition, generate_sql_diff};
 
     // Create a test diff with multiple tables
     let diff = SchemaDiff {
-        tables_only_in_first: vec![("`public`.old_table".to_string(), "old_table".to_string())],
-        tables_only_in_second: vec![("`public`.new_table".to_string(), "new_table".to_string())],
+        tables_only_in_first: vec![TableDefinition { schema: "public".to_string(), table: "old_table".to_string(), definition: "old_table".to_string() }],
+        tables_only_in_second: vec![TableDefinition { schema: "public".to_string(), table: "new_table".to_string(), definition: "new_table".to_string() }],
         columns_only_in_first: vec![
             ColumnInfo::builder(
                 "public".to_string(),
@@ -336,6 +337,7 @@ fn test_generate_sql_diff() {
         ],
         columns_with_different_definitions: vec![
             ColumnDifference {
+                table_schema: "public".to_string(),
                 table_name: "users".to_string(),
                 column_name: "changed_column".to_string(),
                 first: ColumnInfo::builder(
@@ -356,6 +358,7 @@ fn test_generate_sql_diff() {
                 ),
             },
             ColumnDifference {
+                table_schema: "public".to_string(),
                 table_name: "products".to_string(),
                 column_name: "price".to_string(),
                 first: ColumnInfo::builder(
@@ -0,0 +1,123 @@
+mod common;
+
+#[cfg(test)]
+mod snapshot_tests {
+    use std::fs;
+    use db_diff::{compare_schemas, create_column_info};
+    use db_diff::index::create_index_info;
+    use db_diff::snapshot::{
+        ForeignKey, SNAPSHOT_VERSION, Snapshot, SnapshotComparison, SnapshotMetadata, compare_snapshots,
+        create_snapshot, generate_snapshot_report, load_snapshot,
+    };
+    use super::common::{column, index};
+
+    fn metadata(host: &str, server_version: &str) -> SnapshotMetadata {
+        SnapshotMetadata {
+            flavor: Some("mariadb".to_string()),
+            server_version: Some(server_version.to_string()),
+            captured_at: Some("2024-05-01T12:00:00Z".to_string()),
+            host: Some(host.to_string()),
+            content_hash: None,
+        }
+    }
+
+    #[test]
+    fn test_content_hash_ignores_row_order() {
+        let columns = vec![column("orders", "id", "int(11)"), column("orders", "note", "text")];
+        let mut reversed = columns.clone();
+        reversed.reverse();
+
+        let snapshot = Snapshot::new(columns, vec![index("orders", "idx_note", "note", 1).nullable("NO")]).seal();
+        let hash = snapshot.metadata.content_hash.clone().unwrap();
+        assert!(hash.starts_with("sha256:"));
+        assert_eq!(hash.len(), "sha256:".len() + 64);
+        assert_eq!(Snapshot::new(reversed, vec![index("orders", "idx_note", "note", 1).nullable("NO")]).content_hash(), hash);
+        // Metadata is not part of the content
+        assert_eq!(snapshot.clone().metadata(metadata("prod", "10.11.6-MariaDB")).content_hash(), hash);
+
+        let foreign_key = ForeignKey {
+            table_schema: "shop".to_string(),
+            table_name: "orders".to_string(),
+            constraint_name: "fk_orders_customer".to_string(),
+            column_name: "customer_id".to_string(),
+            ordinal_position: Some(1),
+            referenced_table_schema: "shop".to_string(),
+            referenced_table_name: "customers".to_string(),
+            referenced_column_name: "id".to_string(),
+            update_rule: None,
+            delete_rule: Some("CASCADE".to_string()),
+        };
+        assert_ne!(snapshot.foreign_keys(vec![foreign_key]).content_hash(), hash);
+    }
+
+    #[test]
+    fn test_load_snapshot_checks_version_and_hash() {
+        let json1 = fs::read_to_string("sample_schema1.json").expect("Failed to read sample_schema1.json");
+        let snapshot_json = create_snapshot(&json1, "", r#"{"flavor": "mysql", "host": "staging"}"#);
+
+        let snapshot = load_snapshot(&snapshot_json).unwrap();
+        assert_eq!(snapshot.snapshot_version, SNAPSHOT_VERSION);
+        assert_eq!(snapshot.metadata.host.as_deref(), Some("staging"));
+        assert_eq!(snapshot.columns, create_column_info(&json1).unwrap());
+        // Optional parts are left out of the document when empty
+        assert!(!snapshot_json.contains("foreign_keys"));
+
+        let mut tampered = snapshot.clone();
+        tampered.columns.pop();
+        let error = load_snapshot(&serde_json::to_string(&tampered).unwrap()).unwrap_err();
+        assert!(error.starts_with("Snapshot content hash mismatch"), "{}", error);
+
+        let mut future = snapshot;
+        future.snapshot_version = SNAPSHOT_VERSION + 1;
+        assert!(load_snapshot(&serde_json::to_string(&future).unwrap()).unwrap_err().starts_with("Unsupported snapshot version"));
+
+        // A snapshot without a hash, e.g. written by hand, still loads
+        let manual = r#"{"snapshot_version": 1, "columns": [], "indexes": []}"#;
+        assert!(load_snapshot(manual).unwrap().metadata.content_hash.is_none());
+    }
+
+    #[test]
+    fn test_comparison_apis_accept_snapshots() {
+        let snapshot1 = Snapshot::new(vec![column("orders", "id", "int(11)")], vec![])
+            .metadata(metadata("prod", "10.11.6-MariaDB"))
+            .seal();
+        let snapshot2 = Snapshot::new(
+            vec![column("orders", "id", "int(11)"), column("orders", "note", "text")],
+            vec![index("orders", "idx_note", "note", 1).nullable("NO")],
+        )
+        .metadata(metadata("staging", "11.4.2-MariaDB"))
+        .seal();
+        let json1 = serde_json::to_string(&snapshot1).unwrap();
+        let json2 = serde_json::to_string(&snapshot2).unwrap();
+
+        // The existing entry points read the matching part of a snapshot
+        let result: serde_json::Value = serde_json::from_str(&compare_schemas(&json1, &json2)).unwrap();
+        assert_eq!(result["diff"]["columns_only_in_second"][0]["column_name"], "note");
+        assert_eq!(create_index_info(&json2).unwrap().len(), 1);
+
+        let result: SnapshotComparison = serde_json::from_str(&compare_snapshots(&json1, &json2)).unwrap();
+        assert_eq!(result.first.host.as_deref(), Some("prod"));
+        assert_eq!(result.second.server_version.as_deref(), Some("11.4.2-MariaDB"));
+        assert_eq!(result.comparison.forward_script, "ALTER TABLE `shop`.orders ADD COLUMN note text NOT NULL;\nCREATE INDEX `idx_note` ON `shop`.orders (note);");
+        assert!(result.comparison.verification_errors.is_empty());
+
+        assert!(compare_snapshots("[]", &json2).starts_with("First snapshot: Error parsing snapshot"));
+    }
+
+    #[test]
+    fn test_snapshot_report_shows_metadata() {
+        let snapshot1 = Snapshot::new(vec![column("orders", "id", "int(11)")], vec![])
+            .metadata(metadata("prod", "10.11.6-MariaDB"))
+            .seal();
+        let json1 = serde_json::to_string(&snapshot1).unwrap();
+
+        let markdown = generate_snapshot_report(&json1, &json1, "markdown");
+        assert!(markdown.contains("| Source | Schema 1 | Schema 2 |\n"));
+        assert!(markdown.contains("| Host | `prod` | `prod` |\n"));
+        assert!(markdown.contains(&format!("| Content hash | `{}` |", snapshot1.metadata.content_hash.as_deref().unwrap())));
+        assert!(markdown.contains("No differences found."));
+
+        let html = generate_snapshot_report(&json1, &json1, "html");
+        assert!(html.contains("<tr><td>Server version</td><td>10.11.6-MariaDB</td><td>10.11.6-MariaDB</td></tr>"));
+    }
+}
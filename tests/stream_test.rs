@@ -0,0 +1,76 @@
+mod common;
+
+#[cfg(test)]
+mod stream_tests {
+    use std::fs;
+    use db_diff::{ColumnInfo, build_schema_map, compare_schemas, create_column_info};
+    use db_diff::model::{SchemaModel, verify_script};
+    use db_diff::snapshot::Snapshot;
+    use db_diff::stream::{for_each_column, schema_map_from_json};
+    use super::common::column;
+
+    #[test]
+    fn test_streamed_map_matches_build_schema_map() {
+        let json = fs::read_to_string("sample_schema1.json").expect("Failed to read sample_schema1.json");
+        let columns = create_column_info(&json).unwrap();
+        assert_eq!(schema_map_from_json(&json).unwrap(), build_schema_map(columns.clone()));
+
+        let export = format!(
+            r#"[{{"type":"header","version":"5.2.1"}},{{"type":"table","name":"COLUMNS","database":"information_schema","data":{}}}]"#,
+            json
+        );
+        assert_eq!(schema_map_from_json(&export).unwrap(), build_schema_map(columns.clone()));
+
+        let snapshot = serde_json::to_string(&Snapshot::new(columns.clone(), vec![]).seal()).unwrap();
+        assert_eq!(schema_map_from_json(&snapshot).unwrap(), build_schema_map(columns));
+
+        assert!(schema_map_from_json("not json").unwrap_err().starts_with("Error parsing first JSON"));
+    }
+
+    #[test]
+    fn test_rows_of_a_table_do_not_have_to_be_adjacent() {
+        let columns = vec![column("orders", "id", "int(11)"), column("customers", "id", "int(11)"), column("orders", "total", "int(11)"), column("customers", "name", "int(11)")];
+        let json = serde_json::to_string(&columns).unwrap();
+
+        let mut seen = Vec::new();
+        for_each_column(&json, |column| seen.push(column.column_name)).unwrap();
+        assert_eq!(seen, vec!["id", "id", "total", "name"]);
+
+        let map = schema_map_from_json(&json).unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["`shop`.orders"].keys().collect::<Vec<&String>>(), vec!["id", "total"]);
+        assert_eq!(map["`shop`.customers"].keys().collect::<Vec<&String>>(), vec!["id", "name"]);
+    }
+
+    #[test]
+    fn test_large_export_compares() {
+        let columns1: Vec<ColumnInfo> = (0..20_000).map(|i| column(&format!("table_{}", i / 20), &format!("column_{}", i), "int(11)")).collect();
+        let mut columns2 = columns1.clone();
+        columns2[12_345].column_type = "bigint(20)".to_string();
+        columns2.pop();
+
+        let result: serde_json::Value = serde_json::from_str(&compare_schemas(
+            &serde_json::to_string(&columns1).unwrap(),
+            &serde_json::to_string(&columns2).unwrap(),
+        ))
+        .unwrap();
+        assert_eq!(result["diff"]["columns_with_different_definitions"][0]["column_name"], "column_12345");
+        assert_eq!(result["diff"]["columns_only_in_first"][0]["column_name"], "column_19999");
+        assert!(result["verification_errors"].as_array().is_none_or(|errors| errors.is_empty()));
+    }
+
+    #[test]
+    fn test_verification_still_sees_untouched_tables() {
+        // Only the tables a script touches are copied, a difference elsewhere must still be reported
+        let start = SchemaModel::from_columns(&[column("orders", "id", "int(11)"), column("customers", "id", "int(11)")]);
+        let target = SchemaModel::from_columns(&[column("orders", "id", "int(11)"), column("orders", "total", "int(11)"), column("customers", "name", "int(11)")]);
+        let errors = verify_script("forward", &start, &target, "ALTER TABLE `shop`.orders ADD COLUMN total int(11) NOT NULL;");
+        assert_eq!(
+            errors,
+            vec![
+                "forward: Column `shop`.customers.id is only in the first schema",
+                "forward: Column `shop`.customers.name is only in the second schema",
+            ]
+        );
+    }
+}
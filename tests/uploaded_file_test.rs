@@ -26,7 +26,7 @@ mod tests {
         // Test that the function can be called without panicking
         // In a real browser environment, you would verify the result through
         // console output or by using a callback mechanism
-        let _ = process_uploaded_file(file);
+        let _ = process_uploaded_file(file, None);
 
         // If we reach here without panic, the test passes
         assert!(true);